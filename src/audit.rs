@@ -0,0 +1,72 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// This session's log file, or `None` when auditing is off; unset until `init`
+static SESSION_LOG: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// `$XDG_STATE_HOME/sozin`, falling back to `~/.local/state/sozin`
+fn log_dir(xdg_state_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+    match xdg_state_home.filter(|d| !d.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir).join("sozin")),
+        None => home.map(|h| PathBuf::from(h).join(".local/state/sozin")),
+    }
+}
+
+/// Pick this session's log file, or turn auditing off
+///
+/// The file is only created once something is written to it.
+pub fn init(enabled: bool) {
+    let path = if enabled {
+        log_dir(std::env::var("XDG_STATE_HOME").ok(), std::env::var("HOME").ok()).map(|dir| {
+            dir.join(format!("session-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S")))
+        })
+    } else {
+        None
+    };
+    let _ = SESSION_LOG.set(path);
+}
+
+/// One log line: timestamp, action, then free-form details
+fn format_line(at: &chrono::DateTime<chrono::Local>, action: &str, details: &str) -> String {
+    format!("{} {:<12} {}", at.format("%Y-%m-%d %H:%M:%S%:z"), action, details)
+}
+
+/// Append a line describing a change sozin made to the system
+///
+/// Does nothing before `init` (as in tests) or with `--no-audit`. Write errors are
+/// ignored: a missing log line must never stop the change it describes.
+pub fn audit(action: &str, details: &str) {
+    let Some(Some(path)) = SESSION_LOG.get() else {
+        return;
+    };
+    let line = format_line(&chrono::Local::now(), action, details);
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn log_dir_prefers_xdg_state_home() {
+        let dir = |xdg: Option<&str>, home: Option<&str>| log_dir(xdg.map(String::from), home.map(String::from));
+        assert_eq!(dir(Some("/state"), Some("/root")), Some(PathBuf::from("/state/sozin")));
+        assert_eq!(dir(Some(""), Some("/root")), Some(PathBuf::from("/root/.local/state/sozin")));
+        assert_eq!(dir(None, None), None);
+    }
+
+    #[test]
+    fn lines_are_timestamped_and_aligned() {
+        let at = chrono::Local.with_ymd_and_hms(2025, 12, 7, 1, 2, 3).unwrap();
+        let line = format_line(&at, "mac", "wlan0 00:11:22:33:44:55 -> 02:aa:bb:cc:dd:ee");
+        assert!(line.starts_with("2025-12-07 01:02:03"));
+        assert!(line.ends_with(" mac          wlan0 00:11:22:33:44:55 -> 02:aa:bb:cc:dd:ee"));
+    }
+}
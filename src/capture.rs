@@ -0,0 +1,358 @@
+use anyhow::{anyhow, Result};
+use std::io::Write;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Longest frame kept, also the snapshot length in the pcap header
+const SNAPLEN: usize = 65535;
+
+/// How long a capture waits for a frame before checking whether it was asked to stop
+const POLL_MS: u64 = 200;
+
+/// pcap link types for the ARPHRD types sozin can capture on
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_IEEE802_11: u32 = 105;
+const LINKTYPE_IEEE802_11_RADIOTAP: u32 = 127;
+
+/// Raw AF_PACKET socket bound to one interface, reading whole link-layer frames
+///
+/// Monitor-mode interfaces deliver 802.11 frames with a radiotap header in front.
+/// Opening one needs root (CAP_NET_RAW).
+pub struct PacketSocket {
+    fd: OwnedFd,
+    interface: String,
+}
+
+impl PacketSocket {
+    /// Open a socket on `interface` whose `recv` gives up after `poll` without a frame
+    pub fn open(interface: &str, poll: Duration) -> Result<Self> {
+        let name = std::ffi::CString::new(interface)?;
+        // SAFETY: `name` is a valid NUL-terminated string
+        let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if index == 0 {
+            return Err(anyhow!("Interface {} not found", interface));
+        }
+
+        let protocol = (libc::ETH_P_ALL as u16).to_be();
+        // SAFETY: plain socket(2) call; the descriptor is owned by `fd` right after
+        let raw = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW, i32::from(protocol)) };
+        if raw < 0 {
+            return Err(anyhow!("Failed to open packet socket: {}", std::io::Error::last_os_error()));
+        }
+        // SAFETY: `raw` is a fresh descriptor nothing else owns
+        let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+
+        // SAFETY: sockaddr_ll is plain data, valid when zeroed
+        let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = protocol;
+        addr.sll_ifindex = index as i32;
+        // SAFETY: `addr` is a sockaddr_ll of the length passed
+        let bound = unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                (&addr as *const libc::sockaddr_ll).cast(),
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if bound < 0 {
+            return Err(anyhow!("Failed to bind packet socket to {}: {}", interface, std::io::Error::last_os_error()));
+        }
+
+        let timeout = libc::timeval {
+            tv_sec: poll.as_secs() as libc::time_t,
+            tv_usec: poll.subsec_micros() as libc::suseconds_t,
+        };
+        // SAFETY: `timeout` is a timeval of the length passed
+        let set = unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                (&timeout as *const libc::timeval).cast(),
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        // Without the timeout a quiet interface would block `recv`, and a capture could never stop
+        if set < 0 {
+            return Err(anyhow!("Failed to set receive timeout on {}: {}", interface, std::io::Error::last_os_error()));
+        }
+
+        Ok(Self { fd, interface: interface.to_string() })
+    }
+
+    /// The next frame, cut to `buf`'s length, and its length on the wire; `None` if nothing arrived in time
+    pub fn recv<'a>(&self, buf: &'a mut [u8]) -> Result<Option<(&'a [u8], usize)>> {
+        // SAFETY: `buf` is writable for its whole length. With MSG_TRUNC the return
+        // value is the full frame length even when only `buf.len()` bytes were copied.
+        let len = unsafe { libc::recv(self.fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), libc::MSG_TRUNC) };
+        if len < 0 {
+            let err = std::io::Error::last_os_error();
+            return match err.kind() {
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted => Ok(None),
+                _ => Err(anyhow!("Failed to read frames on {}: {}", self.interface, err)),
+            };
+        }
+        let len = len as usize;
+        Ok(Some((&buf[..len.min(buf.len())], len)))
+    }
+}
+
+/// 802.11 frame types, as written in a `--filter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    Management,
+    Control,
+    Data,
+}
+
+/// Which 802.11 frames a capture keeps, in tcpdump's words: "type mgt" or "subtype beacon"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFilter {
+    Type(FrameType),
+    /// A management frame subtype
+    Subtype(u8),
+}
+
+/// Management subtypes `subtype` accepts, with their numbers
+const MGMT_SUBTYPES: [(&str, u8); 12] = [
+    ("assoc-req", 0),
+    ("assoc-resp", 1),
+    ("reassoc-req", 2),
+    ("reassoc-resp", 3),
+    ("probe-req", 4),
+    ("probe-resp", 5),
+    ("beacon", 8),
+    ("atim", 9),
+    ("disassoc", 10),
+    ("auth", 11),
+    ("deauth", 12),
+    ("action", 13),
+];
+
+impl std::str::FromStr for FrameFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<String> = s.split_whitespace().map(str::to_lowercase).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match words[..] {
+            ["type", "mgt"] => Ok(FrameFilter::Type(FrameType::Management)),
+            ["type", "ctl"] => Ok(FrameFilter::Type(FrameType::Control)),
+            ["type", "data"] => Ok(FrameFilter::Type(FrameType::Data)),
+            ["subtype", name] => MGMT_SUBTYPES
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|&(_, subtype)| FrameFilter::Subtype(subtype))
+                .ok_or_else(|| {
+                    let names: Vec<&str> = MGMT_SUBTYPES.iter().map(|(n, _)| *n).collect();
+                    format!("Unknown subtype {}; expected one of {}", name, names.join(", "))
+                }),
+            _ => Err(format!("Unknown filter \"{}\"; expected \"type mgt|ctl|data\" or \"subtype <name>\"", s)),
+        }
+    }
+}
+
+impl FrameFilter {
+    /// Whether a frame with its radiotap header passes; truncated frames never do
+    pub fn matches(self, frame: &[u8]) -> bool {
+        if frame.len() < 4 {
+            return false;
+        }
+        let header_len = usize::from(u16::from_le_bytes([frame[2], frame[3]]));
+        let Some(&control) = frame.get(header_len) else {
+            return false;
+        };
+        let (kind, subtype) = ((control >> 2) & 0x3, control >> 4);
+        match self {
+            FrameFilter::Type(FrameType::Management) => kind == 0,
+            FrameFilter::Type(FrameType::Control) => kind == 1,
+            FrameFilter::Type(FrameType::Data) => kind == 2,
+            FrameFilter::Subtype(wanted) => kind == 0 && subtype == wanted,
+        }
+    }
+}
+
+/// pcap link type for an interface's ARPHRD type from `/sys/class/net/<iface>/type`
+fn link_type(arphrd: u32) -> Option<u32> {
+    match arphrd {
+        1 => Some(LINKTYPE_ETHERNET),
+        801 => Some(LINKTYPE_IEEE802_11),
+        803 => Some(LINKTYPE_IEEE802_11_RADIOTAP),
+        _ => None,
+    }
+}
+
+/// Writes frames in the classic pcap format (microsecond timestamps)
+struct PcapWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> PcapWriter<W> {
+    fn new(mut out: W, link_type: u32) -> std::io::Result<Self> {
+        out.write_all(&0xa1b2_c3d4u32.to_ne_bytes())?;
+        out.write_all(&2u16.to_ne_bytes())?;
+        out.write_all(&4u16.to_ne_bytes())?;
+        // Timezone offset and timestamp accuracy, always 0
+        out.write_all(&[0; 8])?;
+        out.write_all(&(SNAPLEN as u32).to_ne_bytes())?;
+        out.write_all(&link_type.to_ne_bytes())?;
+        Ok(Self { out })
+    }
+
+    /// Write the captured part of a frame that was `orig_len` bytes long on the wire
+    fn write_frame(&mut self, at: SystemTime, frame: &[u8], orig_len: usize) -> std::io::Result<()> {
+        let since_epoch = at.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+        self.out.write_all(&(since_epoch.as_secs() as u32).to_ne_bytes())?;
+        self.out.write_all(&since_epoch.subsec_micros().to_ne_bytes())?;
+        self.out.write_all(&(frame.len() as u32).to_ne_bytes())?;
+        self.out.write_all(&(orig_len as u32).to_ne_bytes())?;
+        self.out.write_all(frame)
+    }
+
+    fn finish(mut self) -> std::io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Totals reported when a capture stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureSummary {
+    /// Frames written to the file
+    pub frames: u64,
+    /// Frames the filter left out
+    pub filtered: u64,
+    /// Size of the capture file
+    pub bytes: u64,
+}
+
+/// A capture writing to a pcap file from its own thread until `stop` is called
+pub struct CaptureSession {
+    pub interface: String,
+    pub path: PathBuf,
+    pub started: Instant,
+    stop: Arc<AtomicBool>,
+    worker: std::thread::JoinHandle<Result<CaptureSummary>>,
+}
+
+impl CaptureSession {
+    /// Start writing frames from `interface` to `path`, keeping only those `filter` passes
+    ///
+    /// Filters look at 802.11 headers, so they need a monitor-mode interface.
+    pub fn start(interface: &str, path: &Path, filter: Option<FrameFilter>) -> Result<Self> {
+        let arphrd: u32 = std::fs::read_to_string(format!("/sys/class/net/{}/type", interface))
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .ok_or_else(|| anyhow!("Interface {} not found", interface))?;
+        let link_type = link_type(arphrd)
+            .ok_or_else(|| anyhow!("Can't capture on {}: unsupported link type {}", interface, arphrd))?;
+        if filter.is_some() && link_type != LINKTYPE_IEEE802_11_RADIOTAP {
+            return Err(anyhow!("Frame filters need a monitor-mode interface; {} isn't one", interface));
+        }
+
+        let socket = PacketSocket::open(interface, Duration::from_millis(POLL_MS))?;
+        let file = std::fs::File::create(path).map_err(|e| anyhow!("Failed to create {}: {}", path.display(), e))?;
+        let writer = PcapWriter::new(std::io::BufWriter::new(file), link_type)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let out_path = path.to_path_buf();
+        let worker = std::thread::spawn(move || record(&socket, writer, filter, &stop_flag, &out_path));
+
+        Ok(Self {
+            interface: interface.to_string(),
+            path: path.to_path_buf(),
+            started: Instant::now(),
+            stop,
+            worker,
+        })
+    }
+
+    /// Stop capturing, flush the file and report what was written
+    pub fn stop(self) -> Result<CaptureSummary> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.worker.join().map_err(|_| anyhow!("Capture on {} crashed", self.interface))?
+    }
+}
+
+/// Copy frames from `socket` to `writer` until `stop` is set
+fn record<W: Write>(
+    socket: &PacketSocket,
+    mut writer: PcapWriter<W>,
+    filter: Option<FrameFilter>,
+    stop: &AtomicBool,
+    path: &Path,
+) -> Result<CaptureSummary> {
+    let write_err = |e: std::io::Error| anyhow!("Failed to write {}: {}", path.display(), e);
+    let mut summary = CaptureSummary { frames: 0, filtered: 0, bytes: 0 };
+    let mut buf = vec![0u8; SNAPLEN];
+    while !stop.load(Ordering::Relaxed) {
+        let Some((frame, orig_len)) = socket.recv(&mut buf)? else {
+            continue;
+        };
+        if filter.is_some_and(|f| !f.matches(frame)) {
+            summary.filtered += 1;
+            continue;
+        }
+        writer.write_frame(SystemTime::now(), frame, orig_len).map_err(write_err)?;
+        summary.frames += 1;
+    }
+    writer.finish().map_err(write_err)?;
+    summary.bytes = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_read_the_frame_control_after_radiotap() {
+        // 8-byte radiotap header with no fields, then the frame control byte
+        let frame = |control: u8| vec![0, 0, 8, 0, 0, 0, 0, 0, control, 0];
+        let beacon = frame(0x80);
+        let deauth = frame(0xc0);
+        let ack = frame(0xd4);
+        let qos_data = frame(0x88);
+
+        let mgt: FrameFilter = "type mgt".parse().unwrap();
+        assert!(mgt.matches(&beacon) && mgt.matches(&deauth));
+        assert!(!mgt.matches(&ack) && !mgt.matches(&qos_data));
+        assert!("type ctl".parse::<FrameFilter>().unwrap().matches(&ack));
+        assert!("TYPE DATA".parse::<FrameFilter>().unwrap().matches(&qos_data));
+
+        let beacons: FrameFilter = "subtype beacon".parse().unwrap();
+        assert!(beacons.matches(&beacon) && !beacons.matches(&deauth));
+        // Same subtype bits, but a data frame
+        assert!(!beacons.matches(&frame(0x88)));
+        assert!(!mgt.matches(&beacon[..8]));
+
+        assert!("subtype nope".parse::<FrameFilter>().is_err());
+        assert!("port 80".parse::<FrameFilter>().is_err());
+    }
+
+    #[test]
+    fn pcap_file_has_header_then_timestamped_records() {
+        let mut writer = PcapWriter::new(Vec::new(), link_type(803).unwrap()).unwrap();
+        let at = SystemTime::UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456);
+        writer.write_frame(at, &[0xaa, 0xbb, 0xcc], 3).unwrap();
+        // Cut to the snapshot length, but the record keeps the real length
+        writer.write_frame(at, &[0xdd], 70_000).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let word = |at: usize| u32::from_ne_bytes(bytes[at..at + 4].try_into().unwrap());
+        assert_eq!(bytes.len(), 24 + 16 + 3 + 16 + 1);
+        assert_eq!(word(0), 0xa1b2_c3d4);
+        assert_eq!((word(16), word(20)), (SNAPLEN as u32, LINKTYPE_IEEE802_11_RADIOTAP));
+        assert_eq!((word(24), word(28)), (1_700_000_000, 123_456));
+        assert_eq!((word(32), word(36)), (3, 3));
+        assert_eq!(&bytes[40..43], [0xaa, 0xbb, 0xcc]);
+        assert_eq!((word(51), word(55)), (1, 70_000));
+        assert_eq!(link_type(772), None);
+    }
+}
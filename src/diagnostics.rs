@@ -0,0 +1,170 @@
+use crate::network::{ConnectionInfo, InterfaceType, NetworkInterface, NetworkManager, WirelessMode};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Environment snapshot for bug reports, produced by `sozin diagnostics`
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsReport {
+    pub sozin_version: &'static str,
+    pub kernel: Option<String>,
+    /// First line of each tool's version output, `None` when not installed
+    pub tools: BTreeMap<String, Option<String>>,
+    /// `iw reg get` output
+    pub reg_domain: Option<String>,
+    pub interfaces: Vec<InterfaceDiagnostics>,
+    pub phys: Vec<PhyDiagnostics>,
+    /// True when MAC addresses, IP addresses, descriptions and SSIDs have been masked or dropped
+    pub redacted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InterfaceDiagnostics {
+    #[serde(flatten)]
+    pub interface: NetworkInterface,
+    pub wireless_mode: Option<WirelessMode>,
+    pub connection: Option<ConnectionInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhyDiagnostics {
+    pub name: String,
+    pub supported_modes: Vec<String>,
+    pub channels: Vec<u32>,
+}
+
+impl DiagnosticsReport {
+    /// Gather the snapshot; individual probes that fail are left empty
+    pub fn collect() -> Result<Self> {
+        let tools = NetworkManager::tool_versions().into_iter().collect();
+
+        let interfaces = NetworkManager::get_interfaces()?
+            .into_iter()
+            .map(|interface| {
+                let wireless = interface.interface_type == InterfaceType::Wireless;
+                InterfaceDiagnostics {
+                    wireless_mode: if wireless { NetworkManager::get_wireless_mode(&interface.name).ok() } else { None },
+                    connection: if wireless { NetworkManager::get_connection_info(&interface.name) } else { None },
+                    interface,
+                }
+            })
+            .collect();
+
+        let phys = NetworkManager::list_phys()
+            .into_iter()
+            .map(|name| {
+                let info = NetworkManager::get_phy_info(&name).unwrap_or_default();
+                PhyDiagnostics {
+                    supported_modes: NetworkManager::parse_supported_modes(&info),
+                    channels: NetworkManager::parse_supported_channels(&info),
+                    name,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            sozin_version: env!("CARGO_PKG_VERSION"),
+            kernel: std::fs::read_to_string("/proc/sys/kernel/osrelease")
+                .ok()
+                .map(|s| s.trim().to_string()),
+            tools,
+            reg_domain: Command::new("iw")
+                .args(["reg", "get"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string()),
+            interfaces,
+            phys,
+            redacted: false,
+        })
+    }
+
+    /// Mask MAC addresses (keeping the vendor OUI), drop IP addresses and descriptions, and replace SSIDs with a
+    /// stable hash
+    pub fn redact(&mut self) {
+        for diag in &mut self.interfaces {
+            diag.interface.mac_address = diag.interface.mac_address.as_deref().map(mask_mac);
+            diag.interface.permanent_mac = diag.interface.permanent_mac.as_deref().map(mask_mac);
+            diag.interface.ip_address = None;
+            // User notes and udev model strings can name people or places
+            diag.interface.description = None;
+            if let Some(conn) = &mut diag.connection {
+                conn.bssid = mask_mac(&conn.bssid);
+                conn.ssid = conn.ssid.as_deref().map(hash_ssid);
+            }
+        }
+        self.redacted = true;
+    }
+}
+
+/// "00:11:22:33:44:55" -> "00:11:22:xx:xx:xx"
+fn mask_mac(mac: &str) -> String {
+    let octets: Vec<&str> = mac.split(':').collect();
+    if octets.len() != 6 {
+        return "xx:xx:xx:xx:xx:xx".to_string();
+    }
+    format!("{}:xx:xx:xx", octets[..3].join(":"))
+}
+
+/// Same SSID always maps to the same token, so reports stay comparable
+///
+/// 32-bit FNV-1a, which unlike `DefaultHasher` gives the same result on every
+/// Rust release and machine.
+fn hash_ssid(ssid: &str) -> String {
+    let hash = ssid.bytes().fold(0x811c_9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193));
+    format!("ssid-{:08x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redaction_masks_macs_and_hashes_ssids() {
+        assert_eq!(mask_mac("00:11:22:33:44:55"), "00:11:22:xx:xx:xx");
+        assert_eq!(mask_mac("garbage"), "xx:xx:xx:xx:xx:xx");
+
+        let hashed = hash_ssid("HomeNet");
+        assert!(hashed.starts_with("ssid-") && !hashed.contains("HomeNet"));
+        assert_eq!(hashed, hash_ssid("HomeNet"));
+        assert_ne!(hashed, hash_ssid("HomeNet-5G"));
+        // FNV-1a test vectors, so the tokens never change between builds
+        assert_eq!(hash_ssid(""), "ssid-811c9dc5");
+        assert_eq!(hash_ssid("a"), "ssid-e40c292c");
+    }
+
+    #[test]
+    fn redact_drops_ip_addresses() {
+        let interface = NetworkInterface {
+            name: "wlan0".to_string(),
+            parent: None,
+            mac_address: Some("02:11:22:33:44:55".to_string()),
+            ip_address: Some("192.168.1.23/24".to_string()),
+            state: crate::network::InterfaceState::Up,
+            interface_type: InterfaceType::Wireless,
+            driver: None,
+            mtu: None,
+            link_speed: None,
+            permanent_mac: Some("00:c0:ca:12:34:56".to_string()),
+            description: Some("Alice's laptop".to_string()),
+        };
+        let mut report = DiagnosticsReport {
+            sozin_version: "test",
+            kernel: None,
+            tools: BTreeMap::new(),
+            reg_domain: None,
+            interfaces: vec![InterfaceDiagnostics { interface, wireless_mode: None, connection: None }],
+            phys: Vec::new(),
+            redacted: false,
+        };
+        report.redact();
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("192.168.1.23"));
+        assert!(!json.contains("Alice"));
+        assert!(json.contains("\"permanent_mac\":\"00:c0:ca:xx:xx:xx\""));
+        assert!(report.redacted);
+    }
+}
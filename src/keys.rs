@@ -0,0 +1,248 @@
+use crossterm::event::KeyCode;
+use crate::store::config_dir;
+use std::collections::{BTreeMap, HashMap};
+
+/// TUI actions that can be moved to another key in `keys.toml`
+///
+/// Arrows, Tab, Enter, Esc, PgUp/PgDn, Home/End, the tab numbers, F1 and Ctrl+F
+/// always keep their meaning and can't be rebound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    Quit,
+    Help,
+    MoveDown,
+    MoveUp,
+    ToggleMonitor,
+    InterfaceUp,
+    InterfaceDown,
+    Rename,
+    SpoofMac,
+    Refresh,
+    AutoRefresh,
+    Favorite,
+    Capture,
+    Disconnect,
+    ResetInterface,
+    ReloadDriver,
+    Scan,
+    CycleFilter,
+    OpenOnly,
+    Watch,
+    Connect,
+    NewNetworkAlerts,
+    SampleActivity,
+    SavedNetworks,
+    RestartNetworkManager,
+    EditNote,
+    SetChannel,
+    LiveScan,
+    LiveScanSlower,
+    LiveScanFaster,
+}
+
+impl Action {
+    /// Changes interfaces or system services, so it can't work without root
+    pub fn requires_root(self) -> bool {
+        matches!(
+            self,
+            Action::ToggleMonitor
+                | Action::InterfaceUp
+                | Action::InterfaceDown
+                | Action::Rename
+                | Action::SpoofMac
+                | Action::Capture
+                | Action::Disconnect
+                | Action::ResetInterface
+                | Action::ReloadDriver
+                | Action::SampleActivity
+                | Action::RestartNetworkManager
+                | Action::SetChannel
+                | Action::LiveScan
+        )
+    }
+}
+
+/// Name used in `keys.toml` and default key of every action
+const ACTIONS: [(Action, &str, char); 30] = [
+    (Action::Quit, "Quit", 'q'),
+    (Action::Help, "Help", '?'),
+    (Action::MoveDown, "MoveDown", 'j'),
+    (Action::MoveUp, "MoveUp", 'k'),
+    (Action::ToggleMonitor, "ToggleMonitor", 'm'),
+    (Action::InterfaceUp, "InterfaceUp", 'u'),
+    (Action::InterfaceDown, "InterfaceDown", 'd'),
+    (Action::Rename, "Rename", 'R'),
+    (Action::SpoofMac, "SpoofMac", 'M'),
+    (Action::Refresh, "Refresh", 'r'),
+    (Action::AutoRefresh, "AutoRefresh", 'a'),
+    (Action::Favorite, "Favorite", '*'),
+    (Action::Capture, "Capture", 'p'),
+    (Action::Disconnect, "Disconnect", 'D'),
+    (Action::ResetInterface, "ResetInterface", 'X'),
+    (Action::ReloadDriver, "ReloadDriver", 'L'),
+    (Action::Scan, "Scan", 's'),
+    (Action::CycleFilter, "CycleFilter", 'f'),
+    (Action::OpenOnly, "OpenOnly", 'o'),
+    (Action::Watch, "Watch", 'w'),
+    (Action::Connect, "Connect", 'c'),
+    (Action::NewNetworkAlerts, "NewNetworkAlerts", 'b'),
+    (Action::SampleActivity, "SampleActivity", 'A'),
+    (Action::SavedNetworks, "SavedNetworks", 'C'),
+    (Action::RestartNetworkManager, "RestartNetworkManager", 'n'),
+    (Action::EditNote, "EditNote", 'e'),
+    (Action::SetChannel, "SetChannel", 'h'),
+    (Action::LiveScan, "LiveScan", 'S'),
+    (Action::LiveScanSlower, "LiveScanSlower", '+'),
+    (Action::LiveScanFaster, "LiveScanFaster", '-'),
+];
+
+/// Key for a `keys.toml` value: a single character, "Space", "Delete", "Insert" or "F2" to "F12"
+fn parse_key(value: &str) -> Option<KeyCode> {
+    let mut chars = value.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        // The digits pick tabs
+        return (!c.is_ascii_digit() && !c.is_whitespace()).then_some(KeyCode::Char(c));
+    }
+    match value {
+        "Space" => Some(KeyCode::Char(' ')),
+        "Delete" => Some(KeyCode::Delete),
+        "Insert" => Some(KeyCode::Insert),
+        // F1 always opens the help
+        _ => value
+            .strip_prefix('F')
+            .and_then(|n| n.parse().ok())
+            .filter(|n| (2..=12).contains(n))
+            .map(KeyCode::F),
+    }
+}
+
+/// Which key triggers each `Action`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    keys: HashMap<Action, KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: ACTIONS.iter().map(|&(action, _, key)| (action, KeyCode::Char(key))).collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Bindings from `keys.toml` in the config dir, plus a warning for everything that was ignored
+    ///
+    /// A missing file means the defaults, without a warning.
+    pub fn load() -> (Self, Vec<String>) {
+        let path = config_dir().map(|dir| dir.join("keys.toml"));
+        match path.and_then(|p| std::fs::read_to_string(p).ok()) {
+            Some(contents) => Self::from_toml(&contents),
+            None => (Self::default(), Vec::new()),
+        }
+    }
+
+    /// Apply `ActionName = "key"` lines over the defaults
+    ///
+    /// Unknown actions and unusable keys are skipped one by one. If the result
+    /// gives one key to two actions, all of the file is dropped for the defaults.
+    fn from_toml(contents: &str) -> (Self, Vec<String>) {
+        let table: BTreeMap<String, String> = match toml::from_str(contents) {
+            Ok(table) => table,
+            Err(e) => return (Self::default(), vec![format!("keys.toml ignored: {}", e.message())]),
+        };
+
+        let mut bindings = Self::default();
+        let mut warnings = Vec::new();
+        for (name, value) in &table {
+            let Some(&(action, _, _)) = ACTIONS.iter().find(|(_, n, _)| n == name) else {
+                warnings.push(format!("keys.toml: unknown action {}", name));
+                continue;
+            };
+            match parse_key(value) {
+                Some(key) => {
+                    bindings.keys.insert(action, key);
+                }
+                None => warnings.push(format!("keys.toml: can't bind {} to \"{}\"", name, value)),
+            }
+        }
+
+        let mut by_key: HashMap<KeyCode, Vec<Action>> = HashMap::new();
+        for (&action, &key) in &bindings.keys {
+            by_key.entry(key).or_default().push(action);
+        }
+        let mut clashes: Vec<String> = by_key
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|(key, mut actions)| {
+                actions.sort();
+                let names: Vec<&str> = actions.iter().map(|a| Self::name(*a)).collect();
+                format!("{} ({})", key_label(key), names.join(", "))
+            })
+            .collect();
+        if !clashes.is_empty() {
+            clashes.sort();
+            warnings.push(format!("keys.toml ignored, keys bound twice: {}", clashes.join("; ")));
+            return (Self::default(), warnings);
+        }
+        (bindings, warnings)
+    }
+
+    /// The action `key` is bound to
+    pub fn action_for(&self, key: KeyCode) -> Option<Action> {
+        self.keys.iter().find(|(_, k)| **k == key).map(|(action, _)| *action)
+    }
+
+    /// The key bound to `action`, as shown in the help
+    pub fn label(&self, action: Action) -> String {
+        self.keys.get(&action).map_or_else(String::new, |key| key_label(*key))
+    }
+
+    fn name(action: Action) -> &'static str {
+        ACTIONS.iter().find(|(a, _, _)| *a == action).map_or("?", |(_, name, _)| name)
+    }
+}
+
+/// How a key is written in the help and in `keys.toml`
+fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_built_in_keys() {
+        let keys = KeyBindings::default();
+        assert_eq!(keys.action_for(KeyCode::Char('m')), Some(Action::ToggleMonitor));
+        assert_eq!(keys.action_for(KeyCode::Char('M')), Some(Action::SpoofMac));
+        assert_eq!(keys.action_for(KeyCode::Char('z')), None);
+        assert_eq!(keys.label(Action::Scan), "s");
+    }
+
+    #[test]
+    fn file_remaps_and_bad_entries_fall_back() {
+        let (keys, warnings) = KeyBindings::from_toml("Scan = \"W\"\nToggleMonitor = \"F5\"\nBogus = \"x\"\nQuit = \"3\"\n");
+        assert_eq!(keys.action_for(KeyCode::Char('W')), Some(Action::Scan));
+        assert_eq!(keys.action_for(KeyCode::Char('s')), None);
+        assert_eq!(keys.action_for(KeyCode::F(5)), Some(Action::ToggleMonitor));
+        // Digits are reserved for tabs, so Quit keeps q
+        assert_eq!(keys.label(Action::Quit), "q");
+        assert_eq!(warnings.len(), 2);
+
+        // Moving Scan onto `m` without moving ToggleMonitor is ambiguous
+        let (keys, warnings) = KeyBindings::from_toml("Scan = \"m\"\n");
+        assert_eq!(keys, KeyBindings::default());
+        assert_eq!(warnings, ["keys.toml ignored, keys bound twice: m (ToggleMonitor, Scan)"]);
+
+        let (keys, warnings) = KeyBindings::from_toml("Scan = [\n");
+        assert_eq!(keys, KeyBindings::default());
+        assert_eq!(warnings.len(), 1);
+    }
+}
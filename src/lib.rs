@@ -0,0 +1,20 @@
+//! Network interface management and WiFi scanning, as used by the `sozin` CLI and TUI
+//!
+//! [`network::NetworkManager`] lists and changes interfaces, [`scanner::WifiScanner`]
+//! scans for access points, and the data types they return serialize with serde.
+//! Most of the work is done by running `ip`, `iw` and `nmcli`; the `netlink` feature
+//! moves link up/down, mode and channel changes and scans onto netlink, but the tools
+//! are still needed for the rest. Changing anything needs root, the same as on the
+//! command line.
+
+pub mod audit;
+pub mod capture;
+pub mod diagnostics;
+pub mod netlink;
+pub mod network;
+pub mod oui;
+pub mod output;
+pub mod runner;
+pub mod scanner;
+pub mod store;
+pub mod survey;
@@ -1,11 +1,14 @@
 mod banner;
-mod network;
-mod scanner;
+mod keys;
+mod theme;
 mod ui;
 
+use sozin::{audit, capture, diagnostics, network, oui, output, scanner, store, survey};
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use colored::*;
+use output::OutputFormat;
 
 #[derive(Parser)]
 #[command(name = "sozin")]
@@ -13,9 +16,67 @@ use colored::*;
 #[command(version = "2.1.0")]
 #[command(about = "Professional Network Interface Manager - WiFi scanning, monitor mode, and network discovery")]
 #[command(long_about = None)]
+#[command(after_help = "Exit codes:
+  0  Success
+  1  Any other error
+  2  The command needs root
+  3  Interface not found
+  4  A required tool (iw, ip, nmcli, hostapd, ...) is not installed
+  5  Operation not permitted by the kernel or a tool")]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Color palette for the TUI
+    #[arg(long, global = true, value_enum, env = "SOZIN_THEME", default_value_t = theme::ThemeName::Default)]
+    theme: theme::ThemeName,
+
+    /// Output format for list, scan and info
+    ///
+    /// JSON output (including diagnostics and --stream lines) is wrapped in a versioned
+    /// envelope: {"schema_version": 1, "tool": "sozin", "data": ...}. schema_version is
+    /// bumped whenever a field is renamed, removed or changes meaning.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Don't record changes to the session audit log
+    #[arg(long, global = true)]
+    no_audit: bool,
+
+    /// Show at most N networks: the N strongest for `scan`, N per page in the TUI
+    #[arg(long, global = true, env = "SOZIN_LIMIT", value_name = "N")]
+    limit: Option<std::num::NonZeroUsize>,
+
+    /// Full scale of the TUI throughput gauges in Mbit/s; by default they scale to the peak seen
+    #[arg(long, global = true, env = "SOZIN_GAUGE_MAX", value_name = "MBPS")]
+    gauge_max: Option<std::num::NonZeroU64>,
+
+    /// Use ASCII labels ([W], [E], ...) instead of emoji for interface types
+    #[arg(long, global = true, env = "SOZIN_ASCII")]
+    ascii: bool,
+
+    /// Remember network names across runs and show them for hidden networks
+    ///
+    /// Names are kept per BSSID in known-networks.json under $XDG_DATA_HOME/sozin
+    /// (or ~/.local/share/sozin).
+    #[arg(long, global = true, env = "SOZIN_REMEMBER_NETWORKS")]
+    remember_networks: bool,
+
+    /// Quit the TUI without offering to undo its monitor mode and MAC changes
+    #[arg(long, global = true, env = "SOZIN_NO_RESTORE_ON_EXIT")]
+    no_restore_on_exit: bool,
+
+    /// Signals above this many dBm are shown as good
+    #[arg(long, global = true, env = "SOZIN_SIGNAL_GOOD", default_value_t = -50, allow_negative_numbers = true)]
+    signal_good: i32,
+
+    /// Signals above this many dBm (and not good) are shown as fair; the rest as weak
+    #[arg(long, global = true, env = "SOZIN_SIGNAL_FAIR", default_value_t = -70, allow_negative_numbers = true)]
+    signal_fair: i32,
 }
 
 #[derive(Subcommand)]
@@ -28,34 +89,195 @@ enum Commands {
         /// Show only wireless interfaces
         #[arg(short, long)]
         wireless: bool,
+
+        /// Group interfaces by type, sorted by name within each group
+        #[arg(short, long)]
+        group: bool,
         
-        /// Output as JSON
+        /// Output as JSON (same as --format json)
         #[arg(short, long)]
         json: bool,
     },
     
-    /// Enable monitor mode on interface
-    Monitor {
+    /// Show full details for one interface
+    Info {
         /// Interface name
         #[arg(short, long)]
         interface: String,
+
+        /// Output as JSON (same as --format json)
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Print one terse status line for a shell prompt or status bar, e.g. `wlan0 UP 📶 MyWiFi -56dBm`
+    Prompt {
+        /// Interface name
+        interface: String,
+    },
+
+    /// Enable monitor mode on interface
+    Monitor {
+        /// Interface name (the only wireless interface if omitted)
+        #[arg(short, long)]
+        interface: Option<String>,
         
         /// Disable monitor mode (set to managed)
         #[arg(short, long)]
         disable: bool,
+
+        /// Keep NetworkManager off the interface while in monitor mode (needs nmcli)
+        #[arg(long, conflicts_with = "vap")]
+        unmanage: bool,
+
+        /// Add a separate monitor interface instead of switching this one (name picked if omitted);
+        /// with --disable, remove the named one
+        #[arg(long, value_name = "NAME", num_args = 0..=1)]
+        vap: Option<Option<String>>,
     },
     
+    /// Switch an interface to managed, monitor, master (AP) or ad-hoc mode
+    Mode {
+        /// Interface name (the only wireless interface if omitted)
+        #[arg(short, long)]
+        interface: Option<String>,
+
+        /// managed, monitor, master or adhoc (also: station, ap, ibss)
+        mode: network::WirelessMode,
+    },
+
+    /// Tune an interface's radio to a channel, e.g. for monitoring one AP
+    Channel {
+        /// Interface name (the only wireless interface if omitted)
+        #[arg(short, long)]
+        interface: Option<String>,
+
+        /// Channel number
+        channel: u32,
+
+        /// HT20 (default), HT40+, HT40-, 80MHz or 160MHz
+        #[arg(short, long)]
+        width: Option<network::ChannelWidth>,
+    },
+
+    /// Run an access point with hostapd
+    Ap {
+        /// Interface name (the only wireless interface if omitted)
+        #[arg(short, long)]
+        interface: Option<String>,
+
+        /// Network name to broadcast
+        #[arg(short, long, required_unless_present = "disable")]
+        ssid: Option<String>,
+
+        /// Channel to run on
+        #[arg(short, long, default_value_t = 6)]
+        channel: u32,
+
+        /// WPA2 passphrase (8-63 characters); the network is open without one
+        #[arg(short, long, env = "SOZIN_AP_PASSWORD")]
+        password: Option<String>,
+
+        /// Stop the access point started earlier
+        #[arg(short, long, conflicts_with_all = ["ssid", "password"])]
+        disable: bool,
+    },
+
+    /// Capture frames to a pcap file from a raw packet socket
+    Capture {
+        /// Interface to capture on, usually one in monitor mode (the only wireless interface if omitted)
+        #[arg(short, long)]
+        interface: Option<String>,
+
+        /// pcap file to write
+        #[arg(short, long)]
+        output: std::path::PathBuf,
+
+        /// Stop after this many seconds instead of waiting for Ctrl+C
+        #[arg(short, long)]
+        duration: Option<u64>,
+
+        /// Keep only these 802.11 frames: "type mgt|ctl|data" or "subtype beacon", "subtype deauth", ...
+        #[arg(short, long)]
+        filter: Option<capture::FrameFilter>,
+    },
+
     /// Scan for WiFi networks
     Scan {
-        /// Interface to scan with
+        /// Interface to scan with (the only wireless interface if omitted)
         #[arg(short, long)]
-        interface: String,
+        interface: Option<String>,
         
-        /// Output as JSON
+        /// Output as JSON (same as --format json)
         #[arg(short, long)]
         json: bool,
+
+        /// Scan continuously, printing one JSON object per network per sweep (JSON Lines)
+        #[arg(long)]
+        stream: bool,
+
+        /// With --stream, ring the terminal bell when a BSSID not seen earlier in the session appears
+        #[arg(long, requires = "stream")]
+        alert: bool,
+
+        /// With --stream, seconds between sweeps (at least 1)
+        #[arg(long, value_name = "SECS", requires = "stream", default_value_t = STREAM_INTERVAL_SECS)]
+        interval: u64,
+
+        /// Listen for beacons only instead of sending probe requests (slower, may miss hidden APs)
+        #[arg(long)]
+        passive: bool,
+
+        /// Read saved `iw scan` output or sozin JSON instead of scanning
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["stream", "passive"])]
+        from_file: Option<std::path::PathBuf>,
+
+        /// Scan each band separately and merge (slower, more complete on some adapters)
+        #[arg(long, conflicts_with_all = ["stream", "passive", "from_file"])]
+        split_bands: bool,
+
+        /// Only scan these channels, e.g. 1,6,11
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["stream", "from_file", "split_bands"])]
+        channels: Vec<u32>,
+
+        /// Scan exactly the channels the adapter supports instead of the driver's full sweep
+        #[arg(long, conflicts_with_all = ["stream", "passive", "from_file", "split_bands", "channels"])]
+        supported_only: bool,
+
+        /// Order the results by this key
+        #[arg(long, value_enum, default_value_t = scanner::SortKey::Signal, conflicts_with = "stream")]
+        sort: scanner::SortKey,
+
+        /// Reverse the --sort order
+        #[arg(long, conflicts_with = "stream")]
+        reverse: bool,
     },
     
+    /// Scan on every wireless interface at once and merge the results
+    ScanAll {
+        /// Output as JSON (same as --format json)
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Scan, summarize the area and write a JSON site survey report
+    Survey {
+        /// Interface to scan with
+        interface: String,
+
+        /// Report file
+        #[arg(short, long)]
+        output: std::path::PathBuf,
+
+        /// Scans to merge, keeping the strongest reading per BSSID
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        sweeps: u32,
+
+        /// Also write the report as Markdown, next to it with a .md extension
+        #[arg(long)]
+        markdown: bool,
+    },
+
     /// Bring interface up
     Up {
         /// Interface name
@@ -67,7 +289,23 @@ enum Commands {
         /// Interface name
         interface: String,
     },
+
+    /// Return an interface to a clean managed state (down, managed mode, flush addresses, up)
+    Reset {
+        /// Interface name
+        interface: String,
+    },
     
+    /// Unload and reload an interface's driver to recover a stuck adapter (asks first)
+    ReloadDriver {
+        /// Interface name
+        interface: String,
+
+        /// Don't ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Spoof MAC address
     Mac {
         /// Interface name
@@ -77,6 +315,29 @@ enum Commands {
         /// New MAC address (random if not specified)
         #[arg(short, long)]
         address: Option<String>,
+
+        /// Also store the address on the active NetworkManager profile so it survives reconnects
+        #[arg(long, conflicts_with = "restore")]
+        persist: bool,
+
+        /// Return to the permanent address and clear any NetworkManager cloned MAC
+        #[arg(long, conflicts_with = "address")]
+        restore: bool,
+
+        /// Random address under a real vendor's OUI (optionally a named vendor, e.g. Apple)
+        #[arg(long, value_name = "VENDOR", num_args = 0..=1, conflicts_with_all = ["address", "restore"])]
+        realistic: Option<Option<String>>,
+    },
+
+    /// Set interface MTU
+    Mtu {
+        /// Interface name
+        #[arg(short, long)]
+        interface: String,
+
+        /// New MTU in bytes (68-9216)
+        #[arg(short, long)]
+        value: u32,
     },
 
     /// Rename network interface
@@ -90,91 +351,559 @@ enum Commands {
         new_name: String,
     },
 
+    /// Create, populate or delete a bridge
+    Bridge {
+        /// What to do with the bridge
+        #[arg(value_enum)]
+        action: BridgeAction,
+
+        /// Bridge name
+        name: String,
+
+        /// Interface to add to the bridge (required for `add`)
+        #[arg(short, long, required_if_eq("action", "add"))]
+        iface: Option<String>,
+    },
+
+    /// List radios or block/unblock them (airplane mode)
+    Rfkill {
+        /// What to do
+        #[arg(value_enum, default_value_t = RfkillAction::List)]
+        action: RfkillAction,
+
+        /// Which radios to block or unblock
+        #[arg(value_enum, default_value_t = RfkillKind::All)]
+        kind: RfkillKind,
+    },
+
+    /// Join a WiFi network through NetworkManager, or wpa_supplicant without it
+    Connect {
+        /// Interface name (the only wireless interface if omitted)
+        #[arg(short, long)]
+        interface: Option<String>,
+
+        /// Network name
+        #[arg(short, long)]
+        ssid: String,
+
+        /// Passphrase; leave out for open networks or ones with a saved profile
+        #[arg(short, long, env = "SOZIN_WIFI_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+
+    /// List saved NetworkManager profiles or delete one
+    Connections {
+        /// What to do
+        #[arg(value_enum, default_value_t = ConnectionsAction::List)]
+        action: ConnectionsAction,
+
+        /// Profile to delete (required for `delete`)
+        #[arg(required_if_eq("action", "delete"))]
+        name: Option<String>,
+    },
+
     /// Restart NetworkManager
     Restart,
+
+    /// Print a JSON environment snapshot for bug reports
+    Diagnostics {
+        /// Mask MAC addresses, drop IP addresses and descriptions, and hash SSIDs
+        #[arg(long)]
+        redact: bool,
+    },
+
+    /// Compare two saved scans (raw iw output or sozin JSON)
+    Diff {
+        /// Earlier scan
+        old_file: std::path::PathBuf,
+
+        /// Later scan
+        new_file: std::path::PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum BridgeAction {
+    /// Create an empty bridge
+    Create,
+    /// Enslave `--iface` to the bridge
+    Add,
+    /// Delete the bridge
+    Delete,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum RfkillAction {
+    /// Show every radio and whether it is soft or hard blocked
+    List,
+    /// Soft-block the radios
+    Block,
+    /// Clear the soft block (a hard block needs the physical switch)
+    Unblock,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ConnectionsAction {
+    /// Show every saved profile and the device it is active on
+    List,
+    /// Delete the named profile
+    Delete,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum RfkillKind {
+    /// WiFi radios only
+    Wifi,
+    /// Every radio
+    All,
+}
+
+impl Commands {
+    /// Subcommand name as typed on the command line
+    fn name(&self) -> &'static str {
+        match self {
+            Commands::Tui => "tui",
+            Commands::List { .. } => "list",
+            Commands::Info { .. } => "info",
+            Commands::Prompt { .. } => "prompt",
+            Commands::Monitor { .. } => "monitor",
+            Commands::Mode { .. } => "mode",
+            Commands::Channel { .. } => "channel",
+            Commands::Ap { .. } => "ap",
+            Commands::Capture { .. } => "capture",
+            Commands::Scan { .. } => "scan",
+            Commands::ScanAll { .. } => "scan-all",
+            Commands::Survey { .. } => "survey",
+            Commands::Up { .. } => "up",
+            Commands::Down { .. } => "down",
+            Commands::Reset { .. } => "reset",
+            Commands::ReloadDriver { .. } => "reload-driver",
+            Commands::Mac { .. } => "mac",
+            Commands::Mtu { .. } => "mtu",
+            Commands::Rename { .. } => "rename",
+            Commands::Bridge { .. } => "bridge",
+            Commands::Rfkill { .. } => "rfkill",
+            Commands::Connect { .. } => "connect",
+            Commands::Connections { .. } => "connections",
+            Commands::Restart => "restart",
+            Commands::Diagnostics { .. } => "diagnostics",
+            Commands::Diff { .. } => "diff",
+        }
+    }
+
+    /// Commands that change system state and cannot work without root
+    fn requires_root(&self) -> bool {
+        match self {
+            Commands::Monitor { .. }
+            | Commands::Mode { .. }
+            | Commands::Channel { .. }
+            | Commands::Ap { .. }
+            | Commands::Capture { .. }
+            | Commands::Up { .. }
+            | Commands::Down { .. }
+            | Commands::Reset { .. }
+            | Commands::ReloadDriver { .. }
+            | Commands::Mac { .. }
+            | Commands::Mtu { .. }
+            | Commands::Rename { .. }
+            | Commands::Bridge { .. }
+            | Commands::Restart => true,
+            Commands::Rfkill { action, .. } => *action != RfkillAction::List,
+            Commands::Connections { action, .. } => *action != ConnectionsAction::List,
+            Commands::Tui
+            | Commands::List { .. }
+            | Commands::Info { .. }
+            | Commands::Prompt { .. }
+            | Commands::Scan { .. }
+            | Commands::ScanAll { .. }
+            | Commands::Survey { .. }
+            | Commands::Connect { .. }
+            | Commands::Diagnostics { .. }
+            | Commands::Diff { .. } => false,
+        }
+    }
+}
+
+/// Process exit codes, listed in `--help`
+const EXIT_GENERIC: i32 = 1;
+const EXIT_NOT_ROOT: i32 = 2;
+const EXIT_NO_INTERFACE: i32 = 3;
+const EXIT_TOOL_MISSING: i32 = 4;
+const EXIT_NOT_PERMITTED: i32 = 5;
+
+/// Classify an error for the process exit code
+///
+/// Errors are mostly anyhow chains wrapping tool stderr, so this looks at io error
+/// kinds first (a binary that couldn't be spawned) and then at well-known messages.
+fn exit_code_for(err: &anyhow::Error) -> i32 {
+    for cause in err.chain() {
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                std::io::ErrorKind::NotFound => return EXIT_TOOL_MISSING,
+                std::io::ErrorKind::PermissionDenied => return EXIT_NOT_PERMITTED,
+                _ => {}
+            }
+        }
+    }
+
+    let message = format!("{:#}", err).to_lowercase();
+    let any = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+    if any(&["not installed", "neither nmcli nor a wpa_supplicant"]) {
+        EXIT_TOOL_MISSING
+    } else if any(&["not permitted", "permission denied"]) {
+        EXIT_NOT_PERMITTED
+    } else if any(&["cannot find device", "no such device", "does not exist"])
+        || (message.contains("interface") && message.contains("not found"))
+    {
+        EXIT_NO_INTERFACE
+    } else {
+        EXIT_GENERIC
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
+    if let Err(err) = run(cli).await {
+        eprintln!("Error: {:?}", err);
+        std::process::exit(exit_code_for(&err));
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    if cli.no_color {
+        colored::control::set_override(false);
+    }
+    audit::init(!cli.no_audit);
+    if cli.signal_good <= cli.signal_fair {
+        return Err(anyhow::anyhow!(
+            "--signal-good ({}) must be higher than --signal-fair ({})",
+            cli.signal_good,
+            cli.signal_fair
+        ));
+    }
+    let thresholds = scanner::SignalThresholds { good: cli.signal_good, fair: cli.signal_fair };
 
     // Check for root privileges
     if !nix::unistd::Uid::effective().is_root() {
-        eprintln!("{}", "⚠ Warning: Some operations require root privileges".yellow());
+        match &cli.command {
+            Some(command) if command.requires_root() => {
+                eprintln!(
+                    "{} `sozin {}` needs root; re-run with sudo",
+                    "✗".red(),
+                    command.name()
+                );
+                std::process::exit(EXIT_NOT_ROOT);
+            }
+            Some(
+                Commands::List { .. }
+                | Commands::Info { .. }
+                | Commands::Prompt { .. }
+                | Commands::Diagnostics { .. }
+                | Commands::Rfkill { .. }
+                | Commands::Connections { .. }
+                | Commands::Diff { .. },
+            ) => {}
+            _ => eprintln!("{}", "⚠ Warning: Some operations require root privileges".yellow()),
+        }
     }
 
     match cli.command {
         Some(Commands::Tui) | None => {
             // Default to TUI mode
             banner::print_banner();
-            ui::run_tui().await?;
+            ui::run_tui(
+                theme::Theme::from_name(cli.theme),
+                thresholds,
+                cli.limit.map(|n| n.get()),
+                // Mbit/s to bytes per second
+                cli.gauge_max.map(|m| m.get() * 125_000),
+                cli.remember_networks,
+                cli.ascii,
+                !cli.no_restore_on_exit,
+            )
+            .await?;
         }
         
-        Some(Commands::List { wireless, json }) => {
+        Some(Commands::List { wireless, group, json }) => {
+            let format = if json { OutputFormat::Json } else { cli.format };
             let interfaces = if wireless {
                 network::NetworkManager::get_wireless_interfaces()?
             } else {
                 network::NetworkManager::get_interfaces()?
             };
+            let total = interfaces.len();
+            // A single untitled group when not grouping
+            let groups: Vec<(Option<network::InterfaceType>, Vec<network::NetworkInterface>)> = if group {
+                network::NetworkManager::group_by_type(interfaces)
+                    .into_iter()
+                    .map(|(kind, group)| (Some(kind), group))
+                    .collect()
+            } else {
+                vec![(None, interfaces)]
+            };
 
-            if json {
-                println!("{}", serde_json::to_string_pretty(&interfaces)?);
+            if group && format == OutputFormat::Json {
+                // {"Ethernet": [...], "Wireless": [...], ...}
+                let by_type: std::collections::BTreeMap<String, Vec<network::NetworkInterface>> = groups
+                    .into_iter()
+                    .filter_map(|(kind, group)| Some((kind?.to_string(), group)))
+                    .collect();
+                println!("{}", output::to_json(&by_type)?);
+            } else if format != OutputFormat::Table {
+                let flat: Vec<network::NetworkInterface> = groups.into_iter().flat_map(|(_, group)| group).collect();
+                output::render_output(&flat, format)?;
             } else {
                 banner::print_mini_banner();
+                // One set of widths for every group so the columns line up across them
+                let mut rows = vec![LIST_HEADER.map(String::from)];
+                rows.extend(groups.iter().flat_map(|(_, group)| group).map(|iface| list_cells(iface, cli.ascii)));
+                let widths = column_widths(&rows);
                 println!();
-                for iface in &interfaces {
-                    let state_color = match iface.state {
-                        network::InterfaceState::Up => "green",
-                        network::InterfaceState::Down => "red",
-                        network::InterfaceState::Unknown => "yellow",
-                    };
-                    
-                    println!(
-                        "  {} {} [{}] - {} {}",
-                        match iface.interface_type {
-                            network::InterfaceType::Wireless => "📶",
-                            network::InterfaceType::Ethernet => "🔌",
-                            network::InterfaceType::Loopback => "🔄",
-                            network::InterfaceType::Virtual => "🌐",
-                            network::InterfaceType::Unknown => "❓",
-                        },
-                        iface.name.bold(),
-                        iface.state.to_string().color(state_color),
-                        iface.interface_type,
-                        iface.mac_address.as_deref().unwrap_or("").bright_black()
-                    );
+                println!("  {}", aligned(&rows[0], &widths).map(|c| c.cyan().to_string()).join(" ").trim_end());
+                for (kind, interfaces) in &groups {
+                    if let Some(kind) = kind {
+                        println!();
+                        println!("  {} ({})", kind.to_string().cyan().bold(), interfaces.len());
+                    }
+                    for iface in interfaces {
+                        let state_color = match iface.state {
+                            network::InterfaceState::Up => "green",
+                            network::InterfaceState::Down => "red",
+                            network::InterfaceState::Unknown => "yellow",
+                        };
+                        let [icon, name, state, kind, mac, ip, description] =
+                            aligned(&list_cells(iface, cli.ascii), &widths);
+                        let line = format!(
+                            "  {} {} {} {} {} {} {}",
+                            icon,
+                            name.bold(),
+                            state.color(state_color),
+                            kind,
+                            mac.bright_black(),
+                            ip,
+                            description
+                        );
+                        println!("{}", line.trim_end());
+                    }
                 }
                 println!();
-                println!("  {} interfaces found", interfaces.len().to_string().cyan());
+                println!("  {} interfaces found", total.to_string().cyan());
             }
         }
         
-        Some(Commands::Monitor { interface, disable }) => {
+        Some(Commands::Info { interface, json }) => {
+            let format = if json { OutputFormat::Json } else { cli.format };
+            let info = network::NetworkManager::get_interface_info(&interface)?;
+
+            match format {
+                // A single interface is printed as an object, not a one-element array
+                OutputFormat::Json => println!("{}", output::to_json(&info)?),
+                OutputFormat::Csv => output::render_output(std::slice::from_ref(&info), format)?,
+                OutputFormat::Table => print_interface_info(&info),
+            }
+        }
+
+        Some(Commands::Prompt { interface }) => {
+            // Called on every prompt redraw: sysfs reads, and at most one `iw` for the link
+            let state = network::NetworkManager::get_interface_state(&interface)
+                .ok_or_else(|| anyhow::anyhow!("Interface {} not found", interface))?;
+            let kind = network::NetworkManager::detect_interface_type(&interface);
+            let connection = (kind == network::InterfaceType::Wireless && state == network::InterfaceState::Up)
+                .then(|| network::NetworkManager::get_connection_info(&interface))
+                .flatten();
+            let state_label = match state {
+                network::InterfaceState::Up => state.to_string().green(),
+                network::InterfaceState::Down => state.to_string().red(),
+                network::InterfaceState::Unknown => state.to_string().yellow(),
+            };
+            println!(
+                "{}",
+                prompt_line(&interface, &state_label.to_string(), network::type_icon(kind, cli.ascii), connection.as_ref())
+            );
+        }
+
+        Some(Commands::Monitor { interface, disable, vap: Some(vap), .. }) => {
             banner::print_mini_banner();
+
+            if disable {
+                let name = vap.ok_or_else(|| anyhow::anyhow!("--disable --vap needs the monitor interface name"))?;
+                println!("  {} Removing monitor interface {}...", "»".cyan(), name.bold());
+                network::NetworkManager::delete_wireless_interface(&name).await?;
+                println!("  {} {} removed", "✓".green(), name);
+            } else {
+                let interface = resolve_interface(interface)?;
+                println!("  {} Adding monitor interface on {}...", "»".cyan(), interface.bold());
+                let name = network::NetworkManager::create_monitor_interface(&interface, vap.as_deref()).await?;
+                println!("  {} {} is up in monitor mode; {} is unchanged", "✓".green(), name.green(), interface);
+                println!("  {} Remove it with: sozin monitor -i {} --disable --vap {}", "»".cyan(), interface, name);
+            }
+        }
+
+        Some(Commands::Monitor { interface, disable, unmanage, vap: None }) => {
+            banner::print_mini_banner();
+            let interface = resolve_interface(interface)?;
             
             if disable {
                 println!("  {} Disabling monitor mode on {}...", "»".cyan(), interface.bold());
-                network::NetworkManager::disable_monitor_mode(&interface).await?;
+                network::NetworkManager::disable_monitor_mode(&interface, unmanage).await?;
                 println!("  {} Monitor mode disabled", "✓".green());
             } else {
                 println!("  {} Enabling monitor mode on {}...", "»".cyan(), interface.bold());
-                network::NetworkManager::enable_monitor_mode(&interface).await?;
+                network::NetworkManager::enable_monitor_mode(&interface, unmanage).await?;
                 println!("  {} Monitor mode enabled", "✓".green());
             }
         }
         
-        Some(Commands::Scan { interface, json }) => {
-            if !json {
+        Some(Commands::Mode { interface, mode }) => {
+            banner::print_mini_banner();
+            let interface = resolve_interface(interface)?;
+            println!("  {} Switching {} to {} mode...", "»".cyan(), interface.bold(), mode);
+            network::NetworkManager::set_wireless_mode(&interface, mode).await?;
+            println!("  {} {} is now in {} mode", "✓".green(), interface, mode);
+        }
+
+        Some(Commands::Channel { interface, channel, width }) => {
+            banner::print_mini_banner();
+            let interface = resolve_interface(interface)?;
+            let width = width.unwrap_or_default();
+            println!("  {} Setting {} to channel {} ({})...", "»".cyan(), interface.bold(), channel, width);
+            network::NetworkManager::set_channel(&interface, channel, Some(width)).await?;
+            println!("  {} {} is on channel {} ({})", "✓".green(), interface, channel, width);
+        }
+
+        Some(Commands::Ap { interface, ssid, channel, password, disable }) => {
+            banner::print_mini_banner();
+            let interface = resolve_interface(interface)?;
+
+            if disable {
+                println!("  {} Stopping access point on {}...", "»".cyan(), interface.bold());
+                network::NetworkManager::disable_ap_mode(&interface).await?;
+                println!("  {} Access point stopped", "✓".green());
+            } else {
+                let ssid = ssid.unwrap_or_default();
+                println!(
+                    "  {} Starting access point {} on {} (channel {})...",
+                    "»".cyan(),
+                    ssid.yellow(),
+                    interface.bold(),
+                    channel
+                );
+                network::NetworkManager::enable_ap_mode(&interface, &ssid, channel, password.as_deref()).await?;
+                let security = if password.is_some() { "WPA2" } else { "open" };
+                println!("  {} Access point {} is up ({})", "✓".green(), ssid.green(), security);
+                println!("  {} Stop it with: sozin ap -i {} --disable", "»".cyan(), interface);
+            }
+        }
+
+        Some(Commands::Capture { interface, output, duration, filter }) => {
+            banner::print_mini_banner();
+            let interface = resolve_interface(interface)?;
+            let session = capture::CaptureSession::start(&interface, &output, filter)?;
+            println!(
+                "  {} Capturing on {} to {} ({})...",
+                "»".cyan(),
+                interface.bold(),
+                output.display().to_string().yellow(),
+                match duration {
+                    Some(secs) => format!("stopping after {}s", secs),
+                    None => "Ctrl+C to stop".to_string(),
+                }
+            );
+
+            let limit = async {
+                match duration {
+                    Some(secs) => tokio::time::sleep(std::time::Duration::from_secs(secs)).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = limit => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+
+            let elapsed = session.started.elapsed().as_secs();
+            let summary = tokio::task::spawn_blocking(move || session.stop()).await??;
+            let filtered = match summary.filtered {
+                0 => String::new(),
+                n => format!(", {} filtered out", n),
+            };
+            println!(
+                "  {} Capture stopped after {}s: {} frames, {} bytes written{}",
+                "✓".green(),
+                elapsed,
+                summary.frames,
+                summary.bytes,
+                filtered
+            );
+        }
+
+        Some(Commands::Scan { interface, json, stream, alert, interval, passive, from_file, split_bands, channels, supported_only, sort, reverse }) => {
+            let format = if json { OutputFormat::Json } else { cli.format };
+            let interface = match from_file {
+                Some(_) => interface.unwrap_or_default(),
+                None => resolve_interface(interface)?,
+            };
+
+            if stream {
+                if format != OutputFormat::Json {
+                    return Err(anyhow::anyhow!("--stream requires --format json (or --json)"));
+                }
+                if cli.limit.is_some() {
+                    return Err(anyhow::anyhow!("--limit cannot be used with --stream"));
+                }
+                stream_scan(&interface, passive, alert, interval).await?;
+                return Ok(());
+            }
+
+            if format == OutputFormat::Table {
                 banner::print_mini_banner();
                 println!();
-                println!("  {} Scanning on {}...", "»".cyan(), interface.bold());
+                match &from_file {
+                    Some(path) => println!("  {} Reading {}...", "»".cyan(), path.display().to_string().bold()),
+                    None => println!(
+                        "  {} {} on {}...",
+                        "»".cyan(),
+                        if passive {
+                            "Passively scanning"
+                        } else if split_bands {
+                            "Scanning band by band"
+                        } else {
+                            "Scanning"
+                        },
+                        interface.bold()
+                    ),
+                }
             }
             
             let mut wifi_scanner = scanner::WifiScanner::new(&interface);
-            let networks = wifi_scanner.scan().await?;
+            if cli.remember_networks {
+                wifi_scanner = wifi_scanner.with_store(store::ScanStore::open());
+            }
+            let networks = match &from_file {
+                Some(path) => {
+                    let contents = std::fs::read_to_string(path)
+                        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+                    wifi_scanner
+                        .parse_saved(&contents)
+                        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?
+                }
+                None if split_bands => wifi_scanner.scan_per_band().await?,
+                None if !channels.is_empty() => wifi_scanner.scan_channels(&channels, passive).await?,
+                None if supported_only => wifi_scanner.scan_supported().await?,
+                None => wifi_scanner.scan(passive).await?,
+            };
+            if wifi_scanner.last_scan_truncated() {
+                eprintln!("  {} Scan timed out; showing partial results", "⚠".yellow());
+            }
+            if let Err(e) = wifi_scanner.save_store() {
+                eprintln!("  {} Couldn't save known networks: {}", "⚠".yellow(), e);
+            }
+            let mut shown = scanner::strongest(&networks, cli.limit.map_or(networks.len(), |n| n.get()));
+            scanner::sort_networks(&mut shown, sort, reverse);
             
-            if json {
-                println!("{}", serde_json::to_string_pretty(&networks)?);
+            if format != OutputFormat::Table {
+                output::render_output(&shown, format)?;
             } else {
                 println!("  {} Found {} networks\n", "✓".green(), networks.len().to_string().cyan());
                 
@@ -188,31 +917,123 @@ async fn main() -> Result<()> {
                 );
                 println!("  {}", "─".repeat(70).bright_black());
                 
-                for net in &networks {
-                    let signal_color = if net.signal_strength > -50 {
-                        "green"
-                    } else if net.signal_strength > -70 {
-                        "yellow"
-                    } else {
-                        "red"
+                for net in &shown {
+                    let signal_color = match scanner::signal_level(net.signal_strength, &thresholds) {
+                        scanner::SignalLevel::Good => "green",
+                        scanner::SignalLevel::Fair => "yellow",
+                        scanner::SignalLevel::Weak => "red",
                     };
                     
                     println!(
-                        "  {:<25} {:<18} {:>4} {:>8} {}",
-                        if net.ssid.len() > 24 {
-                            format!("{}...", &net.ssid[..21])
-                        } else {
-                            net.ssid.clone()
-                        },
+                        "  {} {:<18} {:>4} {:>8} {}{}",
+                        ssid_column(&net.display_ssid()),
                         net.bssid,
                         net.channel,
                         format!("{}dBm", net.signal_strength).color(signal_color),
-                        net.security
+                        net.security,
+                        if net.is_legacy { " legacy".bright_black().to_string() } else { String::new() }
                     );
                 }
+
+                println!();
+                if shown.len() < networks.len() {
+                    println!("  {} Showing {} of {} (strongest first)", "»".cyan(), shown.len(), networks.len());
+                }
+                print_scan_summary(&networks);
             }
         }
         
+        Some(Commands::ScanAll { json }) => {
+            let format = if json { OutputFormat::Json } else { cli.format };
+            let interfaces: Vec<String> = network::NetworkManager::get_wireless_interfaces()?
+                .into_iter()
+                .map(|i| i.name)
+                .collect();
+            if interfaces.is_empty() {
+                return Err(anyhow::anyhow!("Wireless interface not found"));
+            }
+
+            if format == OutputFormat::Table {
+                banner::print_mini_banner();
+                println!();
+                println!("  {} Scanning on {}...", "»".cyan(), interfaces.join(", ").bold());
+            }
+            let (networks, failed) = scanner::scan_all(&interfaces).await;
+            for (interface, e) in &failed {
+                eprintln!("  {} {}: {}", "⚠".yellow(), interface, e);
+            }
+            if failed.len() == interfaces.len() {
+                return Err(anyhow::anyhow!("Scan failed on every wireless interface"));
+            }
+            let shown = scanner::strongest(&networks, cli.limit.map_or(networks.len(), |n| n.get()));
+
+            if format != OutputFormat::Table {
+                output::render_output(&shown, format)?;
+            } else {
+                println!("  {} Found {} networks\n", "✓".green(), networks.len().to_string().cyan());
+                println!(
+                    "  {:<25} {:<18} {:>4} {:>8} {}",
+                    "SSID".cyan(),
+                    "BSSID".cyan(),
+                    "CH".cyan(),
+                    "Signal".cyan(),
+                    "Seen by".cyan()
+                );
+                println!("  {}", "─".repeat(70).bright_black());
+                for net in &shown {
+                    let signal_color = match scanner::signal_level(net.signal_strength, &thresholds) {
+                        scanner::SignalLevel::Good => "green",
+                        scanner::SignalLevel::Fair => "yellow",
+                        scanner::SignalLevel::Weak => "red",
+                    };
+                    println!(
+                        "  {} {:<18} {:>4} {:>8} {}",
+                        ssid_column(&net.display_ssid()),
+                        net.bssid,
+                        net.channel,
+                        format!("{}dBm", net.signal_strength).color(signal_color),
+                        net.seen_by.join(", ")
+                    );
+                }
+                println!();
+                if shown.len() < networks.len() {
+                    println!("  {} Showing {} of {} (strongest first)", "»".cyan(), shown.len(), networks.len());
+                }
+            }
+        }
+
+        Some(Commands::Survey { interface, output, sweeps, markdown }) => {
+            let markdown_path = output.with_extension("md");
+            if markdown && markdown_path == output {
+                return Err(anyhow::anyhow!("--markdown needs an output file that doesn't end in .md"));
+            }
+
+            banner::print_mini_banner();
+            let mut wifi_scanner = scanner::WifiScanner::new(&interface);
+            let mut networks = Vec::new();
+            for sweep in 1..=sweeps {
+                println!("  {} Scanning on {} ({}/{})...", "»".cyan(), interface.bold(), sweep, sweeps);
+                networks.extend(wifi_scanner.scan(false).await?);
+            }
+
+            let report = survey::SurveyReport::build(
+                &interface,
+                sweeps,
+                networks,
+                chrono::Utc::now(),
+                network::NetworkManager::tool_versions(),
+            );
+            std::fs::write(&output, output::to_json(&report)?)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", output.display(), e))?;
+            println!("  {} {} networks, report saved to {}", "✓".green(), report.networks.len(), output.display());
+            if markdown {
+                std::fs::write(&markdown_path, report.to_markdown())
+                    .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", markdown_path.display(), e))?;
+                println!("  {} Markdown saved to {}", "✓".green(), markdown_path.display());
+            }
+            println!("  {} {}", "»".cyan(), report.security);
+        }
+
         Some(Commands::Up { interface }) => {
             banner::print_mini_banner();
             println!("  {} Bringing up {}...", "»".cyan(), interface.bold());
@@ -226,29 +1047,594 @@ async fn main() -> Result<()> {
             network::NetworkManager::bring_down(&interface).await?;
             println!("  {} {} is now DOWN", "✓".green(), interface);
         }
+
+        Some(Commands::Reset { interface }) => {
+            banner::print_mini_banner();
+            println!("  {} Resetting {}...", "»".cyan(), interface.bold());
+            let steps = network::NetworkManager::reset_interface(&interface).await;
+            for step in &steps {
+                match &step.outcome {
+                    Ok(()) => println!("  {} {}", "✓".green(), step.description),
+                    Err(e) => println!("  {} {}: {}", "✗".red(), step.description, e),
+                }
+            }
+            let failed = steps.iter().filter(|s| s.outcome.is_err()).count();
+            if failed > 0 {
+                return Err(anyhow::anyhow!("Reset of {} finished with {} failed step(s)", interface, failed));
+            }
+            println!("  {} {} reset", "✓".green(), interface);
+        }
+
+        Some(Commands::ReloadDriver { interface, yes }) => {
+            banner::print_mini_banner();
+            let prompt = format!(
+                "Reload the driver of {}? Every interface using it drops for a moment",
+                interface
+            );
+            if !yes && !confirm(&prompt)? {
+                println!("  {} Cancelled", "»".cyan());
+                return Ok(());
+            }
+            println!("  {} Reloading the driver of {}...", "»".cyan(), interface.bold());
+            let name = network::NetworkManager::reload_driver(&interface).await?;
+            if name == interface {
+                println!("  {} Driver reloaded; {} is back", "✓".green(), name);
+            } else {
+                println!("  {} Driver reloaded; {} is now {}", "✓".green(), interface, name.green());
+            }
+        }
         
-        Some(Commands::Mac { interface, address }) => {
+        Some(Commands::Mac { interface, address, persist, restore, realistic }) => {
             banner::print_mini_banner();
-            let new_mac = address.unwrap_or_else(|| network::NetworkManager::generate_random_mac());
-            println!("  {} Changing MAC on {} to {}...", "»".cyan(), interface.bold(), new_mac.yellow());
-            network::NetworkManager::spoof_mac(&interface, &new_mac).await?;
+            if restore {
+                println!("  {} Restoring permanent MAC on {}...", "»".cyan(), interface.bold());
+                match network::NetworkManager::restore_mac(&interface).await? {
+                    Some(mac) => println!("  {} MAC address restored to {}", "✓".green(), mac.green()),
+                    None => println!("  {} {} already uses its permanent MAC address", "✓".green(), interface),
+                }
+                return Ok(());
+            }
+
+            let new_mac = match (address, realistic) {
+                (Some(address), _) => address,
+                (None, Some(vendor)) => network::NetworkManager::generate_vendor_mac(vendor.as_deref())?,
+                (None, None) => network::NetworkManager::generate_random_mac(),
+            };
+            match oui::vendor_for(&new_mac) {
+                Some(vendor) => println!(
+                    "  {} Changing MAC on {} to {} ({})...",
+                    "»".cyan(),
+                    interface.bold(),
+                    new_mac.yellow(),
+                    vendor
+                ),
+                None => println!("  {} Changing MAC on {} to {}...", "»".cyan(), interface.bold(), new_mac.yellow()),
+            }
+            let applied = network::NetworkManager::spoof_mac(&interface, &new_mac, persist).await?;
             println!("  {} MAC address changed to {}", "✓".green(), new_mac.green());
+            match applied {
+                network::MacPersistence::NetworkManager(profile) => println!(
+                    "  {} Saved as cloned MAC on NetworkManager profile {}",
+                    "✓".green(),
+                    profile.bold()
+                ),
+                network::MacPersistence::Runtime if persist => println!(
+                    "  {} No active NetworkManager profile; the change may not survive a reconnect",
+                    "⚠".yellow()
+                ),
+                network::MacPersistence::Runtime => {}
+            }
+        }
+
+        Some(Commands::Mtu { interface, value }) => {
+            banner::print_mini_banner();
+            println!("  {} Setting MTU on {} to {}...", "»".cyan(), interface.bold(), value.to_string().yellow());
+            network::NetworkManager::set_mtu(&interface, value).await?;
+            println!("  {} MTU on {} set to {}", "✓".green(), interface, value.to_string().green());
         }
 
         Some(Commands::Rename { interface, new_name }) => {
             banner::print_mini_banner();
             println!("  {} Renaming {} to {}...", "»".cyan(), interface.bold(), new_name.yellow());
-            network::NetworkManager::rename_interface(&interface, &new_name).await?;
+            let warning = network::NetworkManager::rename_interface(&interface, &new_name).await?;
             println!("  {} Interface renamed from {} to {}", "✓".green(), interface, new_name.green());
+            if let Some(warning) = warning {
+                println!("  {} {}", "⚠".yellow(), warning);
+            }
+        }
+
+        Some(Commands::Bridge { action, name, iface }) => {
+            banner::print_mini_banner();
+            match action {
+                BridgeAction::Create => {
+                    println!("  {} Creating bridge {}...", "»".cyan(), name.bold());
+                    network::NetworkManager::create_bridge(&name).await?;
+                    println!("  {} Bridge {} created", "✓".green(), name.green());
+                }
+                BridgeAction::Add => {
+                    let iface = iface.expect("clap requires --iface for add");
+                    println!("  {} Adding {} to {}...", "»".cyan(), iface.bold(), name.bold());
+                    network::NetworkManager::add_to_bridge(&name, &iface).await?;
+                    println!("  {} {} is now a port of {}", "✓".green(), iface, name.green());
+                }
+                BridgeAction::Delete => {
+                    println!("  {} Deleting bridge {}...", "»".cyan(), name.bold());
+                    network::NetworkManager::delete_bridge(&name).await?;
+                    println!("  {} Bridge {} deleted", "✓".green(), name);
+                }
+            }
+        }
+
+        Some(Commands::Diff { old_file, new_file }) => {
+            let load = |path: &std::path::Path| -> Result<Vec<scanner::WifiNetwork>> {
+                let contents = std::fs::read_to_string(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+                scanner::WifiScanner::new("")
+                    .parse_saved(&contents)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+            };
+            let diff = scanner::diff_scans(&load(&old_file)?, &load(&new_file)?);
+
+            match cli.format {
+                OutputFormat::Json => println!("{}", output::to_json(&diff)?),
+                OutputFormat::Csv => return Err(anyhow::anyhow!("diff supports table and json output")),
+                OutputFormat::Table => {
+                    banner::print_mini_banner();
+                    println!();
+                    for net in &diff.appeared {
+                        let line = format!("+ {} {:<18} ch {:>3} {:>5} dBm", ssid_column(&net.ssid), net.bssid, net.channel, net.signal_strength);
+                        println!("  {}", line.green());
+                    }
+                    for net in &diff.disappeared {
+                        let line = format!("- {} {:<18} ch {:>3} {:>5} dBm", ssid_column(&net.ssid), net.bssid, net.channel, net.signal_strength);
+                        println!("  {}", line.red());
+                    }
+                    for change in &diff.changed {
+                        let net = &change.network;
+                        let line = format!(
+                            "~ {} {:<18} ch {:>3} {:>5} → {} dBm ({:+})",
+                            ssid_column(&net.ssid), net.bssid, net.channel, change.old_signal, net.signal_strength, change.delta
+                        );
+                        println!("  {}", line.yellow());
+                    }
+                    println!(
+                        "\n  {} {} appeared, {} gone, {} changed by {}+ dB",
+                        "»".cyan(),
+                        diff.appeared.len(),
+                        diff.disappeared.len(),
+                        diff.changed.len(),
+                        scanner::SIGNAL_CHANGE_THRESHOLD_DBM
+                    );
+                }
+            }
         }
 
+        Some(Commands::Rfkill { action, kind }) => {
+            let kind = match kind {
+                RfkillKind::Wifi => "wifi",
+                RfkillKind::All => "all",
+            };
+            if action != RfkillAction::List {
+                let blocked = action == RfkillAction::Block;
+                network::NetworkManager::set_rfkill(kind, blocked).await?;
+                if cli.format == OutputFormat::Table {
+                    banner::print_mini_banner();
+                    let verb = if blocked { "blocked" } else { "unblocked" };
+                    println!("  {} {} radios {}", "✓".green(), kind, verb);
+                }
+            } else if cli.format == OutputFormat::Table {
+                banner::print_mini_banner();
+                println!();
+            }
+
+            let entries = network::NetworkManager::rfkill_list()?;
+            output::render_output(&entries, cli.format)?;
+            if cli.format == OutputFormat::Table && entries.iter().any(|e| e.hard_blocked) {
+                println!(
+                    "\n  {} Hard-blocked radios need their hardware switch or a BIOS setting",
+                    "⚠".yellow()
+                );
+            }
+        }
+
+        Some(Commands::Connect { interface, ssid, password }) => {
+            banner::print_mini_banner();
+            let interface = resolve_interface(interface)?;
+            println!("  {} Connecting {} to {}...", "»".cyan(), interface.bold(), ssid.bold());
+            network::NetworkManager::connect(&interface, &ssid, password.as_deref()).await?;
+            match network::NetworkManager::get_connection_info(&interface).and_then(|conn| conn.signal_dbm) {
+                Some(dbm) => println!("  {} Connected to {} ({} dBm)", "✓".green(), ssid, dbm),
+                None => println!("  {} Connected to {}", "✓".green(), ssid),
+            }
+        }
+
+        Some(Commands::Connections { action, name }) => match (action, name) {
+            (ConnectionsAction::Delete, Some(name)) => {
+                banner::print_mini_banner();
+                println!("  {} Deleting connection {}...", "»".cyan(), name.bold());
+                network::NetworkManager::delete_connection(&name).await?;
+                println!("  {} Connection {} deleted", "✓".green(), name);
+            }
+            _ => {
+                let connections = network::NetworkManager::list_saved_connections()?;
+                if cli.format == OutputFormat::Table {
+                    banner::print_mini_banner();
+                    println!();
+                }
+                output::render_output(&connections, cli.format)?;
+            }
+        },
+
         Some(Commands::Restart) => {
             banner::print_mini_banner();
             println!("  {} Restarting NetworkManager...", "»".cyan());
             network::NetworkManager::restart_network_manager().await?;
             println!("  {} NetworkManager restarted", "✓".green());
         }
+
+        Some(Commands::Diagnostics { redact }) => {
+            let mut report = diagnostics::DiagnosticsReport::collect()?;
+            if redact {
+                report.redact();
+            }
+            println!("{}", output::to_json(&report)?);
+        }
     }
 
     Ok(())
 }
+
+/// An SSID cut and padded to the 25 columns the scan and diff tables give it
+fn ssid_column(ssid: &str) -> String {
+    output::pad_display(&output::truncate_display(ssid, 24), 25)
+}
+
+/// The `prompt` line: name, state, type icon, then the network and signal when associated
+///
+/// A hidden network is shown by its BSSID.
+fn prompt_line(name: &str, state: &str, icon: &str, connection: Option<&network::ConnectionInfo>) -> String {
+    let mut parts = vec![name.to_string(), state.to_string(), icon.to_string()];
+    if let Some(conn) = connection {
+        parts.push(conn.ssid.clone().filter(|s| !s.is_empty()).unwrap_or_else(|| conn.bssid.clone()));
+        if let Some(dbm) = conn.signal_dbm {
+            parts.push(format!("{}dBm", dbm));
+        }
+    }
+    parts.join(" ")
+}
+
+/// Column titles of the `list` table
+const LIST_HEADER: [&str; 7] = ["", "NAME", "STATE", "TYPE", "MAC", "IP", "DESCRIPTION"];
+
+/// Plain-text `list` cells: icon, name, state, type, MAC, primary IP and description,
+/// with `-` for a missing MAC or IP
+fn list_cells(iface: &network::NetworkInterface, ascii: bool) -> [String; 7] {
+    [
+        network::type_icon(iface.interface_type, ascii).to_string(),
+        iface.name.clone(),
+        iface.state.to_string(),
+        iface.interface_type.to_string(),
+        iface.mac_address.clone().unwrap_or_else(|| "-".to_string()),
+        iface.ip_address.clone().unwrap_or_else(|| "-".to_string()),
+        iface.description.clone().unwrap_or_default(),
+    ]
+}
+
+/// Display width of the widest cell in each column
+fn column_widths<const N: usize>(rows: &[[String; N]]) -> [usize; N] {
+    let mut widths = [0; N];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(unicode_width::UnicodeWidthStr::width(cell.as_str()));
+        }
+    }
+    widths
+}
+
+/// Cells padded to `widths`, except the last, which has nothing after it
+fn aligned<const N: usize>(cells: &[String; N], widths: &[usize; N]) -> [String; N] {
+    std::array::from_fn(|i| {
+        if i + 1 == N {
+            cells[i].clone()
+        } else {
+            output::pad_display(&cells[i], widths[i])
+        }
+    })
+}
+
+/// Networks per security type, strongest first, leaving out types nobody uses
+fn security_counts(networks: &[scanner::WifiNetwork]) -> Vec<(scanner::SecurityType, usize)> {
+    use scanner::SecurityType;
+
+    [
+        SecurityType::WPA3,
+        SecurityType::WPA2Enterprise,
+        SecurityType::WPA2,
+        SecurityType::WPA,
+        SecurityType::WEP,
+        SecurityType::Open,
+        SecurityType::Unknown,
+    ]
+    .into_iter()
+    .map(|kind| (kind, networks.iter().filter(|n| n.security == kind).count()))
+    .filter(|(_, count)| *count > 0)
+    .collect()
+}
+
+/// Overview printed under the scan table: totals per band and security, the quietest channels
+/// and any insecure networks
+fn print_scan_summary(networks: &[scanner::WifiNetwork]) {
+    let (bands, security) = (scanner::band_counts(networks), security_counts(networks));
+    let join = |parts: Vec<String>| parts.join(", ");
+    println!(
+        "  {} {} networks | {} | {}",
+        "»".cyan(),
+        networks.len().to_string().cyan(),
+        join(bands.iter().map(|(band, count)| format!("{} {}", band, count)).collect()),
+        join(security.iter().map(|(kind, count)| format!("{} {}", kind, count)).collect()),
+    );
+
+    let recommendations: Vec<String> = bands
+        .iter()
+        .map(|(band, _)| *band)
+        .filter(|band| *band != scanner::Band::Six)
+        .filter_map(|band| {
+            scanner::recommend_channel(networks, band).map(|ch| {
+                let dfs = if scanner::is_dfs_channel(ch) { " (DFS)" } else { "" };
+                format!("{} ch {}{}", band, ch, dfs)
+            })
+        })
+        .collect();
+    if !recommendations.is_empty() {
+        println!("  {} Least congested: {}", "»".cyan(), recommendations.join(", ").green());
+    }
+
+    let legacy = networks.iter().filter(|n| n.is_legacy).count();
+    if legacy > 0 {
+        println!(
+            "  {} {} legacy (802.11b/g only) AP{} slowing down {} channel",
+            "»".cyan(),
+            legacy.to_string().yellow(),
+            if legacy == 1 { "" } else { "s" },
+            if legacy == 1 { "its" } else { "their" }
+        );
+    }
+
+    let summary = scanner::security_summary(networks);
+    if summary.insecure() > 0 {
+        println!("  {} {}", "⚠".yellow(), summary.to_string().yellow());
+    } else {
+        println!("  {} {}", "✓".green(), summary);
+    }
+}
+
+/// Ask a yes/no question on the terminal; anything but y/yes is a no
+fn confirm(prompt: &str) -> Result<bool> {
+    use std::io::Write;
+
+    print!("  {} {} [y/N] ", "?".yellow(), prompt);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// The interface named on the command line, or the only wireless one when none was given
+fn resolve_interface(arg: Option<String>) -> Result<String> {
+    match arg {
+        Some(name) => Ok(name),
+        None => {
+            let wireless: Vec<String> = network::NetworkManager::get_wireless_interfaces()?
+                .into_iter()
+                .map(|i| i.name)
+                .collect();
+            sole_interface(&wireless)
+        }
+    }
+}
+
+/// The single entry of `wireless`, or an error asking for `--interface`
+fn sole_interface(wireless: &[String]) -> Result<String> {
+    match wireless {
+        [only] => Ok(only.clone()),
+        [] => Err(anyhow::anyhow!("Wireless interface not found; pass one with --interface")),
+        several => Err(anyhow::anyhow!(
+            "Several wireless interfaces found ({}); pick one with --interface",
+            several.join(", ")
+        )),
+    }
+}
+
+/// Print the `info` subcommand's table view
+fn print_interface_info(info: &network::InterfaceInfo) {
+    let iface = &info.interface;
+    let row = |label: &str, value: String| {
+        println!("  {:<18} {}", label.bright_black(), value);
+    };
+    let or_na = |value: Option<String>| value.unwrap_or_else(|| "N/A".to_string());
+
+    banner::print_mini_banner();
+    println!();
+    row("Name", iface.name.bold().to_string());
+    if let Some(description) = &iface.description {
+        row("Description", description.clone());
+    }
+    row("Type", iface.interface_type.to_string());
+    row("State", iface.state.to_string());
+    row("MAC", or_na(iface.mac_address.clone()));
+    row(
+        "Addresses",
+        if info.ip_addresses.is_empty() {
+            "N/A".to_string()
+        } else {
+            info.ip_addresses.join(", ")
+        },
+    );
+    row("Driver", or_na(iface.driver.clone()));
+    row("MTU", or_na(iface.mtu.map(|m| m.to_string())));
+    row("Managed by", info.management.to_string());
+    if let Some(speed) = iface.link_speed {
+        row("Link", speed.to_string());
+    }
+
+    if iface.interface_type == network::InterfaceType::Wireless {
+        row("Mode", or_na(info.wireless_mode.map(|m| m.to_string())));
+        match &info.connection {
+            Some(conn) => {
+                row("Connected to", format!("{} ({})", conn.ssid.as_deref().unwrap_or("?"), conn.bssid));
+                row("Signal", or_na(conn.signal_dbm.map(|s| format!("{} dBm", s))));
+                row("TX bitrate", or_na(conn.tx_bitrate.clone()));
+            }
+            None => row("Connected to", "not connected".to_string()),
+        }
+        row(
+            "Channels",
+            info.supported_channels
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+    }
+
+    if let Some(stats) = info.stats {
+        row("RX", format!("{} bytes, {} packets, {} errors, {} dropped", stats.rx_bytes, stats.rx_packets, stats.rx_errors, stats.rx_dropped));
+        row("TX", format!("{} bytes, {} packets, {} errors, {} dropped", stats.tx_bytes, stats.tx_packets, stats.tx_errors, stats.tx_dropped));
+    }
+}
+
+/// Interval between sweeps when streaming scan results
+const STREAM_INTERVAL_SECS: u64 = 5;
+
+/// Run a continuous scan, writing newline-delimited JSON until Ctrl-C
+async fn stream_scan(interface: &str, passive: bool, alert: bool, interval_secs: u64) -> Result<()> {
+    use std::io::Write;
+
+    let mut continuous = scanner::ContinuousScanner::new(interface, interval_secs);
+    continuous.set_passive(passive);
+    continuous.set_alert(alert);
+    let mut sweep_id: u64 = 0;
+
+    let sweeps = continuous.run(|sweep, alert| {
+        if alert {
+            // Bell on stderr, so it never lands in the JSON on stdout
+            eprint!("\x07");
+        }
+        let networks = match sweep {
+            Ok(networks) => networks,
+            Err(e) => {
+                eprintln!("Scan error: {}", e);
+                return Ok(());
+            }
+        };
+        sweep_id += 1;
+        let timestamp = chrono::Utc::now();
+        let mut stdout = std::io::stdout().lock();
+        for network in &networks {
+            let record = scanner::SweepRecord { sweep_id, timestamp, network };
+            // Serialize before writing so an interrupted sweep never leaves half a line
+            let line = output::to_json_line(&record)?;
+            writeln!(stdout, "{}", line)?;
+            stdout.flush()?;
+        }
+        Ok(())
+    });
+
+    tokio::select! {
+        result = sweeps => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_follow_failure_class() {
+        let spawn = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert_eq!(exit_code_for(&spawn), EXIT_TOOL_MISSING);
+        assert_eq!(exit_code_for(&anyhow::anyhow!("nmcli is not installed")), EXIT_TOOL_MISSING);
+        assert_eq!(
+            exit_code_for(&anyhow::anyhow!("Failed to set MTU: RTNETLINK answers: Operation not permitted")),
+            EXIT_NOT_PERMITTED
+        );
+        assert_eq!(
+            exit_code_for(&anyhow::anyhow!("Failed to bring up interface: Cannot find device \"wlan9\"")),
+            EXIT_NO_INTERFACE
+        );
+        assert_eq!(exit_code_for(&anyhow::anyhow!("Interface wlan9 not found")), EXIT_NO_INTERFACE);
+        assert_eq!(exit_code_for(&anyhow::anyhow!("SSID must be 1-32 bytes")), EXIT_GENERIC);
+    }
+
+    #[test]
+    fn scan_breakdown_counts_bands_and_security() {
+        let mut scanner = scanner::WifiScanner::new("wlan0");
+        let mut networks = scanner.parse_saved(include_str!("../tests/fixtures/wpa2_psk.txt")).unwrap();
+        networks.extend(scanner.parse_saved(include_str!("../tests/fixtures/5ghz.txt")).unwrap());
+        networks.extend(scanner.parse_saved(include_str!("../tests/fixtures/open.txt")).unwrap());
+
+        let (bands, security) = (scanner::band_counts(&networks), security_counts(&networks));
+        assert_eq!(bands.iter().map(|(_, n)| n).sum::<usize>(), networks.len());
+        assert!(bands.iter().any(|(band, _)| *band == scanner::Band::Five));
+        assert_eq!(security.iter().map(|(_, n)| n).sum::<usize>(), networks.len());
+        assert!(security.iter().all(|(_, n)| *n > 0));
+    }
+
+    #[test]
+    fn list_columns_line_up_whatever_the_lengths() {
+        let iface = |name: &str, mac: Option<&str>, ip: Option<&str>| network::NetworkInterface {
+            name: name.to_string(),
+            parent: None,
+            mac_address: mac.map(String::from),
+            ip_address: ip.map(String::from),
+            state: network::InterfaceState::Up,
+            interface_type: network::InterfaceType::Wireless,
+            driver: None,
+            mtu: None,
+            link_speed: None,
+            permanent_mac: None,
+            description: None,
+        };
+        let mut rows = vec![LIST_HEADER.map(String::from)];
+        rows.push(list_cells(&iface("wlp0s20f3mon", Some("aa:bb:cc:dd:ee:ff"), Some("192.168.1.20")), false));
+        rows.push(list_cells(&iface("lo", None, None), true));
+        let widths = column_widths(&rows);
+
+        let lines: Vec<String> = rows.iter().map(|row| aligned(row, &widths).join(" ")).collect();
+        // Every row's last column (IP) starts at the same display column
+        let ip_column = |line: &str, ip: &str| unicode_width::UnicodeWidthStr::width(&line[..line.rfind(ip).unwrap()]);
+        // "IP " so the header lookup skips the IP inside DESCRIPTION
+        assert_eq!(ip_column(&lines[0], "IP "), ip_column(&lines[1], "192.168.1.20"));
+        assert_eq!(ip_column(&lines[0], "IP "), ip_column(lines[2].trim_end(), "-"));
+        assert!(lines[2].contains("[W] lo "));
+    }
+
+    #[test]
+    fn prompt_line_is_terse_and_skips_what_is_missing() {
+        let conn = network::ConnectionInfo {
+            bssid: "aa:bb:cc:dd:ee:ff".to_string(),
+            ssid: Some("MyWiFi".to_string()),
+            frequency: Some(2437),
+            signal_dbm: Some(-56),
+            tx_bitrate: None,
+        };
+        assert_eq!(prompt_line("wlan0", "UP", "📶", Some(&conn)), "wlan0 UP 📶 MyWiFi -56dBm");
+
+        let hidden = network::ConnectionInfo { ssid: None, signal_dbm: None, ..conn };
+        assert_eq!(prompt_line("wlan0", "UP", "[W]", Some(&hidden)), "wlan0 UP [W] aa:bb:cc:dd:ee:ff");
+        assert_eq!(prompt_line("eth0", "DOWN", "[E]", None), "eth0 DOWN [E]");
+    }
+
+    #[test]
+    fn interface_defaults_only_when_unambiguous() {
+        let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(sole_interface(&names(&["wlan0"])).unwrap(), "wlan0");
+
+        let none = sole_interface(&[]).unwrap_err();
+        assert_eq!(exit_code_for(&none), EXIT_NO_INTERFACE);
+        let several = sole_interface(&names(&["wlan0", "wlan1"])).unwrap_err();
+        assert!(several.to_string().contains("wlan0, wlan1"));
+        assert_eq!(exit_code_for(&several), EXIT_GENERIC);
+    }
+}
@@ -0,0 +1,628 @@
+//! Just enough rtnetlink and nl80211 to change links and scan without `ip` and `iw`
+//!
+//! Message building and parsing are always compiled (and tested); the sockets
+//! that use them come with the `netlink` feature.
+#![cfg_attr(not(feature = "netlink"), allow(dead_code))]
+
+use anyhow::{anyhow, Result};
+
+/// How long a request waits for the kernel to answer
+const REPLY_TIMEOUT_SECS: u64 = 5;
+
+const NLMSG_HDRLEN: usize = 16;
+const GENL_HDRLEN: usize = 4;
+const NLA_HDRLEN: usize = 4;
+/// Attribute type bits; the top two flag nesting and byte order
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+const CTRL_ATTR_MCAST_GROUPS: u16 = 7;
+const CTRL_ATTR_MCAST_GRP_NAME: u16 = 1;
+const CTRL_ATTR_MCAST_GRP_ID: u16 = 2;
+
+// From linux/nl80211.h
+const NL80211_CMD_SET_WIPHY: u8 = 2;
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_SET_INTERFACE: u8 = 6;
+const NL80211_CMD_GET_SCAN: u8 = 32;
+const NL80211_CMD_TRIGGER_SCAN: u8 = 33;
+const NL80211_CMD_NEW_SCAN_RESULTS: u8 = 34;
+const NL80211_CMD_SCAN_ABORTED: u8 = 35;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFTYPE: u16 = 5;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_SCAN_FREQUENCIES: u16 = 44;
+const NL80211_ATTR_SCAN_SSIDS: u16 = 45;
+const NL80211_ATTR_BSS: u16 = 47;
+const NL80211_ATTR_CHANNEL_WIDTH: u16 = 159;
+const NL80211_ATTR_CENTER_FREQ1: u16 = 160;
+const NL80211_BSS_BSSID: u16 = 1;
+const NL80211_BSS_FREQUENCY: u16 = 2;
+const NL80211_BSS_BEACON_INTERVAL: u16 = 4;
+const NL80211_BSS_CAPABILITY: u16 = 5;
+const NL80211_BSS_INFORMATION_ELEMENTS: u16 = 6;
+const NL80211_BSS_SIGNAL_MBM: u16 = 7;
+const NL80211_BSS_SEEN_MS_AGO: u16 = 10;
+
+/// nl80211 interface types, as `NL80211_ATTR_IFTYPE` takes them
+pub const IFTYPE_ADHOC: u32 = 1;
+pub const IFTYPE_STATION: u32 = 2;
+pub const IFTYPE_AP: u32 = 3;
+pub const IFTYPE_MONITOR: u32 = 6;
+
+/// A netlink request being built: header, family header, then attributes
+pub struct Request(Vec<u8>);
+
+impl Request {
+    /// Start a request of `msg_type`; NLM_F_REQUEST is added to `flags`
+    pub fn new(msg_type: u16, flags: u16, seq: u32) -> Self {
+        let mut msg = Vec::with_capacity(64);
+        // The length is filled in by `finish`
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        msg.extend_from_slice(&msg_type.to_ne_bytes());
+        msg.extend_from_slice(&(flags | libc::NLM_F_REQUEST as u16).to_ne_bytes());
+        msg.extend_from_slice(&seq.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        Self(msg)
+    }
+
+    /// Start a generic netlink request for command `cmd` of `family`
+    pub fn genl(family: u16, cmd: u8, flags: u16, seq: u32) -> Self {
+        let mut request = Self::new(family, flags, seq);
+        request.0.extend_from_slice(&[cmd, 1, 0, 0]);
+        request
+    }
+
+    pub fn attr(mut self, kind: u16, value: &[u8]) -> Self {
+        push_attr(&mut self.0, kind, value);
+        self
+    }
+
+    pub fn attr_u32(self, kind: u16, value: u32) -> Self {
+        self.attr(kind, &value.to_ne_bytes())
+    }
+
+    /// The message bytes, with the total length written into the header
+    pub fn finish(mut self) -> Vec<u8> {
+        let len = self.0.len() as u32;
+        self.0[..4].copy_from_slice(&len.to_ne_bytes());
+        self.0
+    }
+}
+
+/// Append one attribute, padded to 4 bytes
+fn push_attr(buf: &mut Vec<u8>, kind: u16, value: &[u8]) {
+    buf.extend_from_slice(&((NLA_HDRLEN + value.len()) as u16).to_ne_bytes());
+    buf.extend_from_slice(&kind.to_ne_bytes());
+    buf.extend_from_slice(value);
+    buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+/// A list of u32 values as nested attributes numbered from 0, as the kernel reads frequency lists
+fn nested_u32s(values: &[u32]) -> Vec<u8> {
+    let mut nested = Vec::with_capacity(values.len() * 8);
+    for (i, value) in values.iter().enumerate() {
+        push_attr(&mut nested, i as u16, &value.to_ne_bytes());
+    }
+    nested
+}
+
+/// One message from a datagram the kernel sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Message<'a> {
+    pub msg_type: u16,
+    pub seq: u32,
+    /// Everything after the netlink header
+    pub payload: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    /// Generic netlink command and attributes, if the payload is long enough to hold them
+    fn genl(&self) -> Option<(u8, &'a [u8])> {
+        Some((*self.payload.first()?, self.payload.get(GENL_HDRLEN..)?))
+    }
+}
+
+/// The messages packed into `datagram`, stopping at the first malformed one
+pub fn messages(datagram: &[u8]) -> impl Iterator<Item = Message<'_>> {
+    let mut rest = datagram;
+    std::iter::from_fn(move || {
+        let len = u32::from_ne_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+        if len < NLMSG_HDRLEN || len > rest.len() {
+            return None;
+        }
+        let message = Message {
+            msg_type: u16::from_ne_bytes(rest[4..6].try_into().ok()?),
+            seq: u32::from_ne_bytes(rest[8..12].try_into().ok()?),
+            payload: &rest[NLMSG_HDRLEN..len],
+        };
+        rest = rest.get(len.next_multiple_of(4)..).unwrap_or_default();
+        Some(message)
+    })
+}
+
+/// The (type, value) attributes in `data`, with the nesting flags cleared from the type
+pub fn attrs(data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        let len = u16::from_ne_bytes(rest.get(..2)?.try_into().ok()?) as usize;
+        if len < NLA_HDRLEN || len > rest.len() {
+            return None;
+        }
+        let kind = u16::from_ne_bytes(rest[2..4].try_into().ok()?) & NLA_TYPE_MASK;
+        let value = &rest[NLA_HDRLEN..len];
+        rest = rest.get(len.next_multiple_of(4)..).unwrap_or_default();
+        Some((kind, value))
+    })
+}
+
+fn attr_u32(value: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(value.get(..4)?.try_into().ok()?))
+}
+
+fn attr_u16(value: &[u8]) -> Option<u16> {
+    Some(u16::from_ne_bytes(value.get(..2)?.try_into().ok()?))
+}
+
+/// Outcome of request `seq` if `message` is its NLMSG_ERROR reply; an error code of 0 is the ack
+///
+/// `None` for anything else, such as a multicast event or the reply to an older request.
+pub fn ack_for(message: &Message, seq: u32) -> Option<Result<()>> {
+    if message.msg_type != libc::NLMSG_ERROR as u16 || message.seq != seq {
+        return None;
+    }
+    let errno = message.payload.get(..4).map(|code| i32::from_ne_bytes(code.try_into().unwrap_or_default()));
+    Some(match errno {
+        Some(0) => Ok(()),
+        Some(errno) => Err(anyhow!("{}", std::io::Error::from_raw_os_error(-errno))),
+        None => Err(anyhow!("Short netlink error reply")),
+    })
+}
+
+/// nl80211's family id and "scan" multicast group from a CTRL_CMD_NEWFAMILY reply
+fn parse_family(attributes: &[u8]) -> Option<(u16, Option<u32>)> {
+    let mut id = None;
+    let mut scan_group = None;
+    for (kind, value) in attrs(attributes) {
+        match kind {
+            CTRL_ATTR_FAMILY_ID => id = attr_u16(value),
+            CTRL_ATTR_MCAST_GROUPS => {
+                for (_, group) in attrs(value) {
+                    let mut name = None;
+                    let mut group_id = None;
+                    for (kind, value) in attrs(group) {
+                        match kind {
+                            CTRL_ATTR_MCAST_GRP_NAME => name = Some(value),
+                            CTRL_ATTR_MCAST_GRP_ID => group_id = attr_u32(value),
+                            _ => {}
+                        }
+                    }
+                    if name == Some(b"scan\0".as_slice()) {
+                        scan_group = group_id;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some((id?, scan_group))
+}
+
+/// One access point from an nl80211 scan dump, as the driver reported it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanBss {
+    pub bssid: [u8; 6],
+    /// MHz
+    pub frequency: u32,
+    /// Hundredths of a dBm
+    pub signal_mbm: Option<i32>,
+    pub beacon_interval_tu: Option<u16>,
+    /// The 802.11 capability field
+    pub capability: Option<u16>,
+    pub seen_ms_ago: Option<u32>,
+    /// Information elements from the latest beacon or probe response
+    pub ies: Vec<u8>,
+}
+
+/// The BSS in an NL80211_CMD_NEW_SCAN_RESULTS message, if it has a BSSID and frequency
+fn parse_scan_bss(attributes: &[u8]) -> Option<ScanBss> {
+    let (_, bss) = attrs(attributes).find(|(kind, _)| *kind == NL80211_ATTR_BSS)?;
+    let mut bssid = None;
+    let mut entry = ScanBss {
+        bssid: [0; 6],
+        frequency: 0,
+        signal_mbm: None,
+        beacon_interval_tu: None,
+        capability: None,
+        seen_ms_ago: None,
+        ies: Vec::new(),
+    };
+    for (kind, value) in attrs(bss) {
+        match kind {
+            NL80211_BSS_BSSID => bssid = value.try_into().ok(),
+            NL80211_BSS_FREQUENCY => entry.frequency = attr_u32(value)?,
+            NL80211_BSS_SIGNAL_MBM => entry.signal_mbm = attr_u32(value).map(|mbm| mbm as i32),
+            NL80211_BSS_BEACON_INTERVAL => entry.beacon_interval_tu = attr_u16(value),
+            NL80211_BSS_CAPABILITY => entry.capability = attr_u16(value),
+            NL80211_BSS_SEEN_MS_AGO => entry.seen_ms_ago = attr_u32(value),
+            NL80211_BSS_INFORMATION_ELEMENTS => entry.ies = value.to_vec(),
+            _ => {}
+        }
+    }
+    entry.bssid = bssid?;
+    (entry.frequency != 0).then_some(entry)
+}
+
+/// NL80211_CHAN_WIDTH_* for a channel `width_mhz` wide
+fn chan_width(width_mhz: u32) -> Result<u32> {
+    match width_mhz {
+        20 => Ok(1),
+        40 => Ok(2),
+        80 => Ok(3),
+        160 => Ok(5),
+        _ => Err(anyhow!("Unsupported channel width {} MHz", width_mhz)),
+    }
+}
+
+/// Account for a failed netlink attempt once the `ip`/`iw` fallback for `action` has run
+///
+/// The netlink error goes to the audit log either way, so a broken native path
+/// can't hide behind a working fallback, and is added to the fallback's error
+/// when that failed too.
+pub fn after_fallback<T>(action: &str, native: anyhow::Error, fallback: Result<T>) -> Result<T> {
+    crate::audit::audit("netlink", &format!("{} failed, fell back to the tools: {:#}", action, native));
+    fallback.map_err(|e| {
+        let message = format!("{} (over netlink: {:#})", e.to_string().trim_end(), native);
+        e.context(message)
+    })
+}
+
+/// A netlink socket that matches replies to requests by sequence number
+#[cfg(feature = "netlink")]
+pub struct Socket {
+    socket: netlink_sys::Socket,
+    seq: u32,
+}
+
+#[cfg(feature = "netlink")]
+impl Socket {
+    /// Connect to the kernel on `protocol` (e.g. `NETLINK_ROUTE`)
+    pub fn open(protocol: isize) -> Result<Self> {
+        let mut socket = netlink_sys::Socket::new(protocol)?;
+        socket.bind_auto()?;
+        socket.connect(&netlink_sys::SocketAddr::new(0, 0))?;
+        let socket = Self { socket, seq: 0 };
+        socket.set_timeout(std::time::Duration::from_secs(REPLY_TIMEOUT_SECS))?;
+        Ok(socket)
+    }
+
+    /// Give up on a read after `timeout`, so a lost reply can't hang the caller
+    pub fn set_timeout(&self, timeout: std::time::Duration) -> Result<()> {
+        use std::os::fd::AsRawFd;
+
+        let timeout = libc::timeval {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+        };
+        // SAFETY: `timeout` is a timeval of the length passed
+        let set = unsafe {
+            libc::setsockopt(
+                self.socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                (&timeout as *const libc::timeval).cast(),
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if set < 0 {
+            return Err(anyhow!("Failed to set netlink receive timeout: {}", std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    /// Receive multicast `group` messages alongside replies
+    pub fn join(&self, group: u32) -> Result<()> {
+        self.socket
+            .add_membership(group)
+            .map_err(|e| anyhow!("Failed to join netlink group {}: {}", group, e))
+    }
+
+    /// The next datagram from the kernel, or an error once the timeout passes
+    pub fn recv(&self) -> Result<Vec<u8>> {
+        match self.socket.recv_from_full() {
+            Ok((datagram, _)) => Ok(datagram),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Err(anyhow!("No reply from the kernel")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Send the request `build` makes for a fresh sequence number; returns that number
+    pub fn send(&mut self, build: impl FnOnce(u32) -> Vec<u8>) -> Result<u32> {
+        self.seq = self.seq.wrapping_add(1);
+        self.socket.send(&build(self.seq), 0)?;
+        Ok(self.seq)
+    }
+
+    /// Send a request that asks for an ack and wait for it, skipping unrelated messages
+    pub fn request_ack(&mut self, build: impl FnOnce(u32) -> Vec<u8>) -> Result<()> {
+        let seq = self.send(build)?;
+        loop {
+            let datagram = self.recv()?;
+            let outcome = messages(&datagram).find_map(|message| ack_for(&message, seq));
+            if let Some(outcome) = outcome {
+                return outcome;
+            }
+        }
+    }
+
+    /// Payloads of every reply to request `seq` up to NLMSG_DONE, failing on an error reply
+    fn collect(&self, seq: u32, dump: bool) -> Result<Vec<Vec<u8>>> {
+        let mut payloads = Vec::new();
+        loop {
+            let datagram = self.recv()?;
+            for message in messages(&datagram).filter(|m| m.seq == seq) {
+                if let Some(outcome) = ack_for(&message, seq) {
+                    outcome?;
+                    return Ok(payloads);
+                }
+                if message.msg_type == libc::NLMSG_DONE as u16 {
+                    return Ok(payloads);
+                }
+                payloads.push(message.payload.to_vec());
+                if !dump {
+                    return Ok(payloads);
+                }
+            }
+        }
+    }
+}
+
+/// An nl80211 (generic netlink) connection for changing and scanning wireless interfaces
+///
+/// Speaks to the kernel the way `iw` does, for the few commands sozin has ported.
+#[cfg(feature = "netlink")]
+pub struct Nl80211 {
+    socket: Socket,
+    family: u16,
+    scan_group: Option<u32>,
+}
+
+#[cfg(feature = "netlink")]
+impl Nl80211 {
+    /// Look up the nl80211 family; fails when no wireless driver has registered it
+    pub fn connect() -> Result<Self> {
+        let mut socket = Socket::open(netlink_sys::protocols::NETLINK_GENERIC)?;
+        let seq = socket.send(|seq| {
+            Request::genl(GENL_ID_CTRL, CTRL_CMD_GETFAMILY, 0, seq)
+                .attr(CTRL_ATTR_FAMILY_NAME, b"nl80211\0")
+                .finish()
+        })?;
+        let reply = socket
+            .collect(seq, false)
+            .map_err(|e| anyhow!("nl80211 isn't available: {}", e))?;
+        let (family, scan_group) = reply
+            .first()
+            .and_then(|payload| parse_family(payload.get(GENL_HDRLEN..)?))
+            .ok_or_else(|| anyhow!("Malformed nl80211 family reply"))?;
+        Ok(Self { socket, family, scan_group })
+    }
+
+    /// Switch interface `index` to an `IFTYPE_*`; like `iw set type`, the link must be down
+    pub fn set_interface_type(&mut self, index: u32, iftype: u32) -> Result<()> {
+        let family = self.family;
+        self.socket.request_ack(|seq| {
+            Request::genl(family, NL80211_CMD_SET_INTERFACE, libc::NLM_F_ACK as u16, seq)
+                .attr_u32(NL80211_ATTR_IFINDEX, index)
+                .attr_u32(NL80211_ATTR_IFTYPE, iftype)
+                .finish()
+        })
+    }
+
+    /// Current `IFTYPE_*` of interface `index`, as `iw dev <iface> info` shows it
+    pub fn interface_type(&mut self, index: u32) -> Result<u32> {
+        let family = self.family;
+        let seq = self.socket.send(|seq| {
+            Request::genl(family, NL80211_CMD_GET_INTERFACE, 0, seq)
+                .attr_u32(NL80211_ATTR_IFINDEX, index)
+                .finish()
+        })?;
+        self.socket
+            .collect(seq, false)?
+            .first()
+            .and_then(|payload| attrs(payload.get(GENL_HDRLEN..)?).find(|&(kind, _)| kind == NL80211_ATTR_IFTYPE))
+            .and_then(|(_, value)| attr_u32(value))
+            .ok_or_else(|| anyhow!("No interface type in nl80211 reply"))
+    }
+
+    /// Tune interface `index` to `freq`, `width_mhz` wide around `center_freq`
+    pub fn set_channel(&mut self, index: u32, freq: u32, width_mhz: u32, center_freq: u32) -> Result<()> {
+        let family = self.family;
+        let width = chan_width(width_mhz)?;
+        self.socket.request_ack(|seq| {
+            Request::genl(family, NL80211_CMD_SET_WIPHY, libc::NLM_F_ACK as u16, seq)
+                .attr_u32(NL80211_ATTR_IFINDEX, index)
+                .attr_u32(NL80211_ATTR_WIPHY_FREQ, freq)
+                .attr_u32(NL80211_ATTR_CHANNEL_WIDTH, width)
+                .attr_u32(NL80211_ATTR_CENTER_FREQ1, center_freq)
+                .finish()
+        })
+    }
+
+    /// Scan on interface `index` and return what the driver found
+    ///
+    /// Only `freqs` are scanned when given. Passive scans send no probe requests.
+    /// Waits up to `timeout` for the driver to finish.
+    pub fn scan(&mut self, index: u32, freqs: &[u32], passive: bool, timeout: std::time::Duration) -> Result<Vec<ScanBss>> {
+        let group = self.scan_group.ok_or_else(|| anyhow!("nl80211 has no scan event group"))?;
+        // Join before triggering so the completion event can't be missed
+        self.socket.join(group)?;
+
+        let family = self.family;
+        self.socket.request_ack(|seq| trigger_scan_request(family, index, freqs, passive, seq))?;
+
+        self.socket.set_timeout(timeout)?;
+        let started = std::time::Instant::now();
+        loop {
+            if started.elapsed() > timeout {
+                return Err(anyhow!("Scan timed out after {}s", timeout.as_secs()));
+            }
+            let datagram = self.socket.recv()?;
+            let event = messages(&datagram).find_map(|message| scan_event(&message, family, index));
+            match event {
+                Some(NL80211_CMD_NEW_SCAN_RESULTS) => break,
+                Some(_) => return Err(anyhow!("Scan aborted")),
+                None => {}
+            }
+        }
+        self.socket.set_timeout(std::time::Duration::from_secs(REPLY_TIMEOUT_SECS))?;
+
+        let seq = self.socket.send(|seq| {
+            Request::genl(family, NL80211_CMD_GET_SCAN, libc::NLM_F_DUMP as u16, seq)
+                .attr_u32(NL80211_ATTR_IFINDEX, index)
+                .finish()
+        })?;
+        Ok(self
+            .socket
+            .collect(seq, true)?
+            .iter()
+            .filter_map(|payload| parse_scan_bss(payload.get(GENL_HDRLEN..)?))
+            .collect())
+    }
+}
+
+/// NL80211_CMD_TRIGGER_SCAN for interface `index`; an active scan probes for any SSID
+fn trigger_scan_request(family: u16, index: u32, freqs: &[u32], passive: bool, seq: u32) -> Vec<u8> {
+    let mut request = Request::genl(family, NL80211_CMD_TRIGGER_SCAN, libc::NLM_F_ACK as u16, seq)
+        .attr_u32(NL80211_ATTR_IFINDEX, index);
+    if !freqs.is_empty() {
+        request = request.attr(NL80211_ATTR_SCAN_FREQUENCIES, &nested_u32s(freqs));
+    }
+    if !passive {
+        let mut wildcard = Vec::new();
+        push_attr(&mut wildcard, 1, &[]);
+        request = request.attr(NL80211_ATTR_SCAN_SSIDS, &wildcard);
+    }
+    request.finish()
+}
+
+/// The command of an nl80211 scan-finished or scan-aborted event for interface `index`
+fn scan_event(message: &Message, family: u16, index: u32) -> Option<u8> {
+    if message.msg_type != family {
+        return None;
+    }
+    let (cmd, attributes) = message.genl()?;
+    if cmd != NL80211_CMD_NEW_SCAN_RESULTS && cmd != NL80211_CMD_SCAN_ABORTED {
+        return None;
+    }
+    attrs(attributes)
+        .any(|(kind, value)| kind == NL80211_ATTR_IFINDEX && attr_u32(value) == Some(index))
+        .then_some(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reply datagram as the kernel would send it: one message of `msg_type` for `seq`
+    fn reply(msg_type: u16, seq: u32, payload: &[u8]) -> Vec<u8> {
+        let mut msg = Request::new(msg_type, 0, seq).finish();
+        msg.extend_from_slice(payload);
+        let len = msg.len() as u32;
+        msg[..4].copy_from_slice(&len.to_ne_bytes());
+        msg
+    }
+
+    #[test]
+    fn acks_only_match_their_own_request() {
+        let error = |errno: i32| {
+            let mut payload = errno.to_ne_bytes().to_vec();
+            payload.extend_from_slice(&[0; 16]);
+            payload
+        };
+        let ok = reply(libc::NLMSG_ERROR as u16, 7, &error(0));
+        let denied = reply(libc::NLMSG_ERROR as u16, 7, &error(-libc::EPERM));
+        let ack = |datagram: &[u8], seq| messages(datagram).find_map(|m| ack_for(&m, seq));
+
+        assert!(ack(&ok, 7).unwrap().is_ok());
+        assert!(ack(&denied, 7).unwrap().unwrap_err().to_string().contains("not permitted"));
+        // A stale ack for an earlier request isn't this one's
+        assert!(ack(&ok, 8).is_none());
+        // A multicast event in front of the ack is skipped
+        let mut both = reply(28, 0, &[NL80211_CMD_TRIGGER_SCAN, 1, 0, 0]);
+        both.extend_from_slice(&ok);
+        assert!(ack(&both, 7).unwrap().is_ok());
+        assert!(ack(&ok[..8], 7).is_none());
+    }
+
+    #[test]
+    fn requests_pad_attributes_and_nest_frequencies() {
+        let msg = trigger_scan_request(28, 3, &[2412, 5955], false, 9);
+        let message = messages(&msg).next().unwrap();
+        assert_eq!((message.msg_type, message.seq), (28, 9));
+        let (cmd, attributes) = message.genl().unwrap();
+        assert_eq!(cmd, NL80211_CMD_TRIGGER_SCAN);
+
+        let found: Vec<(u16, &[u8])> = attrs(attributes).collect();
+        assert_eq!(found[0], (NL80211_ATTR_IFINDEX, 3u32.to_ne_bytes().as_slice()));
+        assert_eq!(found[1].0, NL80211_ATTR_SCAN_FREQUENCIES);
+        let freqs: Vec<u32> = attrs(found[1].1).filter_map(|(_, v)| attr_u32(v)).collect();
+        assert_eq!(freqs, [2412, 5955]);
+        assert_eq!(found[2].0, NL80211_ATTR_SCAN_SSIDS);
+        assert_eq!(found.len(), 3);
+
+        // Passive scans carry no SSID list
+        let passive = trigger_scan_request(28, 3, &[], true, 10);
+        assert_eq!(attrs(messages(&passive).next().unwrap().genl().unwrap().1).count(), 1);
+        assert!(chan_width(30).is_err());
+    }
+
+    #[test]
+    fn family_and_scan_results_are_read_from_attributes() {
+        let mut group = Vec::new();
+        push_attr(&mut group, CTRL_ATTR_MCAST_GRP_ID, &6u32.to_ne_bytes());
+        push_attr(&mut group, CTRL_ATTR_MCAST_GRP_NAME, b"scan\0");
+        let mut groups = Vec::new();
+        push_attr(&mut groups, 1, &group);
+        let mut family = Vec::new();
+        push_attr(&mut family, CTRL_ATTR_FAMILY_ID, &28u16.to_ne_bytes());
+        push_attr(&mut family, CTRL_ATTR_MCAST_GROUPS, &groups);
+        assert_eq!(parse_family(&family), Some((28, Some(6))));
+
+        let mut bss = Vec::new();
+        push_attr(&mut bss, NL80211_BSS_BSSID, &[0, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        push_attr(&mut bss, NL80211_BSS_FREQUENCY, &5955u32.to_ne_bytes());
+        push_attr(&mut bss, NL80211_BSS_SIGNAL_MBM, &(-4700i32).to_ne_bytes());
+        push_attr(&mut bss, NL80211_BSS_INFORMATION_ELEMENTS, &[0, 3, b'L', b'a', b'b']);
+        let mut result = Vec::new();
+        push_attr(&mut result, NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes());
+        push_attr(&mut result, NL80211_ATTR_BSS, &bss);
+
+        let entry = parse_scan_bss(&result).unwrap();
+        assert_eq!(entry.bssid, [0, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!((entry.frequency, entry.signal_mbm), (5955, Some(-4700)));
+        assert_eq!(entry.ies, [0, 3, b'L', b'a', b'b']);
+
+        let mut event = vec![NL80211_CMD_NEW_SCAN_RESULTS, 1, 0, 0];
+        event.extend_from_slice(&result);
+        let datagram = reply(28, 0, &event);
+        let message = messages(&datagram).next().unwrap();
+        assert_eq!(scan_event(&message, 28, 3), Some(NL80211_CMD_NEW_SCAN_RESULTS));
+        assert_eq!(scan_event(&message, 28, 4), None);
+    }
+
+    #[test]
+    fn fallback_errors_keep_the_netlink_error() {
+        let native = anyhow!("Failed to set channel: Device or resource busy (os error 16)");
+        assert_eq!(after_fallback("channel", native, Ok(5)).unwrap(), 5);
+
+        let native = anyhow!("Failed to set channel: Device or resource busy (os error 16)");
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let err = after_fallback::<()>("channel", native, Err(missing.into())).unwrap_err();
+        assert!(err.to_string().ends_with("(over netlink: Failed to set channel: Device or resource busy (os error 16))"));
+        // The tool's own error stays in the chain, e.g. for picking an exit code
+        assert!(err.chain().any(|cause| cause.downcast_ref::<std::io::Error>().is_some()));
+    }
+}
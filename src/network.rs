@@ -1,19 +1,74 @@
+use crate::audit::audit;
+use crate::runner::{CommandRunner, SystemRunner};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::Command;
-use tokio::process::Command as AsyncCommand;
+use std::sync::{Mutex, OnceLock};
 
 /// Network interface information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInterface {
     pub name: String,
+    /// Lower device of a stacked interface such as a VLAN (`eth0.100@eth0`)
+    pub parent: Option<String>,
     pub mac_address: Option<String>,
+    /// Factory address, only reported while `mac_address` differs from it (iproute2's `permaddr`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permanent_mac: Option<String>,
     pub ip_address: Option<String>,
     pub state: InterfaceState,
     pub interface_type: InterfaceType,
     pub driver: Option<String>,
+    pub mtu: Option<u32>,
+    pub link_speed: Option<LinkSpeed>,
+    /// The user's note for this adapter (`notes.toml`, by `hardware_mac`), else the model udev reports
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
+impl NetworkInterface {
+    /// Address that identifies the adapter itself: the factory one, even while the MAC is changed
+    pub fn hardware_mac(&self) -> Option<&str> {
+        self.permanent_mac.as_deref().or(self.mac_address.as_deref())
+    }
+}
+
+/// Negotiated Ethernet link speed and duplex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkSpeed {
+    pub mbps: u32,
+    pub duplex: Duplex,
+}
+
+impl std::fmt::Display for LinkSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} Mbps {}", self.mbps, self.duplex)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Duplex {
+    Full,
+    Half,
+    Unknown,
+}
+
+impl std::fmt::Display for Duplex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Duplex::Full => write!(f, "full-duplex"),
+            Duplex::Half => write!(f, "half-duplex"),
+            Duplex::Unknown => write!(f, "unknown duplex"),
+        }
+    }
+}
+
+/// Smallest MTU accepted by `set_mtu` (IPv4 minimum)
+pub const MIN_MTU: u32 = 68;
+/// Largest MTU accepted by `set_mtu` (common jumbo frame ceiling)
+pub const MAX_MTU: u32 = 9216;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InterfaceState {
     Up,
@@ -52,7 +107,24 @@ impl std::fmt::Display for InterfaceType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Icon shown before an interface in `list` and the TUI; `ascii` swaps the emoji for a
+/// bracketed letter that every terminal and screen reader can handle
+pub fn type_icon(t: InterfaceType, ascii: bool) -> &'static str {
+    match (t, ascii) {
+        (InterfaceType::Wireless, false) => "📶",
+        (InterfaceType::Ethernet, false) => "🔌",
+        (InterfaceType::Loopback, false) => "🔄",
+        (InterfaceType::Virtual, false) => "🌐",
+        (InterfaceType::Unknown, false) => "❓",
+        (InterfaceType::Wireless, true) => "[W]",
+        (InterfaceType::Ethernet, true) => "[E]",
+        (InterfaceType::Loopback, true) => "[L]",
+        (InterfaceType::Virtual, true) => "[V]",
+        (InterfaceType::Unknown, true) => "[?]",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WirelessMode {
     Managed,
     Monitor,
@@ -73,6 +145,242 @@ impl std::fmt::Display for WirelessMode {
     }
 }
 
+impl std::str::FromStr for WirelessMode {
+    type Err = String;
+
+    /// Accepts the names iw and iwconfig use: managed/station, monitor, master/ap, adhoc/ibss
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "managed" | "station" | "sta" => Ok(WirelessMode::Managed),
+            "monitor" => Ok(WirelessMode::Monitor),
+            "master" | "ap" => Ok(WirelessMode::Master),
+            "adhoc" | "ad-hoc" | "ibss" => Ok(WirelessMode::Adhoc),
+            _ => Err(format!("unknown mode '{}': expected managed, monitor, master or adhoc", s)),
+        }
+    }
+}
+
+impl WirelessMode {
+    /// Argument for `iw dev <iface> set type`, `None` for `Unknown`
+    pub fn iw_type(self) -> Option<&'static str> {
+        match self {
+            WirelessMode::Managed => Some("managed"),
+            WirelessMode::Monitor => Some("monitor"),
+            WirelessMode::Master => Some("__ap"),
+            WirelessMode::Adhoc => Some("ibss"),
+            WirelessMode::Unknown => None,
+        }
+    }
+
+    /// `NL80211_ATTR_IFTYPE` value for this mode, `None` for `Unknown`
+    #[cfg_attr(not(feature = "netlink"), allow(dead_code))]
+    fn nl80211_iftype(self) -> Option<u32> {
+        use crate::netlink::{IFTYPE_ADHOC, IFTYPE_AP, IFTYPE_MONITOR, IFTYPE_STATION};
+        match self {
+            WirelessMode::Managed => Some(IFTYPE_STATION),
+            WirelessMode::Monitor => Some(IFTYPE_MONITOR),
+            WirelessMode::Master => Some(IFTYPE_AP),
+            WirelessMode::Adhoc => Some(IFTYPE_ADHOC),
+            WirelessMode::Unknown => None,
+        }
+    }
+
+    /// How `iw phy` lists this mode under "Supported interface modes"
+    fn phy_mode_name(self) -> &'static str {
+        match self {
+            WirelessMode::Managed => "managed",
+            WirelessMode::Monitor => "monitor",
+            WirelessMode::Master => "AP",
+            WirelessMode::Adhoc => "IBSS",
+            WirelessMode::Unknown => "",
+        }
+    }
+}
+
+/// Channel width for `set_channel`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelWidth {
+    #[default]
+    Ht20,
+    /// 40 MHz with the secondary channel above the primary
+    Ht40Plus,
+    /// 40 MHz with the secondary channel below the primary
+    Ht40Minus,
+    Mhz80,
+    Mhz160,
+}
+
+impl std::fmt::Display for ChannelWidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelWidth::Ht20 => write!(f, "HT20"),
+            ChannelWidth::Ht40Plus => write!(f, "HT40+"),
+            ChannelWidth::Ht40Minus => write!(f, "HT40-"),
+            ChannelWidth::Mhz80 => write!(f, "80MHz"),
+            ChannelWidth::Mhz160 => write!(f, "160MHz"),
+        }
+    }
+}
+
+impl std::str::FromStr for ChannelWidth {
+    type Err = String;
+
+    /// Accepts iw's names: HT20, HT40+, HT40-, 80MHz, 160MHz (the MHz suffix is optional)
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "HT20" | "20" | "20MHZ" => Ok(ChannelWidth::Ht20),
+            "HT40+" => Ok(ChannelWidth::Ht40Plus),
+            "HT40-" => Ok(ChannelWidth::Ht40Minus),
+            "80" | "80MHZ" => Ok(ChannelWidth::Mhz80),
+            "160" | "160MHZ" => Ok(ChannelWidth::Mhz160),
+            _ => Err(format!("unknown width '{}': expected HT20, HT40+, HT40-, 80MHz or 160MHz", s)),
+        }
+    }
+}
+
+/// First 5 GHz channel of each 40 MHz block
+const BLOCKS_40MHZ: [u32; 12] = [36, 44, 52, 60, 100, 108, 116, 124, 132, 140, 149, 157];
+/// First 5 GHz channel and centre channel of each 80 MHz block
+const BLOCKS_80MHZ: [(u32, u32); 6] = [(36, 42), (52, 58), (100, 106), (116, 122), (132, 138), (149, 155)];
+/// First 5 GHz channel and centre channel of each 160 MHz block
+const BLOCKS_160MHZ: [(u32, u32); 2] = [(36, 50), (100, 114)];
+
+/// Current association of a managed wireless interface (from `iw dev <iface> link`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub bssid: String,
+    pub ssid: Option<String>,
+    pub frequency: Option<u32>,
+    pub signal_dbm: Option<i32>,
+    pub tx_bitrate: Option<String>,
+}
+
+/// Link quality of an associated wireless interface (from `/proc/net/wireless`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkQuality {
+    /// Driver-defined quality value, usually out of 70
+    pub link: u32,
+    pub level_dbm: i32,
+    /// `None` when the driver doesn't report noise
+    pub noise_dbm: Option<i32>,
+}
+
+/// Kernel packet and byte counters for an interface
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// Which service configures an interface, and so may undo manual changes to it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ManagementBackend {
+    NetworkManager,
+    SystemdNetworkd,
+    /// Neither service claims it
+    Unmanaged,
+    /// Neither nmcli nor networkctl could be asked
+    #[default]
+    Unknown,
+}
+
+impl std::fmt::Display for ManagementBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManagementBackend::NetworkManager => write!(f, "NetworkManager"),
+            ManagementBackend::SystemdNetworkd => write!(f, "systemd-networkd"),
+            ManagementBackend::Unmanaged => write!(f, "unmanaged"),
+            ManagementBackend::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Everything known about a single interface, as reported by `sozin info`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceInfo {
+    #[serde(flatten)]
+    pub interface: NetworkInterface,
+    pub ip_addresses: Vec<String>,
+    pub wireless_mode: Option<WirelessMode>,
+    pub connection: Option<ConnectionInfo>,
+    pub supported_channels: Vec<u32>,
+    pub stats: Option<InterfaceStats>,
+    #[serde(default)]
+    pub management: ManagementBackend,
+}
+
+/// A connection profile stored by NetworkManager
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SavedConnection {
+    pub name: String,
+    pub uuid: String,
+    /// Profile type, e.g. 802-11-wireless or 802-3-ethernet
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Interface the profile is active on, if any
+    pub device: Option<String>,
+}
+
+/// A radio known to the kernel's rfkill subsystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RfkillEntry {
+    pub id: u32,
+    /// rfkill type name: wlan, bluetooth, wwan...
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Kernel device, e.g. phy0 or hci0
+    pub device: String,
+    /// Blocked in software; `rfkill unblock` clears it
+    pub soft_blocked: bool,
+    /// Blocked by a hardware switch or firmware; software can't clear it
+    pub hard_blocked: bool,
+}
+
+/// Frames heard on one channel by `sample_channel_activity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ChannelActivity {
+    pub channel: u32,
+    pub frames: u64,
+    /// Share of all frames counted in the sweep, 0-100
+    pub percent: u8,
+}
+
+/// Listening time per channel for `sample_channel_activity`
+pub const ACTIVITY_DWELL_MS: u64 = 500;
+
+/// How long `reload_driver` waits for the interface to reappear
+pub const DRIVER_RELOAD_TIMEOUT_SECS: u64 = 10;
+
+/// Root-only directory for the hostapd config (it holds the passphrase) and pid file
+const AP_STATE_DIR: &str = "/run/sozin";
+
+/// Where wpa_supplicant puts its per-interface control sockets, newest layout first
+const WPA_CTRL_DIRS: [&str; 2] = ["/run/wpa_supplicant", "/var/run/wpa_supplicant"];
+
+/// Where `spoof_mac` recorded the new address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacPersistence {
+    /// Link only; NetworkManager may put the old address back on reconnect
+    Runtime,
+    /// Also stored as the cloned MAC of this NetworkManager profile
+    NetworkManager(String),
+}
+
+/// One step of `reset_interface` and how it went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResetStep {
+    pub description: String,
+    /// `Err` holds the failure message; later steps still run
+    pub outcome: std::result::Result<(), String>,
+}
+
 /// Network manager for interface operations
 pub struct NetworkManager;
 
@@ -89,9 +397,13 @@ impl NetworkManager {
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let mut interfaces = Vec::new();
+        let notes = crate::store::load_notes();
 
         for line in stdout.lines() {
-            if let Some(iface) = Self::parse_interface_line(line) {
+            if let Some(mut iface) = Self::parse_interface_line(line) {
+                if let Some(mac) = iface.hardware_mac().map(str::to_lowercase) {
+                    iface.description = notes.get(&mac).cloned().or_else(|| Self::cached_udev_description(&mac, &iface.name));
+                }
                 interfaces.push(iface);
             }
         }
@@ -99,6 +411,47 @@ impl NetworkManager {
         Ok(interfaces)
     }
 
+    /// `get_udev_description`, asked once per adapter (by hardware MAC) for the life of the process
+    fn cached_udev_description(mac: &str, name: &str) -> Option<String> {
+        static DESCRIPTIONS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+        let mut cache = DESCRIPTIONS.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .entry(mac.to_string())
+            .or_insert_with(|| Self::get_udev_description(name))
+            .clone()
+    }
+
+    /// Vendor and model of the device behind an interface, as udev knows it
+    fn get_udev_description(name: &str) -> Option<String> {
+        let output = Command::new("udevadm")
+            .args(["info", "--query=property", &format!("--path=/sys/class/net/{}", name)])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| Self::parse_udev_description(&String::from_utf8_lossy(&output.stdout)))
+            .flatten()
+    }
+
+    /// "Vendor Model" from `udevadm info --query=property` output
+    ///
+    /// Prefers the names from the hardware database over the raw USB strings, which
+    /// use underscores for spaces. Virtual devices have neither.
+    fn parse_udev_description(output: &str) -> Option<String> {
+        let property = |key: &str| {
+            output.lines().find_map(|line| {
+                let value = line.strip_prefix(key)?.strip_prefix('=')?.trim();
+                (!value.is_empty()).then(|| value.replace('_', " "))
+            })
+        };
+        let model = property("ID_MODEL_FROM_DATABASE").or_else(|| property("ID_MODEL"))?;
+        match property("ID_VENDOR_FROM_DATABASE").or_else(|| property("ID_VENDOR")) {
+            Some(vendor) => Some(format!("{} {}", vendor, model)),
+            None => Some(model),
+        }
+    }
+
     /// Get wireless interfaces only
     pub fn get_wireless_interfaces() -> Result<Vec<NetworkInterface>> {
         let interfaces = Self::get_interfaces()?;
@@ -108,6 +461,28 @@ impl NetworkManager {
             .collect())
     }
 
+    /// Interfaces grouped by type (wireless, ethernet, virtual, loopback, unknown), each sorted by name
+    ///
+    /// Types with no interfaces are left out.
+    pub fn group_by_type(interfaces: Vec<NetworkInterface>) -> Vec<(InterfaceType, Vec<NetworkInterface>)> {
+        let order = [
+            InterfaceType::Wireless,
+            InterfaceType::Ethernet,
+            InterfaceType::Virtual,
+            InterfaceType::Loopback,
+            InterfaceType::Unknown,
+        ];
+        order
+            .into_iter()
+            .filter_map(|kind| {
+                let mut group: Vec<NetworkInterface> =
+                    interfaces.iter().filter(|i| i.interface_type == kind).cloned().collect();
+                group.sort_by(|a, b| a.name.cmp(&b.name));
+                (!group.is_empty()).then_some((kind, group))
+            })
+            .collect()
+    }
+
     fn parse_interface_line(line: &str) -> Option<NetworkInterface> {
         let parts: Vec<&str> = line.split_whitespace().collect();
         if parts.len() < 2 {
@@ -115,17 +490,23 @@ impl NetworkManager {
         }
 
         // Extract interface name (remove trailing colon)
-        let name = parts[1].trim_end_matches(':').to_string();
+        let (name, parent) = Self::split_link_name(parts[1].trim_end_matches(':'));
+        let mtu = Self::parse_mtu(line);
         
         // Skip loopback for most operations
         if name == "lo" {
             return Some(NetworkInterface {
                 name,
+                parent,
                 mac_address: None,
                 ip_address: None,
                 state: InterfaceState::Up,
                 interface_type: InterfaceType::Loopback,
                 driver: None,
+                mtu,
+                link_speed: None,
+                permanent_mac: None,
+                description: None,
             });
         }
 
@@ -150,17 +531,56 @@ impl NetworkManager {
         // Get driver info
         let driver = Self::get_driver(&name);
 
+        // Link speed only means something for wired ports
+        let link_speed = if interface_type == InterfaceType::Ethernet {
+            Self::get_link_speed(&name)
+        } else {
+            None
+        };
+
         Some(NetworkInterface {
             name,
+            parent,
             mac_address,
             ip_address,
             state,
             interface_type,
             driver,
+            mtu,
+            link_speed,
+            permanent_mac: Self::parse_permaddr(line),
+            description: None,
+        })
+    }
+
+    /// Split `ip link`'s `name@parent` form; `@NONE` means there is no parent
+    fn split_link_name(field: &str) -> (String, Option<String>) {
+        match field.split_once('@') {
+            Some((name, "NONE")) => (name.to_string(), None),
+            Some((name, parent)) => (name.to_string(), Some(parent.to_string())),
+            None => (field.to_string(), None),
+        }
+    }
+
+    /// Extract the value following the `mtu` token of an `ip -o link` line
+    fn parse_mtu(line: &str) -> Option<u32> {
+        let mut tokens = line.split_whitespace();
+        tokens.find(|t| *t == "mtu")?;
+        tokens.next()?.parse().ok()
+    }
+
+    /// Operational state from sysfs, without spawning `ip`; `None` if there is no such interface
+    pub fn get_interface_state(name: &str) -> Option<InterfaceState> {
+        let operstate = std::fs::read_to_string(format!("/sys/class/net/{}/operstate", name)).ok()?;
+        Some(match operstate.trim() {
+            "up" => InterfaceState::Up,
+            "down" | "lowerlayerdown" => InterfaceState::Down,
+            _ => InterfaceState::Unknown,
         })
     }
 
-    fn detect_interface_type(name: &str) -> InterfaceType {
+    /// Guess an interface's type from sysfs and its name
+    pub fn detect_interface_type(name: &str) -> InterfaceType {
         // Check if wireless by looking at /sys/class/net/<iface>/wireless
         let wireless_path = format!("/sys/class/net/{}/wireless", name);
         if std::path::Path::new(&wireless_path).exists() {
@@ -180,6 +600,11 @@ impl NetworkManager {
             return InterfaceType::Virtual;
         }
 
+        // Bridges expose a bridge/ directory whatever they are called
+        if std::path::Path::new(&format!("/sys/class/net/{}/bridge", name)).exists() {
+            return InterfaceType::Virtual;
+        }
+
         InterfaceType::Unknown
     }
 
@@ -208,6 +633,23 @@ impl NetworkManager {
         None
     }
 
+    /// Get all IPv4 and IPv6 addresses (with prefix length) assigned to an interface
+    pub fn get_ip_addresses(name: &str) -> Vec<String> {
+        let output = match Command::new("ip").args(["-o", "addr", "show", "dev", name]).output() {
+            Ok(output) => output,
+            Err(_) => return Vec::new(),
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut tokens = line.split_whitespace();
+                tokens.find(|t| *t == "inet" || *t == "inet6")?;
+                tokens.next().map(str::to_string)
+            })
+            .collect()
+    }
+
     fn get_driver(name: &str) -> Option<String> {
         let path = format!("/sys/class/net/{}/device/driver", name);
         std::fs::read_link(&path)
@@ -215,203 +657,2030 @@ impl NetworkManager {
             .and_then(|p| p.file_name().map(|s| s.to_string_lossy().to_string()))
     }
 
-    /// Get current wireless mode
-    pub fn get_wireless_mode(interface: &str) -> Result<WirelessMode> {
-        let output = Command::new("iw")
-            .args(["dev", interface, "info"])
-            .output()?;
+    /// Get negotiated link speed and duplex, or `None` when there is no link
+    pub fn get_link_speed(name: &str) -> Option<LinkSpeed> {
+        let speed = std::fs::read_to_string(format!("/sys/class/net/{}/speed", name)).ok()?;
+        let duplex = std::fs::read_to_string(format!("/sys/class/net/{}/duplex", name)).ok();
+        Self::parse_link_speed(&speed, duplex.as_deref())
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        
-        for line in stdout.lines() {
-            if line.contains("type") {
-                if line.contains("monitor") {
-                    return Ok(WirelessMode::Monitor);
-                } else if line.contains("managed") {
-                    return Ok(WirelessMode::Managed);
-                } else if line.contains("AP") || line.contains("master") {
-                    return Ok(WirelessMode::Master);
-                } else if line.contains("IBSS") || line.contains("ad-hoc") {
-                    return Ok(WirelessMode::Adhoc);
-                }
-            }
+    fn parse_link_speed(speed: &str, duplex: Option<&str>) -> Option<LinkSpeed> {
+        // The kernel reports -1 (or SPEED_UNKNOWN) when the carrier is down
+        let mbps: i64 = speed.trim().parse().ok()?;
+        if mbps <= 0 || mbps >= u32::MAX as i64 {
+            return None;
         }
 
-        Ok(WirelessMode::Unknown)
+        let duplex = match duplex.map(str::trim) {
+            Some("full") => Duplex::Full,
+            Some("half") => Duplex::Half,
+            _ => Duplex::Unknown,
+        };
+
+        Some(LinkSpeed {
+            mbps: mbps as u32,
+            duplex,
+        })
     }
 
-    /// Enable monitor mode on interface
-    pub async fn enable_monitor_mode(interface: &str) -> Result<()> {
-        // Bring interface down
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "down"])
-            .output()
-            .await?;
+    /// Get association details for a wireless interface, or `None` when not connected
+    pub fn get_connection_info(name: &str) -> Option<ConnectionInfo> {
+        let output = Command::new("iw").args(["dev", name, "link"]).output().ok()?;
+        Self::parse_connection_info(&String::from_utf8_lossy(&output.stdout))
+    }
 
-        // Set monitor mode
-        let output = AsyncCommand::new("iw")
-            .args(["dev", interface, "set", "type", "monitor"])
-            .output()
-            .await?;
+    fn parse_connection_info(output: &str) -> Option<ConnectionInfo> {
+        let mut lines = output.lines().map(str::trim);
+        let bssid = lines
+            .next()?
+            .strip_prefix("Connected to ")?
+            .split_whitespace()
+            .next()?
+            .to_string();
+
+        let mut info = ConnectionInfo {
+            bssid,
+            ssid: None,
+            frequency: None,
+            signal_dbm: None,
+            tx_bitrate: None,
+        };
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to set monitor mode: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+        for line in lines {
+            if let Some(ssid) = line.strip_prefix("SSID:") {
+                info.ssid = Some(ssid.trim().to_string());
+            } else if let Some(freq) = line.strip_prefix("freq:") {
+                // Newer iw prints fractional MHz ("2437.0")
+                info.frequency = freq.trim().parse::<f64>().ok().map(|f| f as u32);
+            } else if let Some(signal) = line.strip_prefix("signal:") {
+                info.signal_dbm = signal.split_whitespace().next().and_then(|v| v.parse().ok());
+            } else if let Some(rate) = line.strip_prefix("tx bitrate:") {
+                info.tx_bitrate = Some(rate.trim().to_string());
+            }
         }
 
-        // Bring interface up
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "up"])
-            .output()
-            .await?;
+        Some(info)
+    }
 
-        Ok(())
+    /// Read link quality, signal and noise from `/proc/net/wireless`
+    ///
+    /// `None` when the interface is not listed, e.g. because it is not associated.
+    pub fn get_link_quality(interface: &str) -> Option<LinkQuality> {
+        let contents = std::fs::read_to_string("/proc/net/wireless").ok()?;
+        Self::parse_link_quality(&contents, interface)
     }
 
-    /// Disable monitor mode (set to managed)
-    pub async fn disable_monitor_mode(interface: &str) -> Result<()> {
-        // Bring interface down
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "down"])
-            .output()
-            .await?;
+    fn parse_link_quality(contents: &str, interface: &str) -> Option<LinkQuality> {
+        // Two header lines, then "wlan0: 0000   70.  -40.  -256        0 ..."
+        let fields: Vec<&str> = contents.lines().skip(2).find_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            (name.trim() == interface).then(|| rest.split_whitespace().collect())
+        })?;
+        let value = |i: usize| -> Option<i32> { fields.get(i)?.trim_end_matches('.').parse().ok() };
+
+        let link = value(1)?;
+        let mut level_dbm = value(2)?;
+        // Some drivers still report the old unsigned 8-bit form
+        if level_dbm > 0 {
+            level_dbm -= 256;
+        }
+        // -256 (and 0) are the "not reported" values
+        let noise_dbm = value(3).filter(|n| *n != -256 && *n != 0);
 
-        // Set managed mode
-        let output = AsyncCommand::new("iw")
-            .args(["dev", interface, "set", "type", "managed"])
-            .output()
-            .await?;
+        Some(LinkQuality {
+            link: u32::try_from(link).ok()?,
+            level_dbm,
+            noise_dbm,
+        })
+    }
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to set managed mode: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+    /// Name of the radio (phy0, phy1, ...) behind a wireless interface
+    ///
+    /// Several interfaces can share one radio, e.g. wlan0 and a monitor VAP on it.
+    pub fn get_phy_for_interface(interface: &str) -> Option<String> {
+        Self::phy_for_interface_in(std::path::Path::new("/sys/class/net"), interface)
+    }
+
+    /// `get_phy_for_interface` reading from `sys_class_net` instead of `/sys/class/net`
+    fn phy_for_interface_in(sys_class_net: &std::path::Path, interface: &str) -> Option<String> {
+        std::fs::read_to_string(sys_class_net.join(interface).join("phy80211/name"))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Leading `iw` arguments for a radio-wide command: `phy <phy>` when known, else `dev <interface>`
+    fn radio_target(phy: Option<&str>, interface: &str) -> [String; 2] {
+        match phy {
+            Some(phy) => ["phy".to_string(), phy.to_string()],
+            None => ["dev".to_string(), interface.to_string()],
         }
+    }
 
-        // Bring interface up
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "up"])
-            .output()
-            .await?;
+    /// Get channels the interface's radio can use in the current regulatory domain
+    pub fn get_supported_channels(name: &str) -> Vec<u32> {
+        let Some(phy) = Self::get_phy_for_interface(name) else {
+            return Vec::new();
+        };
+        match Command::new("iw").args(["phy", &phy, "info"]).output() {
+            Ok(output) => Self::parse_supported_channels(&String::from_utf8_lossy(&output.stdout)),
+            Err(_) => Vec::new(),
+        }
+    }
 
-        Ok(())
+    /// Names of all wireless radios (phy0, phy1, ...)
+    pub fn list_phys() -> Vec<String> {
+        let mut phys: Vec<String> = std::fs::read_dir("/sys/class/ieee80211")
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.file_name().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        phys.sort();
+        phys
     }
 
-    /// Bring interface up
-    pub async fn bring_up(interface: &str) -> Result<()> {
-        let output = AsyncCommand::new("ip")
-            .args(["link", "set", interface, "up"])
-            .output()
-            .await?;
+    /// Raw `iw phy <phy> info` output
+    pub fn get_phy_info(phy: &str) -> Option<String> {
+        let output = Command::new("iw").args(["phy", phy, "info"]).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to bring up interface: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
+    /// Interface types listed under "Supported interface modes:" in `iw phy` output
+    pub fn parse_supported_modes(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| *line != "Supported interface modes:")
+            .skip(1)
+            .map_while(|line| line.strip_prefix("* "))
+            .map(|mode| mode.trim().to_string())
+            .collect()
+    }
 
-        Ok(())
+    pub fn parse_supported_channels(output: &str) -> Vec<u32> {
+        let mut channels: Vec<u32> = Self::enabled_frequency_lines(output)
+            .filter_map(|line| {
+                let start = line.find('[')? + 1;
+                let end = line[start..].find(']')? + start;
+                line[start..end].parse().ok()
+            })
+            .collect();
+        channels.sort_unstable();
+        channels.dedup();
+        channels
     }
 
-    /// Bring interface down
-    pub async fn bring_down(interface: &str) -> Result<()> {
-        let output = AsyncCommand::new("ip")
-            .args(["link", "set", interface, "down"])
-            .output()
-            .await?;
+    /// Frequencies (MHz) an interface's radio can currently use
+    pub fn get_supported_frequencies(name: &str) -> Vec<u32> {
+        Self::get_phy_for_interface(name)
+            .and_then(|phy| Self::get_phy_info(&phy))
+            .map(|info| Self::parse_supported_frequencies(&info))
+            .unwrap_or_default()
+    }
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to bring down interface: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
+    /// Enabled frequencies from `iw phy <phy> info` output
+    ///
+    /// Unlike channel numbers these are unambiguous across bands (6 GHz reuses 1, 5, 9...).
+    pub fn parse_supported_frequencies(output: &str) -> Vec<u32> {
+        let mut freqs: Vec<u32> = Self::enabled_frequency_lines(output)
+            .filter_map(|line| line[2..].split_whitespace().next()?.parse::<f32>().ok())
+            .map(|mhz| mhz as u32)
+            .collect();
+        freqs.sort_unstable();
+        freqs.dedup();
+        freqs
+    }
 
-        Ok(())
+    fn enabled_frequency_lines(output: &str) -> impl Iterator<Item = &str> {
+        // Frequency lines look like "* 2412 MHz [1] (20.0 dBm)" or "* 5600 MHz [120] (disabled)"
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("* ") && line.contains(" MHz [") && !line.contains("(disabled)"))
     }
 
-    /// Rename interface
-    pub async fn rename_interface(interface: &str, new_name: &str) -> Result<()> {
-        // Bring interface down first
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "down"])
-            .output()
-            .await?;
+    /// Read kernel counters from sysfs
+    pub fn get_interface_stats(name: &str) -> Option<InterfaceStats> {
+        let read = |counter: &str| -> Option<u64> {
+            std::fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", name, counter))
+                .ok()?
+                .trim()
+                .parse()
+                .ok()
+        };
 
-        // Rename
-        let output = AsyncCommand::new("ip")
-            .args(["link", "set", interface, "name", new_name])
-            .output()
-            .await?;
+        Some(InterfaceStats {
+            rx_bytes: read("rx_bytes")?,
+            tx_bytes: read("tx_bytes")?,
+            rx_packets: read("rx_packets")?,
+            tx_packets: read("tx_packets")?,
+            rx_errors: read("rx_errors")?,
+            tx_errors: read("tx_errors")?,
+            rx_dropped: read("rx_dropped")?,
+            tx_dropped: read("tx_dropped")?,
+        })
+    }
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to rename interface: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
-        }
+    /// Gather everything known about one interface
+    pub fn get_interface_info(name: &str) -> Result<InterfaceInfo> {
+        let interface = Self::get_interfaces()?
+            .into_iter()
+            .find(|i| i.name == name)
+            .ok_or_else(|| anyhow!("Interface {} not found", name))?;
+
+        let wireless = interface.interface_type == InterfaceType::Wireless;
+
+        Ok(InterfaceInfo {
+            ip_addresses: Self::get_ip_addresses(name),
+            wireless_mode: if wireless { Self::get_wireless_mode(name).ok() } else { None },
+            connection: if wireless { Self::get_connection_info(name) } else { None },
+            supported_channels: if wireless { Self::get_supported_channels(name) } else { Vec::new() },
+            stats: Self::get_interface_stats(name),
+            management: Self::get_management_backend(name),
+            interface,
+        })
+    }
 
-        // Bring interface up with new name
-        AsyncCommand::new("ip")
-            .args(["link", "set", new_name, "up"])
-            .output()
-            .await?;
+    /// Whether NetworkManager, systemd-networkd or nobody configures `interface`
+    pub fn get_management_backend(interface: &str) -> ManagementBackend {
+        Self::get_management_backends(&[interface]).remove(interface).unwrap_or_default()
+    }
 
-        Ok(())
+    /// `get_management_backend` for several interfaces, asking each service once
+    ///
+    /// Uses `networkctl list` rather than `networkctl status` per interface, since both
+    /// report the same setup state and the TUI asks on every refresh.
+    pub fn get_management_backends(interfaces: &[&str]) -> HashMap<String, ManagementBackend> {
+        // A failing tool (not installed, service not running) tells us nothing
+        let query = |program: &str, args: &[&str]| {
+            Command::new(program)
+                .args(args)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        let nmcli = query("nmcli", &["-t", "-f", "DEVICE,STATE", "device", "status"]);
+        let networkctl = query("networkctl", &["list", "--no-legend", "--no-pager"]);
+        interfaces
+            .iter()
+            .map(|name| (name.to_string(), Self::management_backend(nmcli.as_deref(), networkctl.as_deref(), name)))
+            .collect()
     }
 
-    /// Restart NetworkManager
-    pub async fn restart_network_manager() -> Result<()> {
-        let output = AsyncCommand::new("systemctl")
-            .args(["restart", "NetworkManager"])
-            .output()
-            .await?;
+    /// Backend of `interface` from `nmcli -t -f DEVICE,STATE device status` and
+    /// `networkctl list --no-legend` output, `None` where the tool couldn't be run
+    fn management_backend(nmcli: Option<&str>, networkctl: Option<&str>, interface: &str) -> ManagementBackend {
+        // "wlan0:connected", "lo:unmanaged"
+        let nm_manages = |output: &str| {
+            output
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .any(|(device, state)| device == interface && state != "unmanaged")
+        };
+        // "  3 wlan0 wlan routable configured": index, link, type, operational state, setup state
+        let networkd_manages = |output: &str| {
+            output.lines().any(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                fields.len() >= 5 && fields[1] == interface && fields[4] != "unmanaged"
+            })
+        };
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to restart NetworkManager: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+        if nmcli.is_some_and(nm_manages) {
+            ManagementBackend::NetworkManager
+        } else if networkctl.is_some_and(networkd_manages) {
+            ManagementBackend::SystemdNetworkd
+        } else if nmcli.is_some() || networkctl.is_some() {
+            ManagementBackend::Unmanaged
+        } else {
+            ManagementBackend::Unknown
         }
-
-        Ok(())
     }
 
-    /// Spoof MAC address
-    pub async fn spoof_mac(interface: &str, new_mac: &str) -> Result<()> {
-        // Bring interface down
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "down"])
-            .output()
-            .await?;
+    /// Get current wireless mode
+    ///
+    /// Falls back to `iwconfig` when iw fails or prints no type it knows, as some
+    /// out-of-tree drivers only implement the old wireless extensions.
+    pub fn get_wireless_mode(interface: &str) -> Result<WirelessMode> {
+        let iw = Command::new("iw").args(["dev", interface, "info"]).output();
+        if let Ok(output) = &iw {
+            let mode = Self::parse_wireless_mode(&String::from_utf8_lossy(&output.stdout));
+            if mode != WirelessMode::Unknown {
+                return Ok(mode);
+            }
+        }
 
-        // Change MAC
-        let output = AsyncCommand::new("ip")
-            .args(["link", "set", interface, "address", new_mac])
-            .output()
-            .await?;
+        match Command::new("iwconfig").arg(interface).output() {
+            Ok(output) => Ok(Self::parse_iwconfig_mode(&String::from_utf8_lossy(&output.stdout))),
+            // Neither tool could run: report iw's error, it's the one that should be installed
+            Err(_) => iw.map(|_| WirelessMode::Unknown).map_err(Into::into),
+        }
+    }
 
-        if !output.status.success() {
-            return Err(anyhow!(
-                "Failed to change MAC address: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ));
+    /// Extract the interface type from `iw dev <iface> info` output
+    fn parse_wireless_mode(output: &str) -> WirelessMode {
+        for line in output.lines() {
+            if let Some(kind) = line.trim().strip_prefix("type ") {
+                return match kind.trim().to_ascii_lowercase().as_str() {
+                    "monitor" => WirelessMode::Monitor,
+                    // A P2P client is a station associated to a group owner
+                    "managed" | "station" | "p2p-client" => WirelessMode::Managed,
+                    // hostapd and P2P group owners run the interface as an access point
+                    "ap" | "ap/vlan" | "p2p-go" | "master" => WirelessMode::Master,
+                    "ibss" | "ad-hoc" | "adhoc" => WirelessMode::Adhoc,
+                    _ => WirelessMode::Unknown,
+                };
+            }
         }
 
-        // Bring interface up
-        AsyncCommand::new("ip")
-            .args(["link", "set", interface, "up"])
-            .output()
-            .await?;
+        WirelessMode::Unknown
+    }
 
-        Ok(())
+    /// Extract the mode from `iwconfig <iface>` output (`Mode:Monitor  Frequency:...`)
+    fn parse_iwconfig_mode(output: &str) -> WirelessMode {
+        output
+            .split_whitespace()
+            .find_map(|token| token.strip_prefix("Mode:"))
+            .map_or(WirelessMode::Unknown, |mode| match mode.to_ascii_lowercase().as_str() {
+                "monitor" => WirelessMode::Monitor,
+                "managed" => WirelessMode::Managed,
+                "master" => WirelessMode::Master,
+                "ad-hoc" => WirelessMode::Adhoc,
+                _ => WirelessMode::Unknown,
+            })
     }
 
-    /// Generate random MAC address
+    /// Administrative state (the UP flag, not the carrier) from an `ip -o link` line
+    fn parse_admin_state(line: &str) -> InterfaceState {
+        match line.split_once('<').and_then(|(_, rest)| rest.split_once('>')) {
+            Some((flags, _)) if flags.split(',').any(|f| f == "UP") => InterfaceState::Up,
+            Some(_) => InterfaceState::Down,
+            None => InterfaceState::Unknown,
+        }
+    }
+
+    /// Put an interface back the way `enable_monitor_mode` found it, best effort
+    async fn restore_link<R: CommandRunner>(runner: &R, interface: &str, mode: WirelessMode, state: InterfaceState) {
+        if let Some(iw_type) = mode.iw_type() {
+            let _ = runner.run("iw", &["dev", interface, "set", "type", iw_type]).await;
+        }
+        if state == InterfaceState::Up {
+            let _ = runner.run("ip", &["link", "set", interface, "up"]).await;
+        }
+    }
+
+    /// Enable monitor mode on interface
+    ///
+    /// With `unmanage`, NetworkManager is told to leave the interface alone first
+    /// so it doesn't switch it back to managed mode behind our back.
+    ///
+    /// If bringing the link down, the type change or bringing it back up fails,
+    /// the interface is returned to the mode and admin state it had before the
+    /// call (and handed back to NetworkManager when `unmanage` was set) before
+    /// the error is returned.
+    pub async fn enable_monitor_mode(interface: &str, unmanage: bool) -> Result<()> {
+        #[cfg(feature = "netlink")]
+        let native = {
+            if unmanage {
+                Self::set_unmanaged_with(&SystemRunner, interface, true).await?;
+            }
+            match Self::set_wireless_mode_native(interface, WirelessMode::Monitor) {
+                Ok(()) => {
+                    audit("monitor", &format!("{} enabled{}", interface, if unmanage { ", unmanaged" } else { "" }));
+                    return Ok(());
+                }
+                Err(e) => e,
+            }
+        };
+        // The native attempt rolled itself back; the iw path hands the interface back to NetworkManager if it fails too
+        let result = Self::enable_monitor_mode_with(&SystemRunner, interface, unmanage).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("monitor mode", native, result);
+        result?;
+        audit("monitor", &format!("{} enabled{}", interface, if unmanage { ", unmanaged" } else { "" }));
+        Ok(())
+    }
+
+    /// `enable_monitor_mode` using the given command runner
+    pub async fn enable_monitor_mode_with<R: CommandRunner>(runner: &R, interface: &str, unmanage: bool) -> Result<()> {
+        // Remember where we started so a failure can be rolled back
+        let info = runner.run("iw", &["dev", interface, "info"]).await?;
+        let original_mode = Self::parse_wireless_mode(&String::from_utf8_lossy(&info.stdout));
+        let link = runner.run("ip", &["-o", "link", "show", "dev", interface]).await?;
+        let original_state = Self::parse_admin_state(&String::from_utf8_lossy(&link.stdout));
+
+        if unmanage {
+            Self::set_unmanaged_with(runner, interface, true).await?;
+        }
+
+        let changed = Self::change_type_with(runner, interface, WirelessMode::Monitor).await;
+        if changed.is_err() {
+            Self::restore_link(runner, interface, original_mode, original_state).await;
+            if unmanage {
+                let _ = Self::set_unmanaged_with(runner, interface, false).await;
+            }
+        }
+        changed
+    }
+
+    /// Bring `interface` down, switch it to `mode` and bring it back up, stopping at the first failure
+    async fn change_type_with<R: CommandRunner>(runner: &R, interface: &str, mode: WirelessMode) -> Result<()> {
+        let iw_type = mode.iw_type().ok_or_else(|| anyhow!("Can't switch to an unknown mode"))?;
+        let output = runner.run("ip", &["link", "set", interface, "down"]).await?;
+        if !output.status.success() {
+            return Err(anyhow!("Failed to bring down interface: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let output = runner.run("iw", &["dev", interface, "set", "type", iw_type]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to set {} mode: {}",
+                mode,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let output = runner.run("ip", &["link", "set", interface, "up"]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to bring up interface in {} mode: {}",
+                mode,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Switch an interface to any wireless mode, bringing it down around the change
+    ///
+    /// Modes the radio doesn't list as supported are rejected up front. Like
+    /// `enable_monitor_mode`, a failed change or bring-up restores the previous mode and admin state.
+    pub async fn set_wireless_mode(interface: &str, mode: WirelessMode) -> Result<()> {
+        let supported = Self::get_phy_for_interface(interface)
+            .and_then(|phy| Self::get_phy_info(&phy))
+            .map(|info| Self::parse_supported_modes(&info))
+            .unwrap_or_default();
+        Self::check_mode_supported(interface, mode, &supported)?;
+        #[cfg(feature = "netlink")]
+        let native = match Self::set_wireless_mode_native(interface, mode) {
+            Ok(()) => {
+                audit("mode", &format!("{} -> {}", interface, mode));
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+        let result = Self::set_wireless_mode_with(&SystemRunner, interface, mode, &supported).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("mode change", native, result);
+        result?;
+        audit("mode", &format!("{} -> {}", interface, mode));
+        Ok(())
+    }
+
+    /// `set_wireless_mode` using the given command runner; an empty `supported` skips the check
+    pub async fn set_wireless_mode_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        mode: WirelessMode,
+        supported: &[String],
+    ) -> Result<()> {
+        if mode.iw_type().is_none() {
+            return Err(anyhow!("Can't switch to an unknown mode"));
+        }
+        Self::check_mode_supported(interface, mode, supported)?;
+
+        let info = runner.run("iw", &["dev", interface, "info"]).await?;
+        let original_mode = Self::parse_wireless_mode(&String::from_utf8_lossy(&info.stdout));
+        let link = runner.run("ip", &["-o", "link", "show", "dev", interface]).await?;
+        let original_state = Self::parse_admin_state(&String::from_utf8_lossy(&link.stdout));
+
+        let changed = Self::change_type_with(runner, interface, mode).await;
+        if changed.is_err() {
+            Self::restore_link(runner, interface, original_mode, original_state).await;
+        }
+        changed
+    }
+
+    /// Refuse `mode` when the radio lists its modes and it isn't one of them
+    fn check_mode_supported(interface: &str, mode: WirelessMode, supported: &[String]) -> Result<()> {
+        if !supported.is_empty() && !supported.iter().any(|m| m.eq_ignore_ascii_case(mode.phy_mode_name())) {
+            return Err(anyhow!(
+                "{} does not support {} mode (supported: {})",
+                interface,
+                mode,
+                supported.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Add a monitor-mode virtual interface on `interface`'s radio, leaving `interface` as it is
+    ///
+    /// Adapters that support it can then capture while staying associated. Without
+    /// `name`, `suggest_monitor_name` picks a free one. Returns the name used.
+    pub async fn create_monitor_interface(interface: &str, name: Option<&str>) -> Result<String> {
+        let name = name.map_or_else(|| Self::suggest_monitor_name(interface), str::to_string);
+        let phy = Self::get_phy_for_interface(interface);
+        Self::create_monitor_interface_with(&SystemRunner, interface, phy.as_deref(), &name).await?;
+        audit("monitor-vap", &format!("{} created on {}", name, interface));
+        Ok(name)
+    }
+
+    /// `create_monitor_interface` using the given command runner and `interface`'s radio, if known
+    pub async fn create_monitor_interface_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        phy: Option<&str>,
+        name: &str,
+    ) -> Result<()> {
+        Self::validate_interface_name(name)?;
+        if std::path::Path::new(&format!("/sys/class/net/{}", name)).exists() {
+            return Err(anyhow!("Interface {} already exists", name));
+        }
+
+        // Add to the radio rather than the netdev, which is ambiguous when it has several
+        let [kind, target] = Self::radio_target(phy, interface);
+        let output = runner.run("iw", &[&kind, &target, "interface", "add", name, "type", "monitor"]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to create monitor interface: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let output = runner.run("ip", &["link", "set", name, "up"]).await?;
+        if !output.status.success() {
+            // Don't leave a half-configured interface behind
+            let _ = runner.run("iw", &["dev", name, "del"]).await;
+            return Err(anyhow!(
+                "Failed to bring up {}: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a virtual wireless interface such as one made by `create_monitor_interface`
+    pub async fn delete_wireless_interface(name: &str) -> Result<()> {
+        Self::delete_wireless_interface_with(&SystemRunner, name).await?;
+        audit("monitor-vap", &format!("{} deleted", name));
+        Ok(())
+    }
+
+    /// `delete_wireless_interface` using the given command runner
+    pub async fn delete_wireless_interface_with<R: CommandRunner>(runner: &R, name: &str) -> Result<()> {
+        let output = runner.run("iw", &["dev", name, "del"]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to delete {}: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(())
+    }
+
+    /// A monitor interface name for `base` that no existing interface uses
+    pub fn suggest_monitor_name(base: &str) -> String {
+        let existing: Vec<String> = std::fs::read_dir("/sys/class/net")
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.file_name().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::suggest_monitor_name_among(base, &existing)
+    }
+
+    /// "<base>mon", then "<base>mon1", "<base>mon2", ... skipping names in `existing`
+    ///
+    /// `base` is shortened when needed so the result fits the kernel's 15-byte limit.
+    fn suggest_monitor_name_among(base: &str, existing: &[String]) -> String {
+        (0..)
+            .map(|n| {
+                let suffix = if n == 0 { "mon".to_string() } else { format!("mon{}", n) };
+                let mut stem = base.to_string();
+                while stem.len() + suffix.len() > 15 {
+                    stem.pop();
+                }
+                stem + &suffix
+            })
+            .find(|candidate| !existing.contains(candidate))
+            .expect("an unbounded range always has a free name")
+    }
+
+    /// Disable monitor mode (set to managed)
+    ///
+    /// With `unmanage`, the interface is handed back to NetworkManager afterwards.
+    pub async fn disable_monitor_mode(interface: &str, unmanage: bool) -> Result<()> {
+        #[cfg(feature = "netlink")]
+        let native = match Self::set_wireless_mode_native(interface, WirelessMode::Managed) {
+            Ok(()) => {
+                if unmanage {
+                    Self::set_unmanaged_with(&SystemRunner, interface, false).await?;
+                }
+                audit("monitor", &format!("{} disabled", interface));
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+        let result = Self::disable_monitor_mode_with(&SystemRunner, interface, unmanage).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("managed mode", native, result);
+        result?;
+        audit("monitor", &format!("{} disabled", interface));
+        Ok(())
+    }
+
+    /// `disable_monitor_mode` using the given command runner
+    pub async fn disable_monitor_mode_with<R: CommandRunner>(runner: &R, interface: &str, unmanage: bool) -> Result<()> {
+        // Bring interface down
+        runner.run("ip", &["link", "set", interface, "down"]).await?;
+
+        // Set managed mode
+        let output = runner.run("iw", &["dev", interface, "set", "type", "managed"]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to set managed mode: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        // Bring interface up
+        runner.run("ip", &["link", "set", interface, "up"]).await?;
+
+        if unmanage {
+            Self::set_unmanaged_with(runner, interface, false).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Tell NetworkManager to stop (or resume) managing an interface
+    ///
+    /// Does nothing when nmcli isn't installed.
+    pub async fn set_unmanaged(interface: &str, unmanaged: bool) -> Result<()> {
+        Self::set_unmanaged_with(&SystemRunner, interface, unmanaged).await?;
+        audit("managed", &format!("{} {}", interface, if unmanaged { "unmanaged" } else { "managed" }));
+        Ok(())
+    }
+
+    /// `set_unmanaged` using the given command runner
+    pub async fn set_unmanaged_with<R: CommandRunner>(runner: &R, interface: &str, unmanaged: bool) -> Result<()> {
+        let managed = if unmanaged { "no" } else { "yes" };
+        let Some(output) = Self::run_nmcli(runner, &["device", "set", interface, "managed", managed]).await? else {
+            return Ok(());
+        };
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to set managed state: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run nmcli, returning `None` when it isn't installed
+    async fn run_nmcli<R: CommandRunner>(runner: &R, args: &[&str]) -> Result<Option<std::process::Output>> {
+        Self::nmcli_installed(runner.run("nmcli", args).await)
+    }
+
+    /// `None` in place of the error when nmcli couldn't be started because it isn't installed
+    fn nmcli_installed(result: Result<std::process::Output>) -> Result<Option<std::process::Output>> {
+        match result {
+            Ok(output) => Ok(Some(output)),
+            Err(e) if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound) => {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Active NetworkManager profile on an interface and its cloned-MAC setting key
+    ///
+    /// `None` when nmcli isn't installed, the device has no active connection,
+    /// or the profile type has no cloned MAC (e.g. bridges, VPNs).
+    async fn cloned_mac_setting<R: CommandRunner>(runner: &R, interface: &str) -> Result<Option<(String, String)>> {
+        let Some(output) = Self::run_nmcli(runner, &["-g", "GENERAL.CONNECTION", "device", "show", interface]).await? else {
+            return Ok(None);
+        };
+        // `-g` escapes colons in values
+        let profile = String::from_utf8_lossy(&output.stdout).trim().replace("\\:", ":");
+        if !output.status.success() || profile.is_empty() || profile == "--" {
+            return Ok(None);
+        }
+
+        let Some(output) = Self::run_nmcli(runner, &["-g", "connection.type", "connection", "show", &profile]).await? else {
+            return Ok(None);
+        };
+        let key = match String::from_utf8_lossy(&output.stdout).trim() {
+            "802-11-wireless" => "802-11-wireless.cloned-mac-address",
+            "802-3-ethernet" => "802-3-ethernet.cloned-mac-address",
+            _ => return Ok(None),
+        };
+
+        Ok(Some((profile, key.to_string())))
+    }
+
+    /// Write (or clear, with an empty `value`) the cloned MAC on a profile
+    async fn set_cloned_mac<R: CommandRunner>(runner: &R, profile: &str, key: &str, value: &str) -> Result<()> {
+        let output = runner.run("nmcli", &["connection", "modify", profile, key, value]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to update NetworkManager profile: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Subscribe to kernel link and address changes
+    ///
+    /// Each message on the channel means "the interface list may have changed";
+    /// bursts should be drained and handled once. `None` when built without the
+    /// `netlink` feature or when the socket can't be opened, in which case callers
+    /// fall back to polling.
+    #[cfg(feature = "netlink")]
+    pub fn watch_link_events() -> Option<std::sync::mpsc::Receiver<()>> {
+        use netlink_sys::{protocols::NETLINK_ROUTE, Socket, SocketAddr};
+
+        let mut socket = Socket::new(NETLINK_ROUTE).ok()?;
+        let groups = libc::RTMGRP_LINK | libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR;
+        socket.bind(&SocketAddr::new(0, groups as u32)).ok()?;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            // Only the arrival matters; the interface list is re-read from `ip` anyway
+            let mut buf = Vec::with_capacity(8192);
+            loop {
+                buf.clear();
+                if socket.recv(&mut buf, 0).is_err() || tx.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+        Some(rx)
+    }
+
+    #[cfg(not(feature = "netlink"))]
+    pub fn watch_link_events() -> Option<std::sync::mpsc::Receiver<()>> {
+        None
+    }
+
+    /// Set or clear IFF_UP on `interface` with an rtnetlink request, as `ip link set up/down` does
+    ///
+    /// Saves spawning `ip` and works where it isn't installed. Callers fall back to
+    /// `ip` on any error, so its message is the one users see.
+    #[cfg(feature = "netlink")]
+    fn set_link_up_native(interface: &str, up: bool) -> Result<()> {
+        let index = Self::interface_index(interface)?;
+        let mut socket = crate::netlink::Socket::open(netlink_sys::protocols::NETLINK_ROUTE)?;
+        socket
+            .request_ack(|seq| Self::link_flags_request(index, up, seq))
+            .map_err(|e| anyhow!("Failed to change link: {}", e))
+    }
+
+    #[cfg(feature = "netlink")]
+    fn interface_index(interface: &str) -> Result<u32> {
+        nix::net::if_::if_nametoindex(interface).map_err(|e| anyhow!("No interface {}: {}", interface, e))
+    }
+
+    /// Switch `interface` to `mode` over nl80211, bringing it down around the change as `iw` needs
+    ///
+    /// As with the `iw` path, if the type change or bringing the link back up fails,
+    /// the previous mode and admin state are restored. Callers fall back to `iw` on any error.
+    #[cfg(feature = "netlink")]
+    fn set_wireless_mode_native(interface: &str, mode: WirelessMode) -> Result<()> {
+        let iftype = mode.nl80211_iftype().ok_or_else(|| anyhow!("Can't switch to an unknown mode"))?;
+        let index = Self::interface_index(interface)?;
+        let mut nl80211 = crate::netlink::Nl80211::connect()?;
+        let original = nl80211.interface_type(index)?;
+        let was_up = std::fs::read_to_string(format!("/sys/class/net/{}/flags", interface))
+            .ok()
+            .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+            .is_some_and(|flags| flags & libc::IFF_UP as u32 != 0);
+
+        Self::set_link_up_native(interface, false)?;
+        let changed = nl80211
+            .set_interface_type(index, iftype)
+            .map_err(|e| anyhow!("Failed to set {} mode: {}", mode, e))
+            .and_then(|()| {
+                Self::set_link_up_native(interface, true)
+                    .map_err(|e| anyhow!("Failed to bring up interface in {} mode: {}", mode, e))
+            });
+        if changed.is_err() {
+            let _ = nl80211.set_interface_type(index, original);
+            if was_up {
+                let _ = Self::set_link_up_native(interface, true);
+            }
+        }
+        changed
+    }
+
+    /// Tune `interface`'s radio over nl80211, as `iw set channel`/`set freq` does
+    #[cfg(feature = "netlink")]
+    fn set_channel_native(interface: &str, channel: u32, width: ChannelWidth) -> Result<()> {
+        let (freq, mhz, centre) = Self::channel_layout(channel, width)?;
+        let index = Self::interface_index(interface)?;
+        crate::netlink::Nl80211::connect()?
+            .set_channel(index, freq, mhz, centre)
+            .map_err(|e| anyhow!("Failed to set channel: {}", e))
+    }
+
+    /// RTM_NEWLINK message changing only the IFF_UP flag of interface `index`, asking for an ack
+    #[cfg_attr(not(feature = "netlink"), allow(dead_code))]
+    fn link_flags_request(index: u32, up: bool, seq: u32) -> Vec<u8> {
+        const HEADER_LEN: u32 = 16;
+        const IFINFOMSG_LEN: u32 = 16;
+        let iff_up = libc::IFF_UP as u32;
+
+        let mut msg = Vec::with_capacity((HEADER_LEN + IFINFOMSG_LEN) as usize);
+        // nlmsghdr
+        msg.extend_from_slice(&(HEADER_LEN + IFINFOMSG_LEN).to_ne_bytes());
+        msg.extend_from_slice(&libc::RTM_NEWLINK.to_ne_bytes());
+        msg.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16).to_ne_bytes());
+        msg.extend_from_slice(&seq.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        // ifinfomsg: family, padding, device type, index, flags, mask of flags to change
+        msg.extend_from_slice(&[libc::AF_UNSPEC as u8, 0]);
+        msg.extend_from_slice(&0u16.to_ne_bytes());
+        msg.extend_from_slice(&(index as i32).to_ne_bytes());
+        msg.extend_from_slice(&(if up { iff_up } else { 0 }).to_ne_bytes());
+        msg.extend_from_slice(&iff_up.to_ne_bytes());
+        msg
+    }
+
+    /// First line of the version output of each external tool sozin uses, `None` when not installed
+    pub fn tool_versions() -> Vec<(String, Option<String>)> {
+        [
+            ("iw", "--version"),
+            ("ip", "-V"),
+            ("nmcli", "--version"),
+            ("systemctl", "--version"),
+            ("rfkill", "--version"),
+        ]
+        .into_iter()
+        .map(|(tool, flag)| {
+            let version = Command::new(tool).arg(flag).output().ok().and_then(|output| {
+                // Some tools print their version on stderr
+                let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+                String::from_utf8_lossy(&text).lines().next().map(|l| l.trim().to_string())
+            });
+            (tool.to_string(), version)
+        })
+        .collect()
+    }
+
+    /// Hop a monitor-mode interface across `channels`, counting the frames heard on each
+    ///
+    /// This is an estimate: frame counts stand in for airtime, ignoring frame length
+    /// and data rate, and a short dwell misses bursty traffic. Channels the radio
+    /// refuses to tune to are skipped. Frames are counted by a `CaptureSession` writing to /dev/null.
+    /// The radio goes back to the channel it was on, and the sweep is audited as one line.
+    pub async fn sample_channel_activity(
+        interface: &str,
+        channels: &[u32],
+        dwell: std::time::Duration,
+    ) -> Result<Vec<ChannelActivity>> {
+        if Self::get_wireless_mode(interface).ok() != Some(WirelessMode::Monitor) {
+            return Err(anyhow!("{} must be in monitor mode to sample channel activity", interface));
+        }
+
+        let original = Command::new("iw")
+            .args(["dev", interface, "info"])
+            .output()
+            .ok()
+            .and_then(|o| Self::parse_current_channel(&String::from_utf8_lossy(&o.stdout)));
+        let phy = Self::get_phy_for_interface(interface);
+
+        let mut counts = Vec::new();
+        // Collected rather than returned early, so the channel is restored either way
+        let sampled: Result<()> = async {
+            for &channel in channels {
+                if Self::tune_channel(interface, phy.as_deref(), channel, ChannelWidth::Ht20).await.is_err() {
+                    continue;
+                }
+                let session = crate::capture::CaptureSession::start(interface, std::path::Path::new("/dev/null"), None)?;
+                tokio::time::sleep(dwell).await;
+                counts.push((channel, session.stop()?.frames));
+            }
+            Ok(())
+        }
+        .await;
+
+        let restored = match original {
+            Some((channel, width)) => match Self::tune_channel(interface, phy.as_deref(), channel, width).await {
+                Ok(()) => format!(", back on {} {}", channel, width),
+                Err(_) => format!(", could not return to {} {}", channel, width),
+            },
+            None => String::new(),
+        };
+        audit("activity", &format!("{} sampled {} of {} channels{}", interface, counts.len(), channels.len(), restored));
+        sampled?;
+        Ok(Self::relative_activity(&counts))
+    }
+
+    /// Channel and width from `iw dev <iface> info` (`channel 36 (5180 MHz), width: 80 MHz, center1: 5210 MHz`)
+    fn parse_current_channel(output: &str) -> Option<(u32, ChannelWidth)> {
+        let line = output.lines().map(str::trim).find_map(|l| l.strip_prefix("channel "))?;
+        let mut fields = line.split(',').map(str::trim);
+        let mut first = fields.next()?.split_whitespace();
+        let channel = first.next()?.parse().ok()?;
+        let freq: u32 = first.next()?.trim_start_matches('(').parse().ok()?;
+        let mut mhz = 20;
+        let mut centre = freq;
+        for field in fields {
+            if let Some(width) = field.strip_prefix("width: ") {
+                mhz = width.split_whitespace().next()?.parse().ok()?;
+            } else if let Some(center1) = field.strip_prefix("center1: ") {
+                centre = center1.split_whitespace().next()?.parse().ok()?;
+            }
+        }
+        let width = match mhz {
+            40 if centre > freq => ChannelWidth::Ht40Plus,
+            40 => ChannelWidth::Ht40Minus,
+            80 => ChannelWidth::Mhz80,
+            160 => ChannelWidth::Mhz160,
+            _ => ChannelWidth::Ht20,
+        };
+        Some((channel, width))
+    }
+
+    /// Turn per-channel frame counts into shares of the whole sweep
+    fn relative_activity(counts: &[(u32, u64)]) -> Vec<ChannelActivity> {
+        let total: u64 = counts.iter().map(|&(_, frames)| frames).sum();
+        counts
+            .iter()
+            .map(|&(channel, frames)| ChannelActivity {
+                channel,
+                frames,
+                percent: (frames * 100).checked_div(total).unwrap_or(0) as u8,
+            })
+            .collect()
+    }
+
+    /// List radios and their block state with `rfkill`
+    pub fn rfkill_list() -> Result<Vec<RfkillEntry>> {
+        let not_installed = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::NotFound => anyhow!("rfkill is not installed"),
+            _ => e.into(),
+        };
+
+        // `--json` needs util-linux 2.34+; older versions only print text
+        let output = Command::new("rfkill").arg("--json").output().map_err(not_installed)?;
+        if output.status.success() {
+            if let Some(entries) = Self::parse_rfkill_json(&String::from_utf8_lossy(&output.stdout)) {
+                return Ok(entries);
+            }
+        }
+
+        let output = Command::new("rfkill").arg("list").output().map_err(not_installed)?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to list rfkill devices: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(Self::parse_rfkill_text(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse `rfkill --json`; the array's key is "" in older releases, "rfkilldevices" in newer
+    fn parse_rfkill_json(output: &str) -> Option<Vec<RfkillEntry>> {
+        let value: serde_json::Value = serde_json::from_str(output).ok()?;
+        let devices = value.as_object()?.values().find_map(|v| v.as_array())?;
+        devices
+            .iter()
+            .map(|d| {
+                Some(RfkillEntry {
+                    id: d.get("id")?.as_u64()? as u32,
+                    kind: d.get("type")?.as_str()?.to_string(),
+                    device: d.get("device")?.as_str()?.to_string(),
+                    soft_blocked: d.get("soft")?.as_str()? == "blocked",
+                    hard_blocked: d.get("hard")?.as_str()? == "blocked",
+                })
+            })
+            .collect()
+    }
+
+    /// Parse `rfkill list` text: "0: phy0: Wireless LAN" followed by "Soft/Hard blocked: yes|no"
+    fn parse_rfkill_text(output: &str) -> Vec<RfkillEntry> {
+        let mut entries: Vec<RfkillEntry> = Vec::new();
+        for line in output.lines() {
+            let trimmed = line.trim();
+            if let Some(blocked) = trimmed.strip_prefix("Soft blocked:") {
+                if let Some(entry) = entries.last_mut() {
+                    entry.soft_blocked = blocked.trim() == "yes";
+                }
+            } else if let Some(blocked) = trimmed.strip_prefix("Hard blocked:") {
+                if let Some(entry) = entries.last_mut() {
+                    entry.hard_blocked = blocked.trim() == "yes";
+                }
+            } else {
+                let mut parts = trimmed.splitn(3, ": ");
+                let (Some(id), Some(device), Some(label)) = (parts.next(), parts.next(), parts.next()) else {
+                    continue;
+                };
+                let Ok(id) = id.parse() else {
+                    continue;
+                };
+                // Same type names `rfkill --json` uses
+                let kind = match label {
+                    "Wireless LAN" => "wlan",
+                    "Bluetooth" => "bluetooth",
+                    "Ultra-Wideband" => "uwb",
+                    "WiMAX" => "wimax",
+                    "Wireless WAN" => "wwan",
+                    "GPS" => "gps",
+                    "FM" => "fm",
+                    "NFC" => "nfc",
+                    other => other,
+                };
+                entries.push(RfkillEntry {
+                    id,
+                    kind: kind.to_string(),
+                    device: device.to_string(),
+                    soft_blocked: false,
+                    hard_blocked: false,
+                });
+            }
+        }
+        entries
+    }
+
+    /// Soft-block or unblock every radio of a type (`wifi`, `bluetooth`, `all`...)
+    pub async fn set_rfkill(kind: &str, blocked: bool) -> Result<()> {
+        Self::set_rfkill_with(&SystemRunner, kind, blocked).await?;
+        audit("rfkill", &format!("{} {}", kind, if blocked { "blocked" } else { "unblocked" }));
+        Ok(())
+    }
+
+    /// `set_rfkill` using the given command runner
+    pub async fn set_rfkill_with<R: CommandRunner>(runner: &R, kind: &str, blocked: bool) -> Result<()> {
+        let action = if blocked { "block" } else { "unblock" };
+        let output = runner.run("rfkill", &[action, kind]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to {} {}: {}",
+                action,
+                kind,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run a WPA2-PSK (or open, without `passphrase`) access point on `interface` with hostapd
+    ///
+    /// hostapd is started in the background with a generated config; stop it
+    /// with `disable_ap_mode`. NetworkManager must not be managing the interface.
+    pub async fn enable_ap_mode(interface: &str, ssid: &str, channel: u32, passphrase: Option<&str>) -> Result<()> {
+        Self::enable_ap_mode_with(&SystemRunner, interface, ssid, channel, passphrase).await?;
+        audit("ap", &format!("{} started \"{}\" on channel {}", interface, ssid, channel));
+        Ok(())
+    }
+
+    /// `enable_ap_mode` using the given command runner
+    pub async fn enable_ap_mode_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        ssid: &str,
+        channel: u32,
+        passphrase: Option<&str>,
+    ) -> Result<()> {
+        let config = Self::hostapd_config(interface, ssid, channel, passphrase)?;
+        let (config_path, pid_path) = Self::ap_paths(interface)?;
+        if pid_path.exists() {
+            return Err(anyhow!("An access point is already running on {}; disable it first", interface));
+        }
+
+        // The config holds the passphrase. Only root can write in the state dir, so a
+        // leftover config is ours from an earlier run and safe to replace.
+        let _ = std::fs::remove_file(&config_path);
+        {
+            use std::io::Write;
+            use std::os::unix::fs::OpenOptionsExt;
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .custom_flags(libc::O_NOFOLLOW)
+                .open(&config_path)
+                .map_err(|e| anyhow!("Failed to create {}: {}", config_path.display(), e))?;
+            file.write_all(config.as_bytes())?;
+        }
+
+        let config_arg = config_path.to_string_lossy();
+        let pid_arg = pid_path.to_string_lossy();
+        let output = match runner.run("hostapd", &["-B", "-P", &pid_arg, &config_arg]).await {
+            Ok(output) => output,
+            Err(e) => {
+                let _ = std::fs::remove_file(&config_path);
+                if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound) {
+                    return Err(anyhow!("hostapd is not installed; install it to use AP mode"));
+                }
+                return Err(e);
+            }
+        };
+
+        if !output.status.success() {
+            let _ = std::fs::remove_file(&config_path);
+            // hostapd reports configuration and driver errors on stdout
+            return Err(anyhow!(
+                "Failed to start hostapd: {}{}",
+                String::from_utf8_lossy(&output.stdout).trim(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Stop the hostapd started by `enable_ap_mode` and remove its config
+    pub async fn disable_ap_mode(interface: &str) -> Result<()> {
+        Self::disable_ap_mode_with(&SystemRunner, interface).await?;
+        audit("ap", &format!("{} stopped", interface));
+        Ok(())
+    }
+
+    /// `disable_ap_mode` using the given command runner
+    pub async fn disable_ap_mode_with<R: CommandRunner>(runner: &R, interface: &str) -> Result<()> {
+        let (config_path, pid_path) = Self::ap_paths(interface)?;
+        let pid = std::fs::read_to_string(&pid_path)
+            .map_err(|_| anyhow!("No access point started by sozin is running on {}", interface))?;
+
+        // A pid that's gone, or was reused by something else, counts as stopped
+        if let Some(pid) = Self::hostapd_pid(&pid, std::path::Path::new("/proc")) {
+            let output = runner.run("kill", &[&pid.to_string()]).await?;
+            if !output.status.success() && !String::from_utf8_lossy(&output.stderr).contains("No such process") {
+                return Err(anyhow!(
+                    "Failed to stop hostapd: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+        }
+
+        let _ = std::fs::remove_file(&pid_path);
+        let _ = std::fs::remove_file(&config_path);
+        Ok(())
+    }
+
+    /// Config and pid file locations for the hostapd instance on `interface`, in `AP_STATE_DIR`
+    fn ap_paths(interface: &str) -> Result<(std::path::PathBuf, std::path::PathBuf)> {
+        Self::ap_paths_in(std::path::Path::new(AP_STATE_DIR), interface)
+    }
+
+    /// `ap_paths` under `dir`, created 0700 if missing and refused unless it is a real
+    /// directory only its owner (us) can use
+    fn ap_paths_in(dir: &std::path::Path, interface: &str) -> Result<(std::path::PathBuf, std::path::PathBuf)> {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+        if let Err(e) = std::fs::DirBuilder::new().mode(0o700).create(dir) {
+            if e.kind() != std::io::ErrorKind::AlreadyExists {
+                return Err(anyhow!("Failed to create {}: {}", dir.display(), e));
+            }
+        }
+        let meta = std::fs::symlink_metadata(dir).map_err(|e| anyhow!("Failed to read {}: {}", dir.display(), e))?;
+        // SAFETY: geteuid has no preconditions
+        let euid = unsafe { libc::geteuid() };
+        if !meta.is_dir() || meta.uid() != euid || meta.mode() & 0o077 != 0 {
+            return Err(anyhow!("Refusing to use {}: it must be a directory private to its owner", dir.display()));
+        }
+        Ok((
+            dir.join(format!("hostapd-{}.conf", interface)),
+            dir.join(format!("hostapd-{}.pid", interface)),
+        ))
+    }
+
+    /// The pid in a hostapd pid file, if that process is still hostapd according to `proc`
+    fn hostapd_pid(contents: &str, proc: &std::path::Path) -> Option<u32> {
+        let pid: u32 = contents.trim().parse().ok()?;
+        let comm = std::fs::read_to_string(proc.join(pid.to_string()).join("comm")).ok()?;
+        (comm.trim() == "hostapd").then_some(pid)
+    }
+
+    /// Minimal hostapd config for a single-SSID access point
+    fn hostapd_config(interface: &str, ssid: &str, channel: u32, passphrase: Option<&str>) -> Result<String> {
+        if ssid.is_empty() || ssid.len() > 32 {
+            return Err(anyhow!("SSID must be 1-32 bytes"));
+        }
+        if ssid.contains('\n') {
+            return Err(anyhow!("SSID must not contain line breaks"));
+        }
+        let freq = crate::scanner::WifiScanner::channel_to_freq(channel)
+            .ok_or_else(|| anyhow!("Unknown channel {}", channel))?;
+        let hw_mode = if freq < 3000 { "g" } else { "a" };
+
+        let mut config = format!(
+            "interface={}\ndriver=nl80211\nssid={}\nhw_mode={}\nchannel={}\nieee80211n=1\n",
+            interface, ssid, hw_mode, channel
+        );
+        if let Some(passphrase) = passphrase {
+            if !(8..=63).contains(&passphrase.len()) || !passphrase.chars().all(|c| (' '..='~').contains(&c)) {
+                return Err(anyhow!("Passphrase must be 8-63 printable ASCII characters"));
+            }
+            config.push_str("wpa=2\nwpa_key_mgmt=WPA-PSK\nrsn_pairwise=CCMP\n");
+            config.push_str(&format!("wpa_passphrase={}\n", passphrase));
+        }
+        Ok(config)
+    }
+
+    /// Bring interface up
+    pub async fn bring_up(interface: &str) -> Result<()> {
+        #[cfg(feature = "netlink")]
+        let native = match Self::set_link_up_native(interface, true) {
+            Ok(()) => {
+                audit("up", interface);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+        let result = Self::bring_up_with(&SystemRunner, interface).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("link up", native, result);
+        result?;
+        audit("up", interface);
+        Ok(())
+    }
+
+    /// `bring_up` using the given command runner
+    pub async fn bring_up_with<R: CommandRunner>(runner: &R, interface: &str) -> Result<()> {
+        let output = runner.run("ip", &["link", "set", interface, "up"]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to bring up interface: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Bring interface down
+    pub async fn bring_down(interface: &str) -> Result<()> {
+        #[cfg(feature = "netlink")]
+        let native = match Self::set_link_up_native(interface, false) {
+            Ok(()) => {
+                audit("down", interface);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+        let result = Self::bring_down_with(&SystemRunner, interface).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("link down", native, result);
+        result?;
+        audit("down", interface);
+        Ok(())
+    }
+
+    /// `bring_down` using the given command runner
+    pub async fn bring_down_with<R: CommandRunner>(runner: &R, interface: &str) -> Result<()> {
+        let output = runner.run("ip", &["link", "set", interface, "down"]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to bring down interface: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Return an interface to a clean managed state: down, managed mode, addresses flushed, up
+    ///
+    /// Wireless interfaces are also switched back to managed mode and handed back to
+    /// NetworkManager. Every step is attempted even if an earlier one fails, so the
+    /// interface ends up as close to normal as possible; check each step's outcome.
+    pub async fn reset_interface(interface: &str) -> Vec<ResetStep> {
+        let wireless = Self::detect_interface_type(interface) == InterfaceType::Wireless;
+        let steps = Self::reset_interface_with(&SystemRunner, interface, wireless).await;
+        let failed = steps.iter().filter(|s| s.outcome.is_err()).count();
+        audit("reset", &format!("{} ({} of {} steps ok)", interface, steps.len() - failed, steps.len()));
+        steps
+    }
+
+    /// `reset_interface` using the given command runner
+    pub async fn reset_interface_with<R: CommandRunner>(runner: &R, interface: &str, wireless: bool) -> Vec<ResetStep> {
+        let mut steps = Vec::new();
+        let mut record = |description: &str, result: Result<()>| {
+            steps.push(ResetStep {
+                description: description.to_string(),
+                outcome: result.map_err(|e| e.to_string().trim().to_string()),
+            });
+        };
+
+        record("Bring down", Self::bring_down_with(runner, interface).await);
+        if wireless {
+            let result = match runner.run("iw", &["dev", interface, "set", "type", "managed"]).await {
+                Ok(output) if !output.status.success() => Err(anyhow!(
+                    "Failed to set managed mode: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                )),
+                other => other.map(|_| ()),
+            };
+            record("Set managed mode", result);
+            record("Hand back to NetworkManager", Self::set_unmanaged_with(runner, interface, false).await);
+        }
+        let result = match runner.run("ip", &["addr", "flush", "dev", interface]).await {
+            Ok(output) if !output.status.success() => Err(anyhow!(
+                "Failed to flush addresses: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )),
+            other => other.map(|_| ()),
+        };
+        record("Flush addresses", result);
+        record("Bring up", Self::bring_up_with(runner, interface).await);
+        steps
+    }
+
+    /// Unload and reload the kernel module behind `interface`, for adapters stuck in a bad state
+    ///
+    /// Every interface using the same driver goes away for a moment. The interface may come
+    /// back under a different name, so it is found again by MAC address (current or
+    /// permanent, as a spoofed address doesn't survive the reload); returns its name.
+    pub async fn reload_driver(interface: &str) -> Result<String> {
+        let driver = Self::get_driver(interface).ok_or_else(|| {
+            anyhow!(
+                "Can't determine the driver of {}: it has no device driver in sysfs (virtual interface?)",
+                interface
+            )
+        })?;
+        // The module name can differ from the driver name (e.g. rtl88xxau provides rtl8812au)
+        let module = std::fs::read_link(format!("/sys/class/net/{}/device/driver/module", interface))
+            .ok()
+            .and_then(|p| p.file_name().map(|s| s.to_string_lossy().to_string()))
+            .unwrap_or_else(|| driver.clone());
+        let macs: Vec<String> = Self::get_mac_address(interface)
+            .into_iter()
+            .chain(
+                Command::new("ip")
+                    .args(["-o", "link", "show", "dev", interface])
+                    .output()
+                    .ok()
+                    .and_then(|o| Self::parse_permaddr(&String::from_utf8_lossy(&o.stdout))),
+            )
+            .collect();
+
+        Self::reload_module_with(&SystemRunner, &module).await?;
+        audit("driver", &format!("{} reloaded {}", interface, module));
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(DRIVER_RELOAD_TIMEOUT_SECS);
+        loop {
+            let interfaces = Self::get_interfaces().unwrap_or_default();
+            if let Some(name) = Self::find_reloaded(&interfaces, interface, &macs) {
+                return Ok(name);
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "{} did not come back within {}s of reloading {}",
+                    interface,
+                    DRIVER_RELOAD_TIMEOUT_SECS,
+                    module
+                ));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+    }
+
+    /// `modprobe -r` then `modprobe` of `module`, using the given command runner
+    pub async fn reload_module_with<R: CommandRunner>(runner: &R, module: &str) -> Result<()> {
+        let output = runner.run("modprobe", &["-r", module]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to unload {}: {}",
+                module,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let output = runner.run("modprobe", &[module]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to load {}: {}",
+                module,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Name of the interface that has one of `macs` after a reload, else one still called `old_name`
+    fn find_reloaded(interfaces: &[NetworkInterface], old_name: &str, macs: &[String]) -> Option<String> {
+        interfaces
+            .iter()
+            .find(|i| {
+                i.mac_address
+                    .as_ref()
+                    .is_some_and(|mac| macs.iter().any(|m| m.eq_ignore_ascii_case(mac)))
+            })
+            .or_else(|| interfaces.iter().find(|i| i.name == old_name))
+            .map(|i| i.name.clone())
+    }
+
+    /// Set interface MTU
+    pub async fn set_mtu(interface: &str, mtu: u32) -> Result<()> {
+        let old = std::fs::read_to_string(format!("/sys/class/net/{}/mtu", interface))
+            .map_or_else(|_| "?".to_string(), |m| m.trim().to_string());
+        Self::set_mtu_with(&SystemRunner, interface, mtu).await?;
+        audit("mtu", &format!("{} {} -> {}", interface, old, mtu));
+        Ok(())
+    }
+
+    /// `set_mtu` using the given command runner
+    pub async fn set_mtu_with<R: CommandRunner>(runner: &R, interface: &str, mtu: u32) -> Result<()> {
+        if !(MIN_MTU..=MAX_MTU).contains(&mtu) {
+            return Err(anyhow!(
+                "MTU {} is out of range ({}-{})",
+                mtu,
+                MIN_MTU,
+                MAX_MTU
+            ));
+        }
+
+        let output = runner.run("ip", &["link", "set", interface, "mtu", &mtu.to_string()]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to set MTU: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Rename interface
+    ///
+    /// Returns a warning to show the user when NetworkManager manages the
+    /// interface and is likely to undo the rename.
+    pub async fn rename_interface(interface: &str, new_name: &str) -> Result<Option<String>> {
+        let warning = Self::rename_interface_with(&SystemRunner, interface, new_name).await?;
+        audit("rename", &format!("{} -> {}", interface, new_name));
+        Ok(warning)
+    }
+
+    /// `rename_interface` using the given command runner
+    pub async fn rename_interface_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        new_name: &str,
+    ) -> Result<Option<String>> {
+        Self::validate_interface_name(new_name)?;
+        if std::path::Path::new(&format!("/sys/class/net/{}", new_name)).exists() {
+            return Err(anyhow!("Interface {} already exists", new_name));
+        }
+
+        // Ask before renaming; afterwards nmcli no longer knows the old name
+        let warning = Self::nm_rename_warning(runner, interface).await?;
+
+        // Bring interface down first
+        runner.run("ip", &["link", "set", interface, "down"]).await?;
+
+        // Rename
+        let output = runner.run("ip", &["link", "set", interface, "name", new_name]).await?;
+
+        if !output.status.success() {
+            // Don't leave the interface down under its old name
+            let _ = runner.run("ip", &["link", "set", interface, "up"]).await;
+            return Err(anyhow!(
+                "Failed to rename interface: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        // Bring interface up with new name
+        runner.run("ip", &["link", "set", new_name, "up"]).await?;
+
+        Ok(warning)
+    }
+
+    /// Arguments after `iw ... set` that tune to `channel` at `width`, or why that combination is impossible
+    ///
+    /// 20 and 40 MHz use `channel <n> HT20|HT40+|HT40-`. 80 and 160 MHz use `freq <control> <width> <centre>`,
+    /// which every iw version understands, unlike `channel <n> 80MHz`.
+    pub fn channel_args(channel: u32, width: ChannelWidth) -> Result<Vec<String>> {
+        let (freq, mhz, centre) = Self::channel_layout(channel, width)?;
+        if mhz <= 40 {
+            return Ok(vec!["channel".to_string(), channel.to_string(), width.to_string()]);
+        }
+        Ok(vec!["freq".to_string(), freq.to_string(), mhz.to_string(), centre.to_string()])
+    }
+
+    /// Control frequency, width in MHz and centre frequency of `channel` at `width`
+    ///
+    /// Fails when the secondary channels `width` needs don't exist next to `channel`.
+    pub fn channel_layout(channel: u32, width: ChannelWidth) -> Result<(u32, u32, u32)> {
+        use crate::scanner::{Band, WifiScanner};
+
+        let freq = WifiScanner::channel_to_freq(channel).ok_or_else(|| anyhow!("Unknown channel {}", channel))?;
+        let band = Band::from_frequency(freq);
+        let not_allowed = || anyhow!("Channel {} can't be used at {}", channel, width);
+        let centre = |blocks: &[(u32, u32)], span: u32| {
+            blocks
+                .iter()
+                .find(|(first, _)| band == Some(Band::Five) && (*first..=first + span).contains(&channel))
+                .map(|&(_, centre)| centre)
+        };
+
+        match width {
+            ChannelWidth::Ht20 => Ok((freq, 20, freq)),
+            // The secondary channel is 4 numbers (20 MHz) away and must exist in the same band
+            ChannelWidth::Ht40Plus => {
+                let ok = match band {
+                    Some(Band::TwoPointFour) => (1..=9).contains(&channel),
+                    Some(Band::Five) => BLOCKS_40MHZ.contains(&channel),
+                    _ => false,
+                };
+                if !ok {
+                    return Err(not_allowed());
+                }
+                Ok((freq, 40, freq + 10))
+            }
+            ChannelWidth::Ht40Minus => {
+                let ok = match band {
+                    Some(Band::TwoPointFour) => (5..=13).contains(&channel),
+                    Some(Band::Five) => channel >= 40 && BLOCKS_40MHZ.contains(&(channel - 4)),
+                    _ => false,
+                };
+                if !ok {
+                    return Err(not_allowed());
+                }
+                Ok((freq, 40, freq - 10))
+            }
+            ChannelWidth::Mhz80 | ChannelWidth::Mhz160 => {
+                let (mhz, centre) = if width == ChannelWidth::Mhz80 {
+                    (80, centre(&BLOCKS_80MHZ, 12))
+                } else {
+                    (160, centre(&BLOCKS_160MHZ, 28))
+                };
+                let centre = centre.ok_or_else(not_allowed)?;
+                Ok((freq, mhz, 5000 + 5 * centre))
+            }
+        }
+    }
+
+    /// Check a name against the kernel's rules: at most 15 bytes, no '/', ':' or whitespace
+    pub fn validate_interface_name(name: &str) -> Result<()> {
+        if name.is_empty() || name == "." || name == ".." {
+            return Err(anyhow!("Invalid interface name '{}'", name));
+        }
+        if name.len() > 15 {
+            return Err(anyhow!("Interface name '{}' is longer than 15 characters", name));
+        }
+        if let Some(c) = name.chars().find(|c| *c == '/' || *c == ':' || c.is_whitespace()) {
+            return Err(anyhow!("Interface name '{}' contains invalid character {:?}", name, c));
+        }
+        Ok(())
+    }
+
+    /// Warning for renaming an interface NetworkManager manages, if it does
+    async fn nm_rename_warning<R: CommandRunner>(runner: &R, interface: &str) -> Result<Option<String>> {
+        let Some(output) = Self::run_nmcli(runner, &["-g", "GENERAL.STATE", "device", "show", interface]).await? else {
+            return Ok(None);
+        };
+        // "100 (connected)", "30 (disconnected)", "10 (unmanaged)"
+        let state = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() || state.trim().is_empty() || state.starts_with("10 ") {
+            return Ok(None);
+        }
+
+        Ok(Some(format!(
+            "NetworkManager manages {}; it may reset or reclaim the interface. \
+             Run `nmcli device set {} managed no` first to keep the new name.",
+            interface, interface
+        )))
+    }
+
+    /// Create a bridge interface
+    pub async fn create_bridge(name: &str) -> Result<()> {
+        Self::create_bridge_with(&SystemRunner, name).await?;
+        audit("bridge", &format!("{} created", name));
+        Ok(())
+    }
+
+    /// `create_bridge` using the given command runner
+    pub async fn create_bridge_with<R: CommandRunner>(runner: &R, name: &str) -> Result<()> {
+        if std::path::Path::new(&format!("/sys/class/net/{}", name)).exists() {
+            return Err(anyhow!("Interface {} already exists", name));
+        }
+
+        let output = runner.run("ip", &["link", "add", "name", name, "type", "bridge"]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to create bridge: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Enslave an interface to a bridge
+    pub async fn add_to_bridge(bridge: &str, iface: &str) -> Result<()> {
+        Self::add_to_bridge_with(&SystemRunner, bridge, iface).await?;
+        audit("bridge", &format!("{} added to {}", iface, bridge));
+        Ok(())
+    }
+
+    /// `add_to_bridge` using the given command runner
+    pub async fn add_to_bridge_with<R: CommandRunner>(runner: &R, bridge: &str, iface: &str) -> Result<()> {
+        let output = runner.run("ip", &["link", "set", iface, "master", bridge]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to add {} to bridge: {}",
+                iface,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Delete a bridge interface (its ports are released, not removed)
+    pub async fn delete_bridge(name: &str) -> Result<()> {
+        Self::delete_bridge_with(&SystemRunner, name).await?;
+        audit("bridge", &format!("{} deleted", name));
+        Ok(())
+    }
+
+    /// `delete_bridge` using the given command runner
+    pub async fn delete_bridge_with<R: CommandRunner>(runner: &R, name: &str) -> Result<()> {
+        let output = runner.run("ip", &["link", "delete", name, "type", "bridge"]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to delete bridge: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Restart NetworkManager
+    pub async fn restart_network_manager() -> Result<()> {
+        Self::restart_network_manager_with(&SystemRunner).await?;
+        audit("restart", "NetworkManager");
+        Ok(())
+    }
+
+    /// `restart_network_manager` using the given command runner
+    pub async fn restart_network_manager_with<R: CommandRunner>(runner: &R) -> Result<()> {
+        let output = runner.run("systemctl", &["restart", "NetworkManager"]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to restart NetworkManager: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Join a WiFi network, with `password` for secured networks
+    ///
+    /// Uses NetworkManager when nmcli is installed, otherwise asks a running
+    /// wpa_supplicant through its control socket (address configuration is then
+    /// left to the system's DHCP client). The passphrase never appears in a
+    /// command line, where any local user could read it.
+    pub async fn connect(interface: &str, ssid: &str, password: Option<&str>) -> Result<()> {
+        Self::connect_with(&SystemRunner, interface, ssid, password).await?;
+        audit("connect", &format!("{} to \"{}\"", interface, ssid));
+        Ok(())
+    }
+
+    /// `connect` using the given command runner
+    pub async fn connect_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        ssid: &str,
+        password: Option<&str>,
+    ) -> Result<()> {
+        // Bound the wait so a bad passphrase doesn't hang the caller for nmcli's default 90s
+        let args = ["--wait", "30", "device", "wifi", "connect", ssid, "ifname", interface];
+        let output = match password {
+            // `--ask` makes nmcli prompt for the secret, which it then reads from stdin
+            Some(password) => {
+                let args: Vec<&str> = std::iter::once("--ask").chain(args).collect();
+                Self::nmcli_installed(runner.run_with_input("nmcli", &args, &format!("{}\n", password)).await)?
+            }
+            None => Self::run_nmcli(runner, &args).await?,
+        };
+        let Some(output) = output else {
+            let ctrl = WPA_CTRL_DIRS
+                .iter()
+                .map(|dir| std::path::Path::new(dir).join(interface))
+                .find(|path| path.exists())
+                .ok_or_else(|| anyhow!("Neither nmcli nor a wpa_supplicant control socket for {} is available", interface))?;
+            return Self::connect_wpa_ctrl(&ctrl, ssid, password).await;
+        };
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to connect to {}: {}",
+                ssid,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Add and select a network through wpa_supplicant's control socket at `ctrl`, as wpa_cli does
+    async fn connect_wpa_ctrl(ctrl: &std::path::Path, ssid: &str, password: Option<&str>) -> Result<()> {
+        // The passphrase goes between quotes on a single command line
+        if password.is_some_and(|p| p.chars().any(|c| c == '"' || c.is_control())) {
+            return Err(anyhow!("wpa_supplicant can't take a passphrase containing quotes or control characters"));
+        }
+
+        static CLIENTS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+        let client = std::env::temp_dir().join(format!(
+            "sozin-wpa-{}-{}",
+            std::process::id(),
+            CLIENTS.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&client);
+        // wpa_supplicant replies to the sender's address, so the client socket needs a path
+        let socket = tokio::net::UnixDatagram::bind(&client)?;
+        let result = async {
+            socket
+                .connect(ctrl)
+                .map_err(|e| anyhow!("Failed to reach wpa_supplicant at {}: {}", ctrl.display(), e))?;
+            let request = |command: String| {
+                let socket = &socket;
+                async move {
+                    let verb = command.split(' ').next().unwrap_or_default().to_string();
+                    socket.send(command.as_bytes()).await?;
+                    let mut reply = [0u8; 4096];
+                    let len = tokio::time::timeout(std::time::Duration::from_secs(5), socket.recv(&mut reply))
+                        .await
+                        .map_err(|_| anyhow!("wpa_supplicant didn't answer {}", verb))??;
+                    let reply = String::from_utf8_lossy(&reply[..len]).trim().to_string();
+                    if reply.starts_with("FAIL") || reply == "UNKNOWN COMMAND" {
+                        return Err(anyhow!("Failed to connect to {}: wpa_supplicant {} returned {}", ssid, verb, reply));
+                    }
+                    Ok(reply)
+                }
+            };
+
+            let id = request("ADD_NETWORK".to_string()).await?;
+            // Unquoted hex needs no escaping, whatever bytes the SSID holds
+            let hex: String = ssid.bytes().map(|b| format!("{:02x}", b)).collect();
+            request(format!("SET_NETWORK {} ssid {}", id, hex)).await?;
+            match password {
+                Some(password) => request(format!("SET_NETWORK {} psk \"{}\"", id, password)).await?,
+                None => request(format!("SET_NETWORK {} key_mgmt NONE", id)).await?,
+            };
+            request(format!("SELECT_NETWORK {}", id)).await?;
+            Ok(())
+        }
+        .await;
+        let _ = std::fs::remove_file(&client);
+        result
+    }
+
+    /// Disconnect an interface through NetworkManager; it won't auto-connect again until asked
+    pub async fn disconnect(interface: &str) -> Result<()> {
+        Self::disconnect_with(&SystemRunner, interface).await?;
+        audit("disconnect", interface);
+        Ok(())
+    }
+
+    /// `disconnect` using the given command runner
+    pub async fn disconnect_with<R: CommandRunner>(runner: &R, interface: &str) -> Result<()> {
+        let output = Self::run_nmcli(runner, &["device", "disconnect", interface])
+            .await?
+            .ok_or_else(|| anyhow!("nmcli is not installed"))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to disconnect {}: {}",
+                interface,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Profiles NetworkManager has saved, active ones first
+    pub fn list_saved_connections() -> Result<Vec<SavedConnection>> {
+        let output = match Command::new("nmcli")
+            .args(["-t", "-f", "NAME,UUID,TYPE,DEVICE", "connection", "show"])
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(anyhow!("nmcli is not installed")),
+            Err(e) => return Err(e.into()),
+        };
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to list connections: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(Self::parse_saved_connections(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse `nmcli -t -f NAME,UUID,TYPE,DEVICE connection show`
+    fn parse_saved_connections(output: &str) -> Vec<SavedConnection> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let fields = Self::split_terse(line);
+                let [name, uuid, kind, device] = <[String; 4]>::try_from(fields).ok()?;
+                Some(SavedConnection {
+                    name,
+                    uuid,
+                    kind,
+                    device: Some(device).filter(|d| !d.is_empty() && d != "--"),
+                })
+            })
+            .collect()
+    }
+
+    /// Split an nmcli terse line on unescaped colons, unescaping `\:` and `\\`
+    pub fn split_terse(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        fields.last_mut().expect("never empty").push(next);
+                    }
+                }
+                ':' => fields.push(String::new()),
+                _ => fields.last_mut().expect("never empty").push(c),
+            }
+        }
+        fields
+    }
+
+    /// Delete a saved NetworkManager profile by name
+    pub async fn delete_connection(name: &str) -> Result<()> {
+        Self::delete_connection_with(&SystemRunner, name).await?;
+        audit("connection", &format!("\"{}\" deleted", name));
+        Ok(())
+    }
+
+    /// `delete_connection` using the given command runner
+    pub async fn delete_connection_with<R: CommandRunner>(runner: &R, name: &str) -> Result<()> {
+        // `id` stops a profile named like a UUID or path from matching something else
+        let output = Self::run_nmcli(runner, &["connection", "delete", "id", name])
+            .await?
+            .ok_or_else(|| anyhow!("nmcli is not installed"))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to delete connection {}: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Spoof MAC address
+    ///
+    /// With `persist`, the address is also stored as the cloned MAC of the active
+    /// NetworkManager profile so it survives reconnects. Falls back to changing
+    /// only the link when there is no such profile.
+    pub async fn spoof_mac(interface: &str, new_mac: &str, persist: bool) -> Result<MacPersistence> {
+        let old = Self::get_mac_address(interface).unwrap_or_else(|| "?".to_string());
+        let applied = Self::spoof_mac_with(&SystemRunner, interface, new_mac, persist).await?;
+        let persisted = match &applied {
+            MacPersistence::NetworkManager(profile) => format!(" (saved on profile {})", profile),
+            MacPersistence::Runtime => String::new(),
+        };
+        audit("mac", &format!("{} {} -> {}{}", interface, old, new_mac, persisted));
+        Ok(applied)
+    }
+
+    /// `spoof_mac` using the given command runner
+    pub async fn spoof_mac_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        new_mac: &str,
+        persist: bool,
+    ) -> Result<MacPersistence> {
+        Self::validate_mac(new_mac)?;
+
+        // Look the profile up first so a persist failure doesn't leave a half-applied change
+        let setting = if persist { Self::cloned_mac_setting(runner, interface).await? } else { None };
+
+        Self::set_link_address(runner, interface, new_mac).await?;
+
+        match setting {
+            Some((profile, key)) => {
+                Self::set_cloned_mac(runner, &profile, &key, new_mac).await?;
+                Ok(MacPersistence::NetworkManager(profile))
+            }
+            None => Ok(MacPersistence::Runtime),
+        }
+    }
+
+    /// Undo `spoof_mac`: clear any NetworkManager cloned MAC and return to the permanent address
+    ///
+    /// Returns the permanent address, or `None` if the link already had it.
+    pub async fn restore_mac(interface: &str) -> Result<Option<String>> {
+        let old = Self::get_mac_address(interface).unwrap_or_else(|| "?".to_string());
+        let restored = Self::restore_mac_with(&SystemRunner, interface).await?;
+        if let Some(mac) = &restored {
+            audit("mac", &format!("{} {} -> {} (permanent)", interface, old, mac));
+        }
+        Ok(restored)
+    }
+
+    /// `restore_mac` using the given command runner
+    pub async fn restore_mac_with<R: CommandRunner>(runner: &R, interface: &str) -> Result<Option<String>> {
+        if let Some((profile, key)) = Self::cloned_mac_setting(runner, interface).await? {
+            Self::set_cloned_mac(runner, &profile, &key, "").await?;
+        }
+
+        // iproute2 only prints `permaddr` when it differs from the current address
+        let output = runner.run("ip", &["-o", "link", "show", "dev", interface]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to read link: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let permanent = Self::parse_permaddr(&String::from_utf8_lossy(&output.stdout));
+        if let Some(mac) = &permanent {
+            Self::set_link_address(runner, interface, mac).await?;
+        }
+
+        Ok(permanent)
+    }
+
+    /// Change the link-layer address; the interface must be down while it changes
+    async fn set_link_address<R: CommandRunner>(runner: &R, interface: &str, mac: &str) -> Result<()> {
+        // Bring interface down
+        runner.run("ip", &["link", "set", interface, "down"]).await?;
+
+        // Change MAC
+        let output = runner.run("ip", &["link", "set", interface, "address", mac]).await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to change MAC address: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        // Bring interface up
+        runner.run("ip", &["link", "set", interface, "up"]).await?;
+
+        Ok(())
+    }
+
+    /// Reject anything that isn't a unicast `xx:xx:xx:xx:xx:xx` address
+    pub fn validate_mac(mac: &str) -> Result<()> {
+        let octets: Vec<&str> = mac.split(':').collect();
+        if octets.len() != 6 || octets.iter().any(|o| o.len() != 2 || u8::from_str_radix(o, 16).is_err()) {
+            return Err(anyhow!("Invalid MAC address '{}': expected xx:xx:xx:xx:xx:xx", mac));
+        }
+        if u8::from_str_radix(octets[0], 16)? & 0x01 != 0 {
+            return Err(anyhow!("Invalid MAC address '{}': multicast bit is set", mac));
+        }
+        Ok(())
+    }
+
+    /// Permanent hardware address from an `ip -o link` line, if shown
+    fn parse_permaddr(line: &str) -> Option<String> {
+        let mut fields = line.split_whitespace();
+        fields.find(|f| *f == "permaddr")?;
+        fields.next().map(|s| s.trim_end_matches('\\').to_string())
+    }
+
+    /// Generate random MAC address
     pub fn generate_random_mac() -> String {
         use rand::Rng;
         let mut rng = rand::thread_rng();
@@ -430,13 +2699,78 @@ impl NetworkManager {
         )
     }
 
-    #[allow(dead_code)]
+    /// Random MAC under a real vendor OUI, so the address passes for an ordinary device
+    ///
+    /// `vendor` is matched case-insensitively against the bundled OUI table; `None` picks
+    /// any vendor.
+    pub fn generate_vendor_mac(vendor: Option<&str>) -> Result<String> {
+        use rand::seq::SliceRandom;
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+
+        let candidates: Vec<&[u8; 3]> = crate::oui::OUI_TABLE
+            .iter()
+            .filter(|(name, _)| vendor.is_none_or(|v| name.eq_ignore_ascii_case(v)))
+            .map(|(_, oui)| oui)
+            .collect();
+        let oui = candidates.choose(&mut rng).ok_or_else(|| {
+            anyhow!(
+                "Unknown vendor '{}'; known vendors: {}",
+                vendor.unwrap_or_default(),
+                crate::oui::vendors().join(", ")
+            )
+        })?;
+
+        Ok(format!(
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            // Keep the multicast bit clear
+            oui[0] & 0xFE,
+            oui[1],
+            oui[2],
+            rng.gen::<u8>(),
+            rng.gen::<u8>(),
+            rng.gen::<u8>()
+        ))
+    }
+
     /// Set wireless channel
-    pub async fn set_channel(interface: &str, channel: u32) -> Result<()> {
-        let output = AsyncCommand::new("iw")
-            .args(["dev", interface, "set", "channel", &channel.to_string()])
-            .output()
-            .await?;
+    ///
+    /// Without a width the channel is set to HT20, so iw doesn't pick one on its own.
+    pub async fn set_channel(interface: &str, channel: u32, width: Option<ChannelWidth>) -> Result<()> {
+        let width = width.unwrap_or_default();
+        let phy = Self::get_phy_for_interface(interface);
+        Self::tune_channel(interface, phy.as_deref(), channel, width).await?;
+        audit("channel", &format!("{} -> {} {}", interface, channel, width));
+        Ok(())
+    }
+
+    /// `set_channel` without the audit line, for callers that hop many channels
+    async fn tune_channel(interface: &str, phy: Option<&str>, channel: u32, width: ChannelWidth) -> Result<()> {
+        #[cfg(feature = "netlink")]
+        let native = match Self::set_channel_native(interface, channel, width) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        let result = Self::set_channel_with(&SystemRunner, interface, phy, channel, width).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("channel", native, result);
+        result
+    }
+
+    /// `set_channel` using the given command runner and `interface`'s radio, if known
+    pub async fn set_channel_with<R: CommandRunner>(
+        runner: &R,
+        interface: &str,
+        phy: Option<&str>,
+        channel: u32,
+        width: ChannelWidth,
+    ) -> Result<()> {
+        let tuning = Self::channel_args(channel, width)?;
+        // The channel belongs to the radio; setting it by phy works whichever of its interfaces is named
+        let [kind, target] = Self::radio_target(phy, interface);
+        let mut args = vec![kind.as_str(), target.as_str(), "set"];
+        args.extend(tuning.iter().map(String::as_str));
+        let output = runner.run("iw", &args).await?;
 
         if !output.status.success() {
             return Err(anyhow!(
@@ -448,3 +2782,782 @@ impl NetworkManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::mock::MockRunner;
+
+    #[test]
+    fn phy_is_read_from_sysfs_and_shared_by_interfaces_on_one_radio() {
+        let root = std::env::temp_dir().join(format!("sozin-sysfs-{}", std::process::id()));
+        for (iface, phy) in [("wlan0", Some("phy1\n")), ("wlan0mon", Some("phy1\n")), ("eth0", None)] {
+            let dir = root.join(iface);
+            std::fs::create_dir_all(dir.join("phy80211")).unwrap();
+            if let Some(phy) = phy {
+                std::fs::write(dir.join("phy80211/name"), phy).unwrap();
+            }
+        }
+
+        let phy = |iface| NetworkManager::phy_for_interface_in(&root, iface);
+        let found = (phy("wlan0"), phy("wlan0mon"), phy("eth0"), phy("missing"));
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, (Some("phy1".to_string()), Some("phy1".to_string()), None, None));
+
+        assert_eq!(NetworkManager::radio_target(Some("phy1"), "wlan0"), ["phy", "phy1"]);
+        assert_eq!(NetworkManager::radio_target(None, "wlan0"), ["dev", "wlan0"]);
+    }
+
+    #[tokio::test]
+    async fn set_channel_maps_widths_to_iw_arguments() {
+        let runner = MockRunner::new();
+        let set = |channel, width| NetworkManager::set_channel_with(&runner, "wlan0", None, channel, width);
+        set(6, ChannelWidth::Ht20).await.unwrap();
+        set(1, ChannelWidth::Ht40Plus).await.unwrap();
+        set(40, ChannelWidth::Ht40Minus).await.unwrap();
+        set(44, ChannelWidth::Mhz80).await.unwrap();
+        NetworkManager::set_channel_with(&runner, "wlan0", Some("phy1"), 100, ChannelWidth::Mhz160)
+            .await
+            .unwrap();
+        assert_eq!(
+            runner.calls(),
+            [
+                "iw dev wlan0 set channel 6 HT20",
+                "iw dev wlan0 set channel 1 HT40+",
+                "iw dev wlan0 set channel 40 HT40-",
+                "iw dev wlan0 set freq 5220 80 5210",
+                "iw phy phy1 set freq 5500 160 5570",
+            ]
+        );
+
+        // No channel above 11 to pair with, no 80 MHz in 2.4 GHz, 165 has no 80 MHz block
+        for (channel, width) in [(11, ChannelWidth::Ht40Plus), (6, ChannelWidth::Mhz80), (165, ChannelWidth::Mhz80), (15, ChannelWidth::Ht20)] {
+            assert!(set(channel, width).await.is_err(), "{} {}", channel, width);
+        }
+        assert_eq!(runner.calls().len(), 5);
+        assert_eq!("ht40+".parse(), Ok(ChannelWidth::Ht40Plus));
+        assert_eq!("80".parse(), Ok(ChannelWidth::Mhz80));
+    }
+
+    #[test]
+    fn parse_mtu_from_link_line() {
+        let line = r"2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 9000 qdisc fq_codel state UP mode DEFAULT group default qlen 1000\    link/ether 00:11:22:33:44:55 brd ff:ff:ff:ff:ff:ff";
+        assert_eq!(NetworkManager::parse_mtu(line), Some(9000));
+
+        let iface = NetworkManager::parse_interface_line(line).unwrap();
+        assert_eq!(iface.name, "eth0");
+        assert_eq!(iface.mtu, Some(9000));
+    }
+
+    #[test]
+    fn parse_vlan_name_strips_parent() {
+        let line = r"5: eth0.100@eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc noqueue state UP mode DEFAULT group default qlen 1000\    link/ether 00:11:22:33:44:55 brd ff:ff:ff:ff:ff:ff";
+        let iface = NetworkManager::parse_interface_line(line).unwrap();
+        assert_eq!(iface.name, "eth0.100");
+        assert_eq!(iface.parent.as_deref(), Some("eth0"));
+
+        let line = r"6: ip6tnl0@NONE: <NOARP> mtu 1452 qdisc noop state DOWN mode DEFAULT group default qlen 1000\    link/tunnel6 :: brd ::";
+        let iface = NetworkManager::parse_interface_line(line).unwrap();
+        assert_eq!(iface.name, "ip6tnl0");
+        assert_eq!(iface.parent, None);
+    }
+
+    #[test]
+    fn hardware_mac_survives_a_spoofed_address() {
+        let line = r"3: wlan-test7: <BROADCAST,MULTICAST,UP> mtu 1500 state UP\    link/ether 02:5e:1a:77:c0:9d brd ff:ff:ff:ff:ff:ff permaddr 00:c0:ca:11:22:33";
+        let mut iface = NetworkManager::parse_interface_line(line).unwrap();
+        iface.mac_address = Some("02:5e:1a:77:c0:9d".to_string());
+        assert_eq!(iface.hardware_mac(), Some("00:c0:ca:11:22:33"));
+
+        iface.permanent_mac = None;
+        assert_eq!(iface.hardware_mac(), Some("02:5e:1a:77:c0:9d"));
+    }
+
+    #[tokio::test]
+    async fn reset_runs_every_step_and_reports_failures() {
+        let runner = MockRunner::new().fail_on("set type managed", "command failed: Device or resource busy (-16)");
+        let steps = NetworkManager::reset_interface_with(&runner, "wlan0", true).await;
+        assert_eq!(
+            runner.calls(),
+            [
+                "ip link set wlan0 down",
+                "iw dev wlan0 set type managed",
+                "nmcli device set wlan0 managed yes",
+                "ip addr flush dev wlan0",
+                "ip link set wlan0 up",
+            ]
+        );
+        let failed: Vec<&str> = steps.iter().filter(|s| s.outcome.is_err()).map(|s| s.description.as_str()).collect();
+        assert_eq!(failed, ["Set managed mode"]);
+
+        let runner = MockRunner::new();
+        let steps = NetworkManager::reset_interface_with(&runner, "eth0", false).await;
+        assert_eq!(steps.len(), 3);
+        assert!(steps.iter().all(|s| s.outcome.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn driver_reload_unloads_then_loads_and_follows_the_mac() {
+        let runner = MockRunner::new();
+        NetworkManager::reload_module_with(&runner, "rtl88xxau").await.unwrap();
+        assert_eq!(runner.calls(), ["modprobe -r rtl88xxau", "modprobe rtl88xxau"]);
+
+        let runner = MockRunner::new().fail_on("modprobe -r", "modprobe: FATAL: Module rtl88xxau is in use.");
+        let err = NetworkManager::reload_module_with(&runner, "rtl88xxau").await.unwrap_err();
+        assert!(err.to_string().contains("in use"));
+        assert_eq!(runner.calls().len(), 1);
+
+        let iface = |name: &str, mac: &str| NetworkInterface {
+            name: name.to_string(),
+            parent: None,
+            mac_address: Some(mac.to_string()),
+            ip_address: None,
+            state: InterfaceState::Down,
+            interface_type: InterfaceType::Wireless,
+            driver: None,
+            mtu: None,
+            link_speed: None,
+            permanent_mac: None,
+            description: None,
+        };
+        let after = [iface("wlan0", "00:11:22:33:44:55"), iface("wlx00c0ca123456", "00:c0:ca:12:34:56")];
+        let macs = ["02:aa:bb:cc:dd:ee".to_string(), "00:C0:CA:12:34:56".to_string()];
+        assert_eq!(NetworkManager::find_reloaded(&after, "wlan1", &macs).as_deref(), Some("wlx00c0ca123456"));
+        assert_eq!(NetworkManager::find_reloaded(&after, "wlan0", &[]).as_deref(), Some("wlan0"));
+        assert_eq!(NetworkManager::find_reloaded(&after, "wlan1", &[]), None);
+    }
+
+    #[test]
+    fn ascii_type_icons_are_plain_ascii() {
+        for t in [
+            InterfaceType::Wireless,
+            InterfaceType::Ethernet,
+            InterfaceType::Loopback,
+            InterfaceType::Virtual,
+            InterfaceType::Unknown,
+        ] {
+            assert!(type_icon(t, true).is_ascii());
+            assert!(!type_icon(t, false).is_ascii());
+        }
+        assert_eq!(type_icon(InterfaceType::Wireless, true), "[W]");
+    }
+
+    #[test]
+    fn group_by_type_orders_groups_and_names() {
+        let iface = |name: &str, interface_type| NetworkInterface {
+            name: name.to_string(),
+            parent: None,
+            mac_address: None,
+            ip_address: None,
+            state: InterfaceState::Up,
+            interface_type,
+            driver: None,
+            mtu: None,
+            link_speed: None,
+            permanent_mac: None,
+            description: None,
+        };
+        let interfaces = vec![
+            iface("veth9", InterfaceType::Virtual),
+            iface("wlan1", InterfaceType::Wireless),
+            iface("docker0", InterfaceType::Virtual),
+            iface("eth0", InterfaceType::Ethernet),
+            iface("wlan0", InterfaceType::Wireless),
+        ];
+        let groups: Vec<(InterfaceType, Vec<String>)> = NetworkManager::group_by_type(interfaces)
+            .into_iter()
+            .map(|(kind, group)| (kind, group.into_iter().map(|i| i.name).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (InterfaceType::Wireless, vec!["wlan0".to_string(), "wlan1".to_string()]),
+                (InterfaceType::Ethernet, vec!["eth0".to_string()]),
+                (InterfaceType::Virtual, vec!["docker0".to_string(), "veth9".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_mtu_missing_or_malformed() {
+        assert_eq!(NetworkManager::parse_mtu("3: wlan0: <BROADCAST> state DOWN"), None);
+        assert_eq!(NetworkManager::parse_mtu("3: wlan0: <BROADCAST> mtu"), None);
+        assert_eq!(NetworkManager::parse_mtu("3: wlan0: <BROADCAST> mtu abc"), None);
+    }
+
+    #[test]
+    fn parses_proc_net_wireless() {
+        let contents = "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE\n \
+                        face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22\n\
+                        wlan0: 0000   58.  -52.  -256        0      0      0      3     12        0\n\
+                        wlan1: 0000   31.  -79.  -95.        0      0      0      0      0        0\n";
+        assert_eq!(
+            NetworkManager::parse_link_quality(contents, "wlan0"),
+            Some(LinkQuality { link: 58, level_dbm: -52, noise_dbm: None })
+        );
+        assert_eq!(
+            NetworkManager::parse_link_quality(contents, "wlan1"),
+            Some(LinkQuality { link: 31, level_dbm: -79, noise_dbm: Some(-95) })
+        );
+        assert_eq!(NetworkManager::parse_link_quality(contents, "wlan2"), None);
+    }
+
+    #[test]
+    fn parse_link_speed_ignores_no_link_sentinel() {
+        assert_eq!(
+            NetworkManager::parse_link_speed("1000\n", Some("full\n")),
+            Some(LinkSpeed { mbps: 1000, duplex: Duplex::Full })
+        );
+        assert_eq!(NetworkManager::parse_link_speed("-1\n", Some("unknown\n")), None);
+        assert_eq!(NetworkManager::parse_link_speed("4294967295\n", None), None);
+    }
+
+    #[test]
+    fn parse_connection_info_connected_and_not() {
+        let output = "Connected to aa:bb:cc:dd:ee:02 (on wlan0)\n\tSSID: HomeNet\n\tfreq: 2437.0\n\tRX: 1234 bytes (10 packets)\n\tsignal: -56 dBm\n\ttx bitrate: 72.2 MBit/s MCS 7 short GI\n";
+        let info = NetworkManager::parse_connection_info(output).unwrap();
+        assert_eq!(info.bssid, "aa:bb:cc:dd:ee:02");
+        assert_eq!(info.ssid.as_deref(), Some("HomeNet"));
+        assert_eq!(info.frequency, Some(2437));
+        assert_eq!(info.signal_dbm, Some(-56));
+        assert_eq!(info.tx_bitrate.as_deref(), Some("72.2 MBit/s MCS 7 short GI"));
+
+        assert_eq!(NetworkManager::parse_connection_info("Not connected.\n"), None);
+    }
+
+    #[test]
+    fn parse_supported_channels_skips_disabled() {
+        let output = "\t\tFrequencies:\n\t\t\t* 2412 MHz [1] (20.0 dBm)\n\t\t\t* 2484 MHz [14] (disabled)\n\t\t\t* 5260 MHz [52] (20.0 dBm) (radar detection)\n\t\t\t* 5180 MHz [36] (23.0 dBm)\n";
+        assert_eq!(NetworkManager::parse_supported_channels(output), [1, 36, 52]);
+        assert_eq!(NetworkManager::parse_supported_frequencies(output), [2412, 5180, 5260]);
+    }
+
+    #[test]
+    fn parse_supported_modes_stops_at_next_section() {
+        let output = "Wiphy phy0\n\tSupported interface modes:\n\t\t * IBSS\n\t\t * managed\n\t\t * AP\n\t\t * monitor\n\tBand 1:\n\t\tFrequencies:\n\t\t\t* 2412 MHz [1] (20.0 dBm)\n";
+        assert_eq!(NetworkManager::parse_supported_modes(output), ["IBSS", "managed", "AP", "monitor"]);
+        assert!(NetworkManager::parse_supported_modes("Wiphy phy0\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn set_mtu_rejects_out_of_range() {
+        assert!(NetworkManager::set_mtu("eth0", MIN_MTU - 1).await.is_err());
+        assert!(NetworkManager::set_mtu("eth0", MAX_MTU + 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn enable_monitor_mode_cycles_link_around_type_change() {
+        let runner = MockRunner::new();
+        NetworkManager::enable_monitor_mode_with(&runner, "wlan0", false).await.unwrap();
+        assert_eq!(
+            runner.calls(),
+            [
+                "iw dev wlan0 info",
+                "ip -o link show dev wlan0",
+                "ip link set wlan0 down",
+                "iw dev wlan0 set type monitor",
+                "ip link set wlan0 up",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn monitor_mode_unmanage_wraps_type_change_with_nmcli() {
+        let runner = MockRunner::new();
+        NetworkManager::enable_monitor_mode_with(&runner, "wlan0", true).await.unwrap();
+        NetworkManager::disable_monitor_mode_with(&runner, "wlan0", true).await.unwrap();
+        assert_eq!(
+            runner.calls(),
+            [
+                "iw dev wlan0 info",
+                "ip -o link show dev wlan0",
+                "nmcli device set wlan0 managed no",
+                "ip link set wlan0 down",
+                "iw dev wlan0 set type monitor",
+                "ip link set wlan0 up",
+                "ip link set wlan0 down",
+                "iw dev wlan0 set type managed",
+                "ip link set wlan0 up",
+                "nmcli device set wlan0 managed yes",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enable_monitor_mode_reports_iw_stderr() {
+        let runner = MockRunner::new().fail_on("set type monitor", "command failed: Device or resource busy (-16)");
+        let err = NetworkManager::enable_monitor_mode_with(&runner, "wlan0", false).await.unwrap_err();
+        assert!(err.to_string().contains("Device or resource busy"));
+    }
+
+    #[tokio::test]
+    async fn enable_monitor_mode_restores_mode_and_state_on_failure() {
+        let runner = MockRunner::new()
+            .stdout_for("iw dev wlan0 info", "Interface wlan0\n\tifindex 3\n\ttype managed\n")
+            .stdout_for("ip -o link show dev wlan0", "3: wlan0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 state UP")
+            .fail_on("set type monitor", "command failed: Operation not supported (-95)");
+        assert!(NetworkManager::enable_monitor_mode_with(&runner, "wlan0", true).await.is_err());
+        assert_eq!(
+            runner.calls(),
+            [
+                "iw dev wlan0 info",
+                "ip -o link show dev wlan0",
+                "nmcli device set wlan0 managed no",
+                "ip link set wlan0 down",
+                "iw dev wlan0 set type monitor",
+                "iw dev wlan0 set type managed",
+                "ip link set wlan0 up",
+                "nmcli device set wlan0 managed yes",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enable_monitor_mode_rolls_back_when_bring_up_fails() {
+        let runner = MockRunner::new()
+            .stdout_for("iw dev wlan0 info", "Interface wlan0\n\tifindex 3\n\ttype managed\n")
+            .stdout_for("ip -o link show dev wlan0", "3: wlan0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 state UP")
+            .fail_on("ip link set wlan0 up", "RTNETLINK answers: Operation not permitted");
+        let err = NetworkManager::enable_monitor_mode_with(&runner, "wlan0", true).await.unwrap_err();
+        assert!(err.to_string().contains("Failed to bring up interface in Monitor mode"));
+        assert_eq!(
+            runner.calls(),
+            [
+                "iw dev wlan0 info",
+                "ip -o link show dev wlan0",
+                "nmcli device set wlan0 managed no",
+                "ip link set wlan0 down",
+                "iw dev wlan0 set type monitor",
+                "ip link set wlan0 up",
+                "iw dev wlan0 set type managed",
+                "ip link set wlan0 up",
+                "nmcli device set wlan0 managed yes",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn enable_monitor_mode_leaves_down_interface_down_on_failure() {
+        let runner = MockRunner::new()
+            .stdout_for("iw dev wlan0 info", "Interface wlan0\n\ttype managed\n")
+            .stdout_for("ip -o link show dev wlan0", "3: wlan0: <BROADCAST,MULTICAST> mtu 1500 state DOWN")
+            .fail_on("set type monitor", "command failed: Device or resource busy (-16)");
+        assert!(NetworkManager::enable_monitor_mode_with(&runner, "wlan0", false).await.is_err());
+        assert_eq!(runner.calls().last().map(String::as_str), Some("iw dev wlan0 set type managed"));
+        assert!(!runner.calls().contains(&"ip link set wlan0 up".to_string()));
+    }
+
+    #[tokio::test]
+    async fn connect_passes_password_only_when_given() {
+        let runner = MockRunner::new();
+        NetworkManager::connect_with(&runner, "wlan0", "Cafe", None).await.unwrap();
+        NetworkManager::connect_with(&runner, "wlan0", "HomeNet", Some("hunter22")).await.unwrap();
+        assert_eq!(
+            runner.calls(),
+            [
+                "nmcli --wait 30 device wifi connect Cafe ifname wlan0",
+                "nmcli --ask --wait 30 device wifi connect HomeNet ifname wlan0",
+            ]
+        );
+        // The passphrase goes to stdin, never into the command line
+        assert_eq!(runner.inputs(), ["hunter22\n"]);
+        assert!(runner.calls().iter().all(|call| !call.contains("hunter22")));
+
+        let runner = MockRunner::new().fail_on("wifi connect", "Error: Secrets were required, but not provided.");
+        let err = NetworkManager::connect_with(&runner, "wlan0", "HomeNet", Some("wrong")).await.unwrap_err();
+        assert!(err.to_string().contains("Secrets were required"));
+    }
+
+    #[tokio::test]
+    async fn wpa_supplicant_gets_hex_ssid_over_its_control_socket() {
+        let dir = std::env::temp_dir().join(format!("sozin-wpa-ctrl-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ctrl = dir.join("wlan0");
+        let _ = std::fs::remove_file(&ctrl);
+        let server = tokio::net::UnixDatagram::bind(&ctrl).unwrap();
+        let supplicant = tokio::spawn(async move {
+            let mut commands = Vec::new();
+            let mut buf = [0u8; 512];
+            while commands.len() < 4 {
+                let (len, from) = server.recv_from(&mut buf).await.unwrap();
+                let command = String::from_utf8_lossy(&buf[..len]).to_string();
+                let reply = if command == "ADD_NETWORK" { "3\n" } else { "OK\n" };
+                server.send_to(reply.as_bytes(), from.as_pathname().unwrap()).await.unwrap();
+                commands.push(command);
+            }
+            commands
+        });
+
+        NetworkManager::connect_wpa_ctrl(&ctrl, "Caf\"é", Some("correct horse")).await.unwrap();
+        assert_eq!(
+            supplicant.await.unwrap(),
+            [
+                "ADD_NETWORK",
+                "SET_NETWORK 3 ssid 43616622c3a9",
+                "SET_NETWORK 3 psk \"correct horse\"",
+                "SELECT_NETWORK 3",
+            ]
+        );
+
+        let err = NetworkManager::connect_wpa_ctrl(&ctrl, "Cafe", Some("say \"hi\"")).await.unwrap_err();
+        assert!(err.to_string().contains("quotes"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn rename_failure_brings_old_name_back_up() {
+        let runner = MockRunner::new().fail_on("name wlan9", "RTNETLINK answers: Device or resource busy");
+        assert!(NetworkManager::rename_interface_with(&runner, "wlan0", "wlan9").await.is_err());
+        assert_eq!(
+            runner.calls(),
+            [
+                "nmcli -g GENERAL.STATE device show wlan0",
+                "ip link set wlan0 down",
+                "ip link set wlan0 name wlan9",
+                "ip link set wlan0 up",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rename_rejects_bad_or_taken_names_before_touching_link() {
+        let runner = MockRunner::new();
+        for name in ["", "wlan-name-too-long", "wl an", "wlan:1", "a/b", "lo"] {
+            assert!(NetworkManager::rename_interface_with(&runner, "wlan0", name).await.is_err(), "{:?}", name);
+        }
+        assert!(runner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rename_warns_when_networkmanager_manages_interface() {
+        let runner = MockRunner::new().stdout_for("GENERAL.STATE", "100 (connected)\n");
+        let warning = NetworkManager::rename_interface_with(&runner, "wlan0", "wlan9").await.unwrap();
+        assert!(warning.is_some_and(|w| w.contains("managed no")));
+
+        let runner = MockRunner::new().stdout_for("GENERAL.STATE", "10 (unmanaged)\n");
+        assert_eq!(NetworkManager::rename_interface_with(&runner, "wlan0", "wlan9").await.unwrap(), None);
+    }
+
+    #[test]
+    fn udev_description_prefers_the_hardware_database() {
+        let usb = "ID_VENDOR=Realtek\nID_MODEL=802.11ac_NIC\nID_VENDOR_FROM_DATABASE=Realtek Semiconductor Corp.\n\
+                   ID_MODEL_FROM_DATABASE=RTL8812AU 802.11a/b/g/n/ac 2T2R DB WLAN Adapter\n";
+        assert_eq!(
+            NetworkManager::parse_udev_description(usb).as_deref(),
+            Some("Realtek Semiconductor Corp. RTL8812AU 802.11a/b/g/n/ac 2T2R DB WLAN Adapter")
+        );
+        assert_eq!(
+            NetworkManager::parse_udev_description("ID_MODEL=802.11ac_NIC\n").as_deref(),
+            Some("802.11ac NIC")
+        );
+        assert_eq!(NetworkManager::parse_udev_description("INTERFACE=veth0\nID_NET_DRIVER=veth\n"), None);
+    }
+
+    #[test]
+    fn management_backend_prefers_networkmanager_then_networkd() {
+        let nmcli = "wlan0:connected\neth0:unmanaged\nlo:unmanaged\n";
+        let networkctl = "  1 lo   loopback carrier   unmanaged\n  2 eth0 ether    routable  configured\n  3 wlan0 wlan  routable  unmanaged\n";
+        let backend = |nm, nd, iface| NetworkManager::management_backend(nm, nd, iface);
+
+        assert_eq!(backend(Some(nmcli), Some(networkctl), "wlan0"), ManagementBackend::NetworkManager);
+        assert_eq!(backend(Some(nmcli), Some(networkctl), "eth0"), ManagementBackend::SystemdNetworkd);
+        assert_eq!(backend(Some(nmcli), Some(networkctl), "lo"), ManagementBackend::Unmanaged);
+        // Not listed by the one tool that answered
+        assert_eq!(backend(None, Some(networkctl), "wlan1"), ManagementBackend::Unmanaged);
+        assert_eq!(backend(None, None, "wlan0"), ManagementBackend::Unknown);
+    }
+
+    #[test]
+    fn parse_wireless_mode_recognises_access_points() {
+        assert_eq!(NetworkManager::parse_wireless_mode("Interface wlan0\n\ttype AP\n"), WirelessMode::Master);
+        assert_eq!(NetworkManager::parse_wireless_mode("Interface wlan0\n\ttype managed\n"), WirelessMode::Managed);
+        assert_eq!(NetworkManager::parse_wireless_mode("Interface wlan0\n\ttype P2P-client\n"), WirelessMode::Managed);
+        assert_eq!(NetworkManager::parse_wireless_mode("Interface wlan0\n\ttype mesh point\n"), WirelessMode::Unknown);
+    }
+
+    #[test]
+    fn current_channel_is_read_from_iw_info() {
+        let info = |channel: &str| format!("Interface wlan0mon\n\tifindex 5\n\ttype monitor\n\t{}\n\ttxpower 20.00 dBm\n", channel);
+        let parse = |channel: &str| NetworkManager::parse_current_channel(&info(channel));
+        assert_eq!(parse("channel 6 (2437 MHz), width: 20 MHz (no HT), center1: 2437 MHz"), Some((6, ChannelWidth::Ht20)));
+        assert_eq!(parse("channel 1 (2412 MHz), width: 40 MHz, center1: 2422 MHz"), Some((1, ChannelWidth::Ht40Plus)));
+        assert_eq!(parse("channel 40 (5200 MHz), width: 40 MHz, center1: 5190 MHz"), Some((40, ChannelWidth::Ht40Minus)));
+        assert_eq!(parse("channel 36 (5180 MHz), width: 80 MHz, center1: 5210 MHz"), Some((36, ChannelWidth::Mhz80)));
+        assert_eq!(NetworkManager::parse_current_channel("Interface wlan0mon\n\ttype monitor\n"), None);
+    }
+
+    #[test]
+    fn iwconfig_mode_is_read_when_iw_has_no_type() {
+        let monitor = "wlan1     IEEE 802.11b  ESSID:off/any  \n          \
+                       Mode:Monitor  Frequency:2.437 GHz  Tx-Power=20 dBm   \n          \
+                       Retry short limit:7   RTS thr:off   Fragment thr:off\n";
+        assert_eq!(NetworkManager::parse_iwconfig_mode(monitor), WirelessMode::Monitor);
+        let managed = "wlan0     IEEE 802.11  ESSID:\"HomeNet\"  \n          \
+                       Mode:Managed  Frequency:5.18 GHz  Access Point: AA:BB:CC:DD:EE:01   \n";
+        assert_eq!(NetworkManager::parse_iwconfig_mode(managed), WirelessMode::Managed);
+        assert_eq!(NetworkManager::parse_iwconfig_mode("eth0      no wireless extensions.\n"), WirelessMode::Unknown);
+        // iw from a driver without nl80211 type reporting prints no type line at all
+        assert_eq!(NetworkManager::parse_wireless_mode("Interface wlan1\n\tifindex 4\n\twdev 0x1\n"), WirelessMode::Unknown);
+    }
+
+    #[test]
+    fn parse_rfkill_json_and_text_agree() {
+        let json = r#"{"": [
+            {"id": 0, "type": "wlan", "device": "phy0", "soft": "unblocked", "hard": "unblocked"},
+            {"id": 1, "type": "bluetooth", "device": "hci0", "soft": "blocked", "hard": "unblocked"}
+        ]}"#;
+        let text = "0: phy0: Wireless LAN\n\tSoft blocked: no\n\tHard blocked: no\n1: hci0: Bluetooth\n\tSoft blocked: yes\n\tHard blocked: no\n";
+
+        let entries = NetworkManager::parse_rfkill_json(json).unwrap();
+        assert_eq!(entries, NetworkManager::parse_rfkill_text(text));
+        assert_eq!(entries[1].kind, "bluetooth");
+        assert!(entries[1].soft_blocked && !entries[1].hard_blocked);
+        assert!(NetworkManager::parse_rfkill_json("not json").is_none());
+    }
+
+    #[test]
+    fn relative_activity_is_share_of_sweep() {
+        let activity = NetworkManager::relative_activity(&[(1, 300), (6, 100), (11, 0)]);
+        let percents: Vec<u8> = activity.iter().map(|a| a.percent).collect();
+        assert_eq!(percents, [75, 25, 0]);
+        assert!(NetworkManager::relative_activity(&[(1, 0)]).iter().all(|a| a.percent == 0));
+    }
+
+    #[test]
+    fn wireless_mode_parses_iw_and_iwconfig_names() {
+        assert_eq!("station".parse(), Ok(WirelessMode::Managed));
+        assert_eq!("AP".parse(), Ok(WirelessMode::Master));
+        assert_eq!("ibss".parse(), Ok(WirelessMode::Adhoc));
+        assert!("mesh".parse::<WirelessMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn set_wireless_mode_checks_phy_support() {
+        let supported: Vec<String> = ["managed", "monitor", "IBSS"].map(String::from).to_vec();
+        let runner = MockRunner::new();
+        let err = NetworkManager::set_wireless_mode_with(&runner, "wlan0", WirelessMode::Master, &supported)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not support Master mode"));
+        assert!(runner.calls().is_empty());
+
+        NetworkManager::set_wireless_mode_with(&runner, "wlan0", WirelessMode::Adhoc, &supported)
+            .await
+            .unwrap();
+        assert_eq!(
+            runner.calls()[2..],
+            ["ip link set wlan0 down", "iw dev wlan0 set type ibss", "ip link set wlan0 up"]
+        );
+    }
+
+    #[test]
+    fn suggest_monitor_name_avoids_existing_interfaces() {
+        let existing: Vec<String> = ["wlan0", "wlan0mon", "wlan0mon1", "eth0"].map(String::from).to_vec();
+        assert_eq!(NetworkManager::suggest_monitor_name_among("wlan1", &existing), "wlan1mon");
+        assert_eq!(NetworkManager::suggest_monitor_name_among("wlan0", &existing), "wlan0mon2");
+
+        // Long predictable names are cut to fit 15 bytes
+        let name = NetworkManager::suggest_monitor_name_among("wlx00c0ca123456", &existing);
+        assert_eq!(name, "wlx00c0ca123mon");
+        assert!(NetworkManager::validate_interface_name(&name).is_ok());
+    }
+
+    #[tokio::test]
+    async fn create_monitor_interface_rolls_back_when_link_up_fails() {
+        let runner = MockRunner::new().fail_on("ip link set", "RTNETLINK answers: Operation not supported");
+        let err = NetworkManager::create_monitor_interface_with(&runner, "wlan0", None, "wlan0mon")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to bring up wlan0mon"));
+        assert_eq!(
+            runner.calls(),
+            [
+                "iw dev wlan0 interface add wlan0mon type monitor",
+                "ip link set wlan0mon up",
+                "iw dev wlan0mon del",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_saved_connections_handles_escaped_colons() {
+        let output = "Home\\:Net:0b1d6a2c-1111-4222-8333-944455556666:802-11-wireless:wlan0\n\
+                      Wired connection 1:5c2e7f10-aaaa-4bbb-8ccc-dddd00001111:802-3-ethernet:\n";
+        let connections = NetworkManager::parse_saved_connections(output);
+        assert_eq!(connections.len(), 2);
+        assert_eq!(connections[0].name, "Home:Net");
+        assert_eq!(connections[0].kind, "802-11-wireless");
+        assert_eq!(connections[0].device.as_deref(), Some("wlan0"));
+        assert_eq!(connections[1].device, None);
+    }
+
+    #[tokio::test]
+    async fn disconnect_and_delete_use_nmcli() {
+        let runner = MockRunner::new();
+        NetworkManager::disconnect_with(&runner, "wlan0").await.unwrap();
+        NetworkManager::delete_connection_with(&runner, "Home:Net").await.unwrap();
+        assert_eq!(runner.calls(), ["nmcli device disconnect wlan0", "nmcli connection delete id Home:Net"]);
+    }
+
+    #[test]
+    fn ap_files_stay_private_and_only_hostapd_is_killed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("sozin-ap-{}", std::process::id()));
+        let dir = root.join("run");
+        std::fs::create_dir_all(&root).unwrap();
+        let (config, pid) = NetworkManager::ap_paths_in(&dir, "wlan0").unwrap();
+        assert_eq!(config, dir.join("hostapd-wlan0.conf"));
+        assert_eq!(pid, dir.join("hostapd-wlan0.pid"));
+        assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+
+        // Open to others, or a symlink to somewhere else: refused
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(NetworkManager::ap_paths_in(&dir, "wlan0").is_err());
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&root, &link).unwrap();
+        assert!(NetworkManager::ap_paths_in(&link, "wlan0").is_err());
+
+        let proc = root.join("proc");
+        for (pid, comm) in [("100", "hostapd\n"), ("200", "sshd\n")] {
+            std::fs::create_dir_all(proc.join(pid)).unwrap();
+            std::fs::write(proc.join(pid).join("comm"), comm).unwrap();
+        }
+        assert_eq!(NetworkManager::hostapd_pid("100\n", &proc), Some(100));
+        assert_eq!(NetworkManager::hostapd_pid("200\n", &proc), None);
+        assert_eq!(NetworkManager::hostapd_pid("300", &proc), None);
+        assert_eq!(NetworkManager::hostapd_pid("1 && reboot", &proc), None);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn hostapd_config_for_open_and_wpa2() {
+        let open = NetworkManager::hostapd_config("wlan0", "Lab", 6, None).unwrap();
+        assert_eq!(open, "interface=wlan0\ndriver=nl80211\nssid=Lab\nhw_mode=g\nchannel=6\nieee80211n=1\n");
+
+        let secured = NetworkManager::hostapd_config("wlan0", "Lab", 36, Some("correct horse")).unwrap();
+        assert!(secured.contains("hw_mode=a\n"));
+        assert!(secured.ends_with("wpa=2\nwpa_key_mgmt=WPA-PSK\nrsn_pairwise=CCMP\nwpa_passphrase=correct horse\n"));
+
+        assert!(NetworkManager::hostapd_config("wlan0", "Lab", 15, None).is_err());
+        assert!(NetworkManager::hostapd_config("wlan0", "", 6, None).is_err());
+        assert!(NetworkManager::hostapd_config("wlan0", "Lab", 6, Some("short")).is_err());
+    }
+
+    #[tokio::test]
+    async fn spoof_mac_sets_address_while_down() {
+        let runner = MockRunner::new();
+        let applied = NetworkManager::spoof_mac_with(&runner, "wlan0", "02:11:22:33:44:55", false).await.unwrap();
+        assert_eq!(applied, MacPersistence::Runtime);
+        assert_eq!(
+            runner.calls(),
+            [
+                "ip link set wlan0 down",
+                "ip link set wlan0 address 02:11:22:33:44:55",
+                "ip link set wlan0 up",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn spoof_mac_persists_on_active_profile() {
+        let runner = MockRunner::new()
+            .stdout_for("GENERAL.CONNECTION device show wlan0", "Home\\:Net\n")
+            .stdout_for("connection.type connection show Home:Net", "802-11-wireless\n");
+        let applied = NetworkManager::spoof_mac_with(&runner, "wlan0", "02:11:22:33:44:55", true).await.unwrap();
+        assert_eq!(applied, MacPersistence::NetworkManager("Home:Net".to_string()));
+        assert_eq!(
+            runner.calls().last().map(String::as_str),
+            Some("nmcli connection modify Home:Net 802-11-wireless.cloned-mac-address 02:11:22:33:44:55")
+        );
+
+        // No active connection: only the link changes
+        let runner = MockRunner::new().stdout_for("GENERAL.CONNECTION", "\n");
+        let applied = NetworkManager::spoof_mac_with(&runner, "eth0", "02:11:22:33:44:55", true).await.unwrap();
+        assert_eq!(applied, MacPersistence::Runtime);
+        assert!(!runner.calls().iter().any(|c| c.contains("connection modify")));
+    }
+
+    #[test]
+    fn vendor_mac_uses_a_real_oui() {
+        for _ in 0..20 {
+            let mac = NetworkManager::generate_vendor_mac(None).unwrap();
+            NetworkManager::validate_mac(&mac).unwrap();
+            assert!(crate::oui::vendor_for(&mac).is_some(), "{}", mac);
+        }
+        let mac = NetworkManager::generate_vendor_mac(Some("raspberry pi")).unwrap();
+        assert_eq!(crate::oui::vendor_for(&mac), Some("Raspberry Pi"));
+        assert!(NetworkManager::generate_vendor_mac(Some("Nokia")).is_err());
+    }
+
+    #[tokio::test]
+    async fn spoof_mac_rejects_bad_addresses_before_touching_link() {
+        let runner = MockRunner::new();
+        for mac in ["02:11:22:33:44", "zz:11:22:33:44:55", "01:11:22:33:44:55"] {
+            assert!(NetworkManager::spoof_mac_with(&runner, "wlan0", mac, false).await.is_err());
+        }
+        assert!(runner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restore_mac_clears_profile_and_resets_permanent_address() {
+        let runner = MockRunner::new()
+            .stdout_for("GENERAL.CONNECTION device show eth0", "Wired\n")
+            .stdout_for("connection.type connection show Wired", "802-3-ethernet\n")
+            .stdout_for(
+                "ip -o link show dev eth0",
+                "2: eth0: <BROADCAST,MULTICAST,UP> mtu 1500 state UP\\    link/ether 02:11:22:33:44:55 brd ff:ff:ff:ff:ff:ff permaddr 00:1a:2b:3c:4d:5e",
+            );
+        let restored = NetworkManager::restore_mac_with(&runner, "eth0").await.unwrap();
+        assert_eq!(restored.as_deref(), Some("00:1a:2b:3c:4d:5e"));
+        let calls = runner.calls();
+        assert!(calls.contains(&"nmcli connection modify Wired 802-3-ethernet.cloned-mac-address ".to_string()));
+        assert_eq!(calls.last().map(String::as_str), Some("ip link set eth0 up"));
+        assert!(calls.contains(&"ip link set eth0 address 00:1a:2b:3c:4d:5e".to_string()));
+    }
+
+    #[tokio::test]
+    async fn bridge_lifecycle_commands() {
+        let runner = MockRunner::new();
+        NetworkManager::create_bridge_with(&runner, "sozbr0").await.unwrap();
+        NetworkManager::add_to_bridge_with(&runner, "sozbr0", "eth1").await.unwrap();
+        NetworkManager::delete_bridge_with(&runner, "sozbr0").await.unwrap();
+        assert_eq!(
+            runner.calls(),
+            [
+                "ip link add name sozbr0 type bridge",
+                "ip link set eth1 master sozbr0",
+                "ip link delete sozbr0 type bridge",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn create_bridge_refuses_existing_interface() {
+        let runner = MockRunner::new();
+        let err = NetworkManager::create_bridge_with(&runner, "lo").await.unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn bring_up_reports_ip_stderr() {
+        let runner = MockRunner::new().fail_on("ip link set eth9 up", "Cannot find device \"eth9\"");
+        let err = NetworkManager::bring_up_with(&runner, "eth9").await.unwrap_err();
+        assert!(err.to_string().contains("Cannot find device"));
+    }
+
+    #[test]
+    fn link_up_request_sets_only_iff_up() {
+        let msg = NetworkManager::link_flags_request(7, true, 4);
+        let word = |at: usize| u32::from_ne_bytes(msg[at..at + 4].try_into().unwrap());
+        assert_eq!(msg.len(), 32);
+        assert_eq!(word(0), 32);
+        assert_eq!(i32::from_ne_bytes(msg[20..24].try_into().unwrap()), 7);
+        assert_eq!((word(24), word(28)), (libc::IFF_UP as u32, libc::IFF_UP as u32));
+        assert_eq!(u32::from_ne_bytes(NetworkManager::link_flags_request(7, false, 1)[24..28].try_into().unwrap()), 0);
+
+        let parsed = crate::netlink::messages(&msg).next().unwrap();
+        assert_eq!((parsed.msg_type, parsed.seq), (libc::RTM_NEWLINK, 4));
+    }
+}
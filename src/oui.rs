@@ -0,0 +1,72 @@
+/// Organizationally Unique Identifiers of common client and router vendors
+///
+/// Deliberately small: enough to make a spoofed address look like an everyday device
+/// and to name the vendor of one, not a full copy of the IEEE registry.
+pub const OUI_TABLE: &[(&str, [u8; 3])] = &[
+    ("Apple", [0x00, 0x03, 0x93]),
+    ("Apple", [0x00, 0x1b, 0x63]),
+    ("Apple", [0x00, 0x25, 0x00]),
+    ("Apple", [0x00, 0x26, 0xbb]),
+    ("Samsung", [0x00, 0x12, 0xfb]),
+    ("Samsung", [0x00, 0x16, 0x32]),
+    ("Samsung", [0x00, 0x1d, 0x25]),
+    ("Google", [0x00, 0x1a, 0x11]),
+    ("Google", [0x3c, 0x5a, 0xb4]),
+    ("Google", [0xf4, 0xf5, 0xd8]),
+    ("Intel", [0x00, 0x13, 0xe8]),
+    ("Intel", [0x00, 0x1b, 0x21]),
+    ("Intel", [0x00, 0x24, 0xd7]),
+    ("Microsoft", [0x00, 0x50, 0xf2]),
+    ("Dell", [0x00, 0x14, 0x22]),
+    ("Dell", [0x00, 0x1a, 0xa0]),
+    ("Raspberry Pi", [0xb8, 0x27, 0xeb]),
+    ("Raspberry Pi", [0xdc, 0xa6, 0x32]),
+    ("Realtek", [0x00, 0xe0, 0x4c]),
+    ("Broadcom", [0x00, 0x10, 0x18]),
+    ("Netgear", [0x00, 0x09, 0x5b]),
+    ("Netgear", [0x00, 0x14, 0x6c]),
+    ("TP-Link", [0x50, 0xc7, 0xbf]),
+    ("TP-Link", [0xf4, 0xec, 0x38]),
+    ("D-Link", [0x00, 0x05, 0x5d]),
+    ("D-Link", [0x00, 0x1e, 0x58]),
+    ("Cisco-Linksys", [0x00, 0x18, 0x39]),
+    ("Cisco-Linksys", [0x00, 0x1c, 0x10]),
+];
+
+/// Vendor owning the OUI of `mac`, if it is in the table
+pub fn vendor_for(mac: &str) -> Option<&'static str> {
+    let octets: Vec<u8> = mac
+        .split(':')
+        .take(3)
+        .map(|o| u8::from_str_radix(o, 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    OUI_TABLE.iter().find(|(_, oui)| oui[..] == octets[..]).map(|(vendor, _)| *vendor)
+}
+
+/// Vendor names in the table, each listed once
+pub fn vendors() -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = OUI_TABLE.iter().map(|(vendor, _)| *vendor).collect();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_holds_only_unicast_global_ouis() {
+        for (vendor, oui) in OUI_TABLE {
+            assert_eq!(oui[0] & 0x03, 0, "{} {:02x?}", vendor, oui);
+        }
+    }
+
+    #[test]
+    fn looks_up_vendor_by_prefix() {
+        assert_eq!(vendor_for("B8:27:EB:12:34:56"), Some("Raspberry Pi"));
+        assert_eq!(vendor_for("02:00:00:00:00:01"), None);
+        assert_eq!(vendor_for("garbage"), None);
+        assert_eq!(vendors().iter().filter(|v| **v == "Apple").count(), 1);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::network::{InterfaceInfo, NetworkInterface, RfkillEntry, SavedConnection};
+use crate::scanner::WifiNetwork;
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Output format shared by every command that prints data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+/// Version of the `--json` envelope; bump when a field is renamed, removed or changes meaning
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Wrapper around every JSON document sozin prints, so consumers can check compatibility
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonEnvelope<T> {
+    pub schema_version: u32,
+    pub tool: String,
+    pub data: T,
+}
+
+impl<T> JsonEnvelope<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tool: "sozin".to_string(),
+            data,
+        }
+    }
+}
+
+/// Pretty-printed JSON of `data` inside the envelope
+pub fn to_json<T: Serialize>(data: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&JsonEnvelope::new(data))?)
+}
+
+/// Single-line JSON of `data` inside the envelope, for JSON Lines streams
+pub fn to_json_line<T: Serialize>(data: &T) -> Result<String> {
+    Ok(serde_json::to_string(&JsonEnvelope::new(data))?)
+}
+
+/// A record that can be flattened into one row of a table or CSV file
+pub trait TableRow {
+    /// Column names, in the order `row` returns values
+    fn headers() -> &'static [&'static str];
+    /// Cell values; missing values are empty strings
+    fn row(&self) -> Vec<String>;
+}
+
+/// Print `items` to stdout in the requested format
+pub fn render_output<T: Serialize + TableRow>(items: &[T], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", to_json(&items)?),
+        OutputFormat::Csv => {
+            println!("{}", csv_line(T::headers().iter().map(|h| h.to_string())));
+            for item in items {
+                println!("{}", csv_line(item.row()));
+            }
+        }
+        OutputFormat::Table => {
+            let rows: Vec<Vec<String>> = items.iter().map(|i| i.row()).collect();
+            let widths: Vec<usize> = T::headers()
+                .iter()
+                .enumerate()
+                .map(|(col, h)| rows.iter().map(|r| r[col].width()).chain([h.len()]).max().unwrap_or(0))
+                .collect();
+
+            let header: Vec<String> = T::headers()
+                .iter()
+                .zip(&widths)
+                .map(|(h, w)| format!("{:<w$}", h, w = *w).cyan().to_string())
+                .collect();
+            println!("  {}", header.join("  "));
+            for row in rows {
+                let cells: Vec<String> = row.iter().zip(&widths).map(|(c, w)| pad_display(c, *w)).collect();
+                println!("  {}", cells.join("  ").trim_end());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Cut `s` to at most `max` terminal columns, ending in `…` when anything was dropped
+///
+/// Counts display width rather than bytes or chars, so emoji and CJK SSIDs neither
+/// panic nor push the columns after them out of line.
+pub fn truncate_display(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut width = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        // Leave a column for the ellipsis
+        if width + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        width += w;
+    }
+    if max > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// `s` followed by spaces up to `width` terminal columns
+pub fn pad_display(s: &str, width: usize) -> String {
+    format!("{}{}", s, " ".repeat(width.saturating_sub(s.width())))
+}
+
+/// Join fields into a CSV line, quoting any that contain separators or quotes
+fn csv_line<I: IntoIterator<Item = String>>(fields: I) -> String {
+    fields
+        .into_iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn opt<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl TableRow for NetworkInterface {
+    fn headers() -> &'static [&'static str] {
+        &["name", "type", "state", "mac_address", "ip_address", "driver", "mtu", "description"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.interface_type.to_string(),
+            self.state.to_string(),
+            opt(self.mac_address.as_ref()),
+            opt(self.ip_address.as_ref()),
+            opt(self.driver.as_ref()),
+            opt(self.mtu),
+            opt(self.description.as_ref()),
+        ]
+    }
+}
+
+impl TableRow for WifiNetwork {
+    fn headers() -> &'static [&'static str] {
+        &["ssid", "bssid", "channel", "frequency", "signal_strength", "security", "hidden"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.ssid.clone(),
+            self.bssid.clone(),
+            self.channel.to_string(),
+            self.frequency.to_string(),
+            self.signal_strength.to_string(),
+            self.security.to_string(),
+            self.hidden.to_string(),
+        ]
+    }
+}
+
+impl TableRow for InterfaceInfo {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "name", "type", "state", "mac_address", "ip_addresses", "driver", "mtu", "wireless_mode",
+            "connected_bssid", "connected_ssid", "signal_dbm", "rx_bytes", "tx_bytes",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let iface = &self.interface;
+        let conn = self.connection.as_ref();
+        vec![
+            iface.name.clone(),
+            iface.interface_type.to_string(),
+            iface.state.to_string(),
+            opt(iface.mac_address.as_ref()),
+            self.ip_addresses.join(" "),
+            opt(iface.driver.as_ref()),
+            opt(iface.mtu),
+            opt(self.wireless_mode),
+            opt(conn.map(|c| &c.bssid)),
+            opt(conn.and_then(|c| c.ssid.as_ref())),
+            opt(conn.and_then(|c| c.signal_dbm)),
+            opt(self.stats.map(|s| s.rx_bytes)),
+            opt(self.stats.map(|s| s.tx_bytes)),
+        ]
+    }
+}
+
+impl TableRow for SavedConnection {
+    fn headers() -> &'static [&'static str] {
+        &["name", "uuid", "type", "device"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![self.name.clone(), self.uuid.clone(), self.kind.clone(), opt(self.device.as_ref())]
+    }
+}
+
+impl TableRow for RfkillEntry {
+    fn headers() -> &'static [&'static str] {
+        &["id", "type", "device", "soft", "hard"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let state = |blocked: bool| if blocked { "blocked" } else { "unblocked" }.to_string();
+        vec![
+            self.id.to_string(),
+            self.kind.clone(),
+            self.device.clone(),
+            state(self.soft_blocked),
+            state(self.hard_blocked),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_is_wrapped_in_versioned_envelope() {
+        let json = to_json_line(&["a", "b"]).unwrap();
+        assert_eq!(json, r#"{"schema_version":1,"tool":"sozin","data":["a","b"]}"#);
+
+        let back: JsonEnvelope<Vec<String>> = serde_json::from_str(&to_json(&["a"]).unwrap()).unwrap();
+        assert_eq!((back.schema_version, back.tool.as_str(), back.data), (SCHEMA_VERSION, "sozin", vec!["a".to_string()]));
+    }
+
+    #[test]
+    fn truncation_counts_columns_not_bytes() {
+        // Multi-byte characters straddle any byte limit; a byte slice here would panic
+        let ssid = "カフェ☕ Free WiFi for guests";
+        let cut = truncate_display(ssid, 12);
+        assert_eq!(cut, "カフェ☕ Fr…");
+        assert!(cut.width() <= 12);
+        assert_eq!(truncate_display("HomeNet", 24), "HomeNet");
+        assert_eq!(pad_display("カフェ", 8), "カフェ  ");
+    }
+
+    #[test]
+    fn csv_line_quotes_only_when_needed() {
+        let fields = ["Cafe".to_string(), "Bob's, \"free\" wifi".to_string(), String::new()];
+        assert_eq!(csv_line(fields), "Cafe,\"Bob's, \"\"free\"\" wifi\",");
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::Result;
+use std::process::{Output, Stdio};
+use tokio::process::Command as AsyncCommand;
+
+/// Executes external programs on behalf of `NetworkManager` and `WifiScanner`
+///
+/// Callers await it on their own task, so the future doesn't need to be `Send`.
+#[allow(async_fn_in_trait)]
+pub trait CommandRunner {
+    /// Run `program` with `args` to completion and capture its output
+    async fn run(&self, program: &str, args: &[&str]) -> Result<Output>;
+
+    /// `run`, writing `input` to the program's stdin; for secrets, which any local user could read from argv
+    async fn run_with_input(&self, program: &str, args: &[&str], input: &str) -> Result<Output>;
+}
+
+/// Runs commands on the host system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    async fn run(&self, program: &str, args: &[&str]) -> Result<Output> {
+        Ok(AsyncCommand::new(program).args(args).output().await?)
+    }
+
+    async fn run_with_input(&self, program: &str, args: &[&str], input: &str) -> Result<Output> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut child = AsyncCommand::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A program that exits without reading it all is reported through its output
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+        Ok(child.wait_with_output().await?)
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+    use std::sync::Mutex;
+
+    /// Records every command and replies with scripted output
+    ///
+    /// Commands succeed with empty output unless a rule matches. Rules match when
+    /// the full command line (`program arg1 arg2 ...`) contains the given pattern;
+    /// the most recently added matching rule wins.
+    #[derive(Default)]
+    pub struct MockRunner {
+        calls: Mutex<Vec<String>>,
+        inputs: Mutex<Vec<String>>,
+        rules: Mutex<Vec<(String, Output)>>,
+    }
+
+    impl MockRunner {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Make commands matching `pattern` exit with status 1 and `stderr`
+        pub fn fail_on(self, pattern: &str, stderr: &str) -> Self {
+            self.respond(pattern, 1, "", stderr)
+        }
+
+        /// Make commands matching `pattern` succeed with `stdout`
+        pub fn stdout_for(self, pattern: &str, stdout: &str) -> Self {
+            self.respond(pattern, 0, stdout, "")
+        }
+
+        fn respond(self, pattern: &str, code: i32, stdout: &str, stderr: &str) -> Self {
+            let output = Output {
+                status: ExitStatus::from_raw(code << 8),
+                stdout: stdout.as_bytes().to_vec(),
+                stderr: stderr.as_bytes().to_vec(),
+            };
+            self.rules.lock().unwrap().push((pattern.to_string(), output));
+            self
+        }
+
+        /// Command lines issued so far, in order
+        pub fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        /// Whatever was written to stdin through `run_with_input`, in order
+        pub fn inputs(&self) -> Vec<String> {
+            self.inputs.lock().unwrap().clone()
+        }
+    }
+
+    impl CommandRunner for MockRunner {
+        async fn run(&self, program: &str, args: &[&str]) -> Result<Output> {
+            let line = std::iter::once(program)
+                .chain(args.iter().copied())
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.calls.lock().unwrap().push(line.clone());
+
+            let rules = self.rules.lock().unwrap();
+            Ok(rules
+                .iter()
+                .rev()
+                .find(|(pattern, _)| line.contains(pattern.as_str()))
+                .map(|(_, output)| output.clone())
+                .unwrap_or_else(|| Output {
+                    status: ExitStatus::from_raw(0),
+                    stdout: Vec::new(),
+                    stderr: Vec::new(),
+                }))
+        }
+
+        async fn run_with_input(&self, program: &str, args: &[&str], input: &str) -> Result<Output> {
+            self.inputs.lock().unwrap().push(input.to_string());
+            self.run(program, args).await
+        }
+    }
+}
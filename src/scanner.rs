@@ -1,6 +1,13 @@
+use crate::netlink::ScanBss;
+use crate::network::NetworkManager;
+use crate::output::JsonEnvelope;
+use crate::runner::{CommandRunner, SystemRunner};
+use crate::store::ScanStore;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command as AsyncCommand;
 use tokio::time::{timeout, Duration};
 
@@ -15,8 +22,98 @@ pub struct WifiNetwork {
     pub security: SecurityType,
     pub mode: String,
     pub last_seen: chrono::DateTime<chrono::Utc>,
+    /// True when the AP beacons with an empty SSID element
+    #[serde(default)]
+    pub hidden: bool,
+    /// Beacon interval in time units (1 TU = 1024 µs)
+    pub beacon_interval_tu: Option<u16>,
+    /// Capability flags as printed by iw, e.g. "ESS Privacy ShortSlotTime"
+    pub capability: Option<String>,
+    /// Milliseconds since the AP was last heard, as reported by the driver
+    pub last_seen_ms: Option<u64>,
+    /// True when TKIP is the only pairwise cipher offered
+    #[serde(default)]
+    pub tkip_only: bool,
+    /// Rough upper bound on the PHY rate in Mbps (see `WifiNetworkBuilder::max_rate_mbps`)
+    pub max_rate_mbps: Option<u32>,
+    /// Operating channel width in MHz: 20, 40, 80 or 160
+    pub channel_width_mhz: Option<u32>,
+    /// Channel number at the centre of the occupied block; differs from `channel` on wide channels
+    pub center_channel: Option<u32>,
+    /// True for a 6 GHz AP on a Preferred Scanning Channel
+    #[serde(default)]
+    pub psc: bool,
+    /// ISO 3166 code from the AP's Country element, e.g. "US"; "XX" means no specific country
+    #[serde(default)]
+    pub country_code: Option<String>,
+    /// Name this hidden BSSID broadcast on an earlier run, from `--remember-networks`
+    #[serde(default)]
+    pub remembered_ssid: Option<String>,
+    /// Associated clients, from the AP's BSS Load element
+    #[serde(default)]
+    pub station_count: Option<u16>,
+    /// Percentage of time the AP sensed its channel busy, from the BSS Load element
+    #[serde(default)]
+    pub channel_utilization: Option<u8>,
+    /// True when the AP only advertises 802.11b/g rates (no HT, VHT or HE)
+    #[serde(default)]
+    pub is_legacy: bool,
+    /// Interfaces that heard this AP, filled in by `scan_all`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seen_by: Vec<String>,
 }
 
+impl WifiNetwork {
+    /// SSID for display: a hidden network's remembered name goes in parentheses
+    pub fn display_ssid(&self) -> String {
+        match &self.remembered_ssid {
+            Some(name) if self.ssid == HIDDEN_SSID => format!("({})", name),
+            _ => self.ssid.clone(),
+        }
+    }
+
+    /// Rough 0-100 rating of how good a network is to join
+    ///
+    /// Weighted 60 for signal, 25 for security and 15 for band. Signal scales
+    /// linearly from nothing at -100 dBm to full marks at -50 dBm. Security gives
+    /// WPA3 25, WPA2-Enterprise 22, WPA2 18, unknown 10, WPA or TKIP-only 8, WEP 2
+    /// and open 0. Band gives 6 GHz 15, 5 GHz 12 and 2.4 GHz 5, for the less
+    /// crowded spectrum; their shorter reach already shows in the signal.
+    pub fn quality_score(&self) -> u8 {
+        let signal = signal_to_quality(self.signal_strength) as u32 * 60 / 100;
+        let security = match self.security {
+            SecurityType::WPA2 | SecurityType::WPA3 | SecurityType::WPA2Enterprise if self.tkip_only => 8,
+            SecurityType::WPA3 => 25,
+            SecurityType::WPA2Enterprise => 22,
+            SecurityType::WPA2 => 18,
+            SecurityType::Unknown => 10,
+            SecurityType::WPA => 8,
+            SecurityType::WEP => 2,
+            SecurityType::Open => 0,
+        };
+        let band = match Band::from_frequency(self.frequency) {
+            Some(Band::Six) => 15,
+            Some(Band::Five) => 12,
+            Some(Band::TwoPointFour) => 5,
+            None => 0,
+        };
+        (signal + security + band) as u8
+    }
+
+    /// First and last 20 MHz channel number the AP's transmissions cover
+    pub fn occupied_channels(&self) -> (u32, u32) {
+        let width = self.channel_width_mhz.unwrap_or(20);
+        let center = self.center_channel.unwrap_or(self.channel);
+        // 20 MHz channels are numbered 4 apart, each centred 2 numbers in from its edge
+        let half = (width / 10).saturating_sub(2);
+        (center.saturating_sub(half), center + half)
+    }
+}
+
+/// Placeholder shown for hidden networks whose name is not known
+pub const HIDDEN_SSID: &str = "<hidden>";
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SecurityType {
     Open,
@@ -42,10 +139,32 @@ impl std::fmt::Display for SecurityType {
     }
 }
 
+/// One line of a saved `--stream`, with or without the JSON envelope
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedLine {
+    Enveloped(JsonEnvelope<WifiNetwork>),
+    Bare(WifiNetwork),
+}
+
+/// Time allowed for an active `iw scan`
+const ACTIVE_SCAN_TIMEOUT_SECS: u64 = 10;
+/// Passive scans dwell on each channel waiting for beacons
+const PASSIVE_SCAN_TIMEOUT_SECS: u64 = 30;
+/// Wait before reading the cached BSS table when another scan is already running
+const BUSY_SCAN_WAIT_MS: u64 = 1500;
+
 /// WiFi scanner for network discovery
 pub struct WifiScanner {
     interface: String,
     networks: HashMap<String, WifiNetwork>,
+    truncated: bool,
+    /// BSSIDs the latest scan saw for the first time this session
+    discovered: Vec<String>,
+    /// Whether `record` should fill `discovered`; off until the cache has a baseline
+    track_new: bool,
+    /// Names remembered across runs, set with `with_store`
+    store: Option<ScanStore>,
 }
 
 impl WifiScanner {
@@ -53,36 +172,453 @@ impl WifiScanner {
         Self {
             interface: interface.to_string(),
             networks: HashMap::new(),
+            truncated: false,
+            discovered: Vec::new(),
+            track_new: false,
+            store: None,
+        }
+    }
+
+    /// Remember broadcast names in `store` and use them to label hidden networks
+    pub fn with_store(mut self, store: ScanStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Persist names learned since the store was opened; a no-op without one
+    pub fn save_store(&mut self) -> Result<()> {
+        match self.store.as_mut() {
+            Some(store) => store.save(),
+            None => Ok(()),
         }
     }
 
+    /// BSSIDs the last scan found that no earlier scan this session had seen
+    ///
+    /// Always empty after the first scan, which only establishes what was already around.
+    pub fn discovered(&self) -> &[String] {
+        &self.discovered
+    }
+
+    /// Reset per-scan state before running iw
+    fn begin_scan(&mut self) {
+        self.truncated = false;
+        self.discovered.clear();
+        self.track_new = !self.networks.is_empty();
+    }
+
+    /// Interface this scanner is bound to
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// Whether the last scan timed out and returned only what iw printed before being killed
+    pub fn last_scan_truncated(&self) -> bool {
+        self.truncated
+    }
+
     /// Scan for WiFi networks using iw
-    pub async fn scan(&mut self) -> Result<Vec<WifiNetwork>> {
-        // Trigger scan
-        let scan_result = timeout(
-            Duration::from_secs(10),
-            AsyncCommand::new("iw")
-                .args(["dev", &self.interface, "scan"])
-                .output(),
-        )
+    ///
+    /// Active scans send probe requests; passive scans only listen for beacons,
+    /// which is quieter but slower and misses hidden APs that never beacon a name.
+    ///
+    /// On timeout, whatever iw had already printed is parsed and returned with
+    /// `last_scan_truncated` set; the timeout is only an error if nothing was captured.
+    ///
+    /// If the driver is busy with a scan someone else started ("Resource busy"), this waits
+    /// for it and returns the cached results from `iw scan dump` instead of failing.
+    pub async fn scan(&mut self, passive: bool) -> Result<Vec<WifiNetwork>> {
+        self.begin_scan();
+        #[cfg(feature = "netlink")]
+        let native = match self.scan_native(&[], passive).await {
+            Ok(networks) => return Ok(networks),
+            Err(e) => e,
+        };
+        let interface = self.interface.clone();
+        let args = Self::scan_args(&interface, passive);
+        let result = match self.run_scan(&args, passive).await {
+            Err(e) => self.recover_busy_with(&SystemRunner, e, Duration::from_millis(BUSY_SCAN_WAIT_MS)).await,
+            ok => ok,
+        };
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("scan", native, result);
+        result
+    }
+
+    /// Fall back to the cached BSS table when `error` is the kernel's EBUSY, else return it
+    async fn recover_busy_with<R: CommandRunner>(
+        &mut self,
+        runner: &R,
+        error: anyhow::Error,
+        wait: Duration,
+    ) -> Result<Vec<WifiNetwork>> {
+        let message = error.to_string();
+        if !message.contains("Resource busy") && !message.contains("(-16)") {
+            return Err(error);
+        }
+        tokio::time::sleep(wait).await;
+
+        let interface = self.interface.clone();
+        let output = runner.run("iw", &["dev", interface.as_str(), "scan", "dump"]).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Scan failed: interface busy, and reading cached results failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        self.parse_scan_results(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Scan without knocking an associated interface off its network
+    ///
+    /// A full `iw scan` makes the card leave the operating channel for several seconds,
+    /// which can stall or drop the connection. When the interface is associated, this asks
+    /// NetworkManager instead: its rescans go through wpa_supplicant, which scans in the
+    /// background between frames. Disconnected interfaces, or systems without nmcli, use
+    /// `iw scan` as usual.
+    pub async fn scan_nondisruptive(&mut self) -> Result<(Vec<WifiNetwork>, ScanMethod)> {
+        if NetworkManager::get_connection_info(&self.interface).is_some() {
+            if let Ok(networks) = self.scan_nmcli_with(&SystemRunner).await {
+                return Ok((networks, ScanMethod::NetworkManager));
+            }
+        }
+        Ok((self.scan(false).await?, ScanMethod::Iw))
+    }
+
+    /// Ask NetworkManager to rescan and read back its list of access points
+    async fn scan_nmcli_with<R: CommandRunner>(&mut self, runner: &R) -> Result<Vec<WifiNetwork>> {
+        self.begin_scan();
+        let interface = self.interface.clone();
+        // Refused if another scan just ran; the list is still fresh enough then
+        runner
+            .run("nmcli", &["device", "wifi", "rescan", "ifname", interface.as_str()])
+            .await?;
+        let output = runner
+            .run(
+                "nmcli",
+                &[
+                    "-t", "-f", "BSSID,SSID,CHAN,FREQ,SIGNAL,SECURITY", "device", "wifi", "list", "ifname",
+                    interface.as_str(), "--rescan", "no",
+                ],
+            )
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "NetworkManager scan failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(self.parse_nmcli_list(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Parse `nmcli -t -f BSSID,SSID,CHAN,FREQ,SIGNAL,SECURITY device wifi list`
+    fn parse_nmcli_list(&mut self, output: &str) -> Vec<WifiNetwork> {
+        let mut networks: Vec<WifiNetwork> = output
+            .lines()
+            .filter_map(|line| {
+                let fields = NetworkManager::split_terse(line);
+                let [bssid, ssid, chan, freq, signal, security] = fields.as_slice() else {
+                    return None;
+                };
+                let channel = chan.parse().ok()?;
+                let frequency = freq.split_whitespace().next()?.parse().ok()?;
+                // NetworkManager reports quality as 2 * (dBm + 100), clamped to 0-100
+                let quality: i32 = signal.parse().ok()?;
+                let hidden = ssid.is_empty();
+                Some(self.record(WifiNetwork {
+                    ssid: if hidden { HIDDEN_SSID.to_string() } else { ssid.clone() },
+                    bssid: normalize_bssid(bssid),
+                    channel,
+                    frequency,
+                    signal_strength: quality / 2 - 100,
+                    security: Self::nmcli_security(security),
+                    mode: "Infrastructure".to_string(),
+                    last_seen: chrono::Utc::now(),
+                    hidden,
+                    beacon_interval_tu: None,
+                    capability: None,
+                    last_seen_ms: None,
+                    tkip_only: false,
+                    max_rate_mbps: None,
+                    channel_width_mhz: None,
+                    center_channel: None,
+                    psc: Band::from_frequency(frequency) == Some(Band::Six) && is_psc_channel(channel),
+                    country_code: None,
+                    remembered_ssid: None,
+                    station_count: None,
+                    channel_utilization: None,
+                    is_legacy: false,
+                    seen_by: Vec::new(),
+                }))
+            })
+            .collect();
+        networks.sort_by_key(|n| std::cmp::Reverse(n.signal_strength));
+        networks
+    }
+
+    /// Map nmcli's SECURITY column ("WPA1 WPA2", "WPA2 802.1X", "WPA3", "" ...) to a `SecurityType`
+    fn nmcli_security(security: &str) -> SecurityType {
+        if security.contains("WPA3") || security.contains("SAE") {
+            SecurityType::WPA3
+        } else if security.contains("802.1X") {
+            SecurityType::WPA2Enterprise
+        } else if security.contains("WPA2") {
+            SecurityType::WPA2
+        } else if security.contains("WPA") {
+            SecurityType::WPA
+        } else if security.contains("WEP") {
+            SecurityType::WEP
+        } else {
+            SecurityType::Open
+        }
+    }
+
+    /// Scan each band separately and merge the results
+    ///
+    /// Slower than `scan`, but some drivers fail or return partial results when
+    /// asked for 2.4 and 5/6 GHz in one request. A BSSID seen in more than one
+    /// pass keeps its strongest reading. Fails only if every band fails.
+    pub async fn scan_per_band(&mut self) -> Result<Vec<WifiNetwork>> {
+        self.begin_scan();
+        let interface = self.interface.clone();
+        let freqs = NetworkManager::get_supported_frequencies(&interface);
+        if freqs.is_empty() {
+            // Nothing to split on; let iw pick the channels
+            return self.scan(false).await;
+        }
+
+        let mut found = Vec::new();
+        let mut first_error = None;
+        let mut any_ok = false;
+        for band in [Band::TwoPointFour, Band::Five, Band::Six] {
+            let band_freqs: Vec<u32> = freqs
+                .iter()
+                .copied()
+                .filter(|f| Band::from_frequency(*f) == Some(band))
+                .collect();
+            if band_freqs.is_empty() {
+                continue;
+            }
+
+            match self.scan_freqs(&band_freqs, false).await {
+                Ok(networks) => {
+                    any_ok = true;
+                    found.extend(networks);
+                }
+                Err(e) => {
+                    first_error.get_or_insert_with(|| anyhow!("{} scan failed: {}", band, e));
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if !any_ok => Err(e),
+            _ => Ok(merge_strongest(found)),
+        }
+    }
+
+    /// Scan exactly the channels the adapter reports as usable
+    ///
+    /// Passes an explicit `freq` list instead of leaving the sweep to the driver, which on
+    /// some region-locked adapters includes frequencies it then rejects as invalid. Falls
+    /// back to a plain `scan` when the supported frequencies can't be read.
+    pub async fn scan_supported(&mut self) -> Result<Vec<WifiNetwork>> {
+        let interface = self.interface.clone();
+        let freqs = NetworkManager::get_supported_frequencies(&interface);
+        if freqs.is_empty() {
+            return self.scan(false).await;
+        }
+
+        self.begin_scan();
+        self.scan_freqs(&freqs, false).await
+    }
+
+    /// Scan only `freqs`, over nl80211 with the `netlink` feature and with `iw` otherwise or if that fails
+    async fn scan_freqs(&mut self, freqs: &[u32], passive: bool) -> Result<Vec<WifiNetwork>> {
+        #[cfg(feature = "netlink")]
+        let native = match self.scan_native(freqs, passive).await {
+            Ok(networks) => return Ok(networks),
+            Err(e) => e,
+        };
+        let mut args = Self::freq_scan_args(&self.interface, freqs);
+        if passive {
+            args.push("passive".to_string());
+        }
+        let result = self.run_scan(&args.iter().map(String::as_str).collect::<Vec<_>>(), passive).await;
+        #[cfg(feature = "netlink")]
+        let result = crate::netlink::after_fallback("scan", native, result);
+        result
+    }
+
+    /// `iw dev <interface> scan freq ...` arguments for `freqs` in MHz
+    ///
+    /// Takes frequencies rather than channel numbers, which 6 GHz reuses from 2.4 GHz.
+    fn freq_scan_args(interface: &str, freqs: &[u32]) -> Vec<String> {
+        ["dev", interface, "scan", "freq"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(freqs.iter().map(u32::to_string))
+            .collect()
+    }
+
+    /// Scan `freqs` (every channel when empty) over nl80211, without `iw`
+    #[cfg(feature = "netlink")]
+    async fn scan_native(&mut self, freqs: &[u32], passive: bool) -> Result<Vec<WifiNetwork>> {
+        let limit = if passive {
+            PASSIVE_SCAN_TIMEOUT_SECS
+        } else {
+            ACTIVE_SCAN_TIMEOUT_SECS
+        };
+        let index = nix::net::if_::if_nametoindex(self.interface.as_str())?;
+        let freqs = freqs.to_vec();
+        let found = tokio::task::spawn_blocking(move || {
+            crate::netlink::Nl80211::connect()?.scan(index, &freqs, passive, Duration::from_secs(limit))
+        })
         .await??;
+        let networks = found
+            .iter()
+            .filter_map(|bss| WifiNetworkBuilder::from_bss(bss).build())
+            .map(|network| self.record(network))
+            .collect();
+        Ok(self.finish_results(networks))
+    }
+
+    /// Scan only the given channel numbers, e.g. 1, 6 and 11
+    ///
+    /// Channels must be known and enabled on the adapter; checking up front
+    /// gives a clearer error than the kernel's "Invalid argument".
+    pub async fn scan_channels(&mut self, channels: &[u32], passive: bool) -> Result<Vec<WifiNetwork>> {
+        self.begin_scan();
+        let interface = self.interface.clone();
+        let supported = NetworkManager::get_supported_frequencies(&interface);
+        let freqs = channels
+            .iter()
+            .map(|&ch| {
+                let freq = Self::channel_to_freq(ch).ok_or_else(|| anyhow!("Unknown channel {}", ch))?;
+                if !supported.is_empty() && !supported.contains(&freq) {
+                    return Err(anyhow!("Channel {} is disabled or not supported on {}", ch, interface));
+                }
+                Ok(freq)
+            })
+            .collect::<Result<Vec<u32>>>()?;
+
+        self.scan_freqs(&freqs, passive).await
+    }
+
+    /// Run `iw` with `args` and parse what it prints, within the scan timeout
+    async fn run_scan(&mut self, args: &[&str], passive: bool) -> Result<Vec<WifiNetwork>> {
+        let limit = if passive {
+            PASSIVE_SCAN_TIMEOUT_SECS
+        } else {
+            ACTIVE_SCAN_TIMEOUT_SECS
+        };
+        let passive_hint = if passive {
+            " Passive scans dwell on every channel, take longer and may miss hidden APs."
+        } else {
+            ""
+        };
+
+        // Trigger scan, collecting output as it arrives so a timeout doesn't lose it
+        let mut child = AsyncCommand::new("iw")
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let mut child_stdout = child.stdout.take().expect("stdout is piped");
+        let mut child_stderr = child.stderr.take().expect("stderr is piped");
+        let mut stdout_buf = Vec::new();
+        let mut stderr_buf = Vec::new();
+
+        let finished = timeout(Duration::from_secs(limit), async {
+            tokio::try_join!(
+                read_chunks(&mut child_stdout, &mut stdout_buf),
+                read_chunks(&mut child_stderr, &mut stderr_buf),
+            )?;
+            child.wait().await
+        })
+        .await;
 
-        if !scan_result.status.success() {
+        let status = match finished {
+            Ok(status) => status?,
+            Err(_) => {
+                let _ = child.kill().await;
+                let networks = self.parse_truncated(&String::from_utf8_lossy(&stdout_buf))?;
+                if networks.is_empty() {
+                    return Err(anyhow!("Scan timed out after {}s.{}", limit, passive_hint));
+                }
+                self.truncated = true;
+                return Ok(networks);
+            }
+        };
+
+        if !status.success() {
             // Try with sudo if permission denied
-            let stderr = String::from_utf8_lossy(&scan_result.stderr);
+            let stderr = String::from_utf8_lossy(&stderr_buf);
             if stderr.contains("Operation not permitted") || stderr.contains("Network is down") {
                 return Err(anyhow!(
-                    "Scan failed: {}. Try running with sudo or ensure interface is up.",
-                    stderr.trim()
+                    "Scan failed: {}. Try running with sudo or ensure interface is up.{}",
+                    stderr.trim(),
+                    passive_hint
                 ));
             }
-            return Err(anyhow!("Scan failed: {}", stderr.trim()));
+            return Err(anyhow!("Scan failed: {}{}", stderr.trim(), passive_hint));
         }
 
-        let stdout = String::from_utf8_lossy(&scan_result.stdout);
+        let stdout = String::from_utf8_lossy(&stdout_buf);
         self.parse_scan_results(&stdout)
     }
 
+    /// Parse previously saved results: raw `iw scan` text, `--json` output or `--stream` JSON Lines
+    ///
+    /// Bare arrays and lines from before the JSON envelope are still accepted.
+    pub fn parse_saved(&mut self, contents: &str) -> Result<Vec<WifiNetwork>> {
+        let trimmed = contents.trim_start();
+        if trimmed.starts_with('[') {
+            return serde_json::from_str(trimmed).map_err(|e| anyhow!("Invalid scan JSON: {}", e));
+        }
+        // Pretty-printed `--json` output opens with a lone brace; streams put a whole object per line
+        if trimmed.lines().next().is_some_and(|l| l.trim() == "{") {
+            return serde_json::from_str::<JsonEnvelope<Vec<WifiNetwork>>>(trimmed)
+                .map(|envelope| envelope.data)
+                .map_err(|e| anyhow!("Invalid scan JSON: {}", e));
+        }
+        if trimmed.starts_with('{') {
+            return trimmed
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .enumerate()
+                .map(|(i, l)| {
+                    serde_json::from_str::<SavedLine>(l)
+                        .map(|line| match line {
+                            SavedLine::Enveloped(envelope) => envelope.data,
+                            SavedLine::Bare(network) => network,
+                        })
+                        .map_err(|e| anyhow!("Invalid JSON on line {}: {}", i + 1, e))
+                })
+                .collect();
+        }
+        if !trimmed.lines().any(|l| l.starts_with("BSS ")) {
+            return Err(anyhow!("Not iw scan output or sozin JSON (no BSS entries found)"));
+        }
+        self.parse_scan_results(contents)
+    }
+
+    /// Parse output cut off mid-stream, ignoring a trailing partial line
+    fn parse_truncated(&mut self, output: &str) -> Result<Vec<WifiNetwork>> {
+        let complete = output.rfind('\n').map_or("", |end| &output[..=end]);
+        self.parse_scan_results(complete)
+    }
+
+    /// Build the `iw` argument list for a scan
+    fn scan_args(interface: &str, passive: bool) -> Vec<&str> {
+        let mut args = vec!["dev", interface, "scan"];
+        if passive {
+            args.push("passive");
+        }
+        args
+    }
+
     /// Parse iw scan output
     fn parse_scan_results(&mut self, output: &str) -> Result<Vec<WifiNetwork>> {
         let mut networks = Vec::new();
@@ -91,12 +627,12 @@ impl WifiScanner {
         for line in output.lines() {
             let line = line.trim();
 
-            if line.starts_with("BSS ") {
+            // "BSS Load:" is an element inside the block, not a new AP
+            if line.starts_with("BSS ") && !line.starts_with("BSS Load:") {
                 // Save previous network if exists
                 if let Some(builder) = current_network.take() {
                     if let Some(network) = builder.build() {
-                        self.networks.insert(network.bssid.clone(), network.clone());
-                        networks.push(network);
+                        networks.push(self.record(network));
                     }
                 }
 
@@ -104,7 +640,7 @@ impl WifiScanner {
                 let bssid = line
                     .strip_prefix("BSS ")
                     .and_then(|s| s.split('(').next())
-                    .map(|s| s.trim().to_string())
+                    .map(normalize_bssid)
                     .unwrap_or_default();
 
                 current_network = Some(WifiNetworkBuilder::new(bssid));
@@ -118,15 +654,50 @@ impl WifiScanner {
                     }
                 } else if line.starts_with("signal:") {
                     if let Some(signal_str) = line.strip_prefix("signal:") {
-                        let signal_str = signal_str.trim().split_whitespace().next().unwrap_or("0");
-                        builder.signal_strength = signal_str.parse().ok();
+                        // iw prints fractional dBm, e.g. "-45.00 dBm"
+                        let signal_str = signal_str.split_whitespace().next().unwrap_or("0");
+                        builder.signal_strength = signal_str.parse::<f64>().ok().map(|dbm| dbm.round() as i32);
+                    }
+                } else if let Some(caps) = line.strip_prefix("capability:") {
+                    // "ESS Privacy ShortSlotTime (0x0411)" - drop the raw hex value
+                    let flags = caps.split('(').next().unwrap_or("").trim();
+                    builder.privacy = flags.split_whitespace().any(|flag| flag == "Privacy");
+                    builder.capability = Some(flags.to_string()).filter(|f| !f.is_empty());
+                } else if let Some(country) = line.strip_prefix("Country:") {
+                    // "Country: US\tEnvironment: Indoor/Outdoor", followed by per-channel power lines
+                    builder.country_code = country.split_whitespace().next().map(str::to_string);
+                } else if let Some(count) = line.strip_prefix("* station count:") {
+                    // BSS Load element
+                    builder.station_count = count.trim().parse().ok();
+                } else if let Some(busy) = line.strip_prefix("* channel utilisation:") {
+                    // "153/255"
+                    builder.channel_utilization = busy
+                        .split('/')
+                        .next()
+                        .and_then(|v| v.trim().parse::<u32>().ok())
+                        .map(|v| ((v.min(255) * 100 + 127) / 255) as u8);
+                } else if let Some(interval) = line.strip_prefix("beacon interval:") {
+                    builder.beacon_interval_tu = interval.split_whitespace().next().and_then(|v| v.parse().ok());
+                } else if let Some(seen) = line.strip_prefix("last seen:") {
+                    // Newer iw also prints "last seen: 1523.912s [boottime]"; only the relative form is useful
+                    if let Some(ms) = seen.trim().strip_suffix("ms ago") {
+                        builder.last_seen_ms = ms.trim().parse().ok();
                     }
-                } else if line.contains("WPA") || line.contains("RSN") || line.contains("WEP") {
-                    builder.update_security(line);
+                } else if line.starts_with("RSN:") {
+                    builder.rsn = true;
+                } else if line.starts_with("WPA:") {
+                    builder.wpa = true;
+                } else if let Some(suites) = line.split("Authentication suites:").nth(1) {
+                    builder.update_auth_suites(suites);
+                } else if let Some(ciphers) = line.split("Pairwise ciphers:").nth(1) {
+                    builder.update_pairwise_ciphers(ciphers);
                 } else if line.starts_with("DS Parameter set:") {
-                    if let Some(ch_str) = line.split("channel").nth(1) {
-                        builder.channel = ch_str.trim().parse().ok();
+                    // Keep the frequency-derived channel unless this parses cleanly
+                    if let Some(channel) = Self::parse_ds_channel(line) {
+                        builder.channel = Some(channel);
                     }
+                } else {
+                    builder.update_rate_info(line);
                 }
             }
         }
@@ -134,15 +705,61 @@ impl WifiScanner {
         // Don't forget the last network
         if let Some(builder) = current_network {
             if let Some(network) = builder.build() {
-                self.networks.insert(network.bssid.clone(), network.clone());
-                networks.push(network);
+                networks.push(self.record(network));
             }
         }
 
+        Ok(self.finish_results(networks))
+    }
+
+    /// Name hidden networks a later entry revealed, then sort strongest first
+    fn finish_results(&self, mut networks: Vec<WifiNetwork>) -> Vec<WifiNetwork> {
+        // A probe response later in the same dump may have revealed a name
+        for network in networks.iter_mut().filter(|n| n.hidden) {
+            self.resolve_hidden(network);
+        }
+
         // Sort by signal strength (strongest first)
-        networks.sort_by(|a, b| b.signal_strength.cmp(&a.signal_strength));
+        networks.sort_by_key(|n| std::cmp::Reverse(n.signal_strength));
+        networks
+    }
+
+    /// Extract the channel from a `DS Parameter set: channel N` line
+    fn parse_ds_channel(line: &str) -> Option<u32> {
+        line.split("channel")
+            .nth(1)?
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|token| !token.is_empty())?
+            .parse()
+            .ok()
+    }
 
-        Ok(networks)
+    /// Fill in a hidden network's name from a previous sighting of the same BSSID
+    fn resolve_hidden(&self, network: &mut WifiNetwork) {
+        if let Some(known) = self.networks.get(&network.bssid) {
+            if known.ssid != HIDDEN_SSID {
+                network.ssid = known.ssid.clone();
+            }
+        }
+    }
+
+    /// Store a parsed network in the cache, keeping any name already known for a hidden BSSID
+    fn record(&mut self, mut network: WifiNetwork) -> WifiNetwork {
+        if network.hidden {
+            self.resolve_hidden(&mut network);
+        }
+        if let Some(store) = self.store.as_mut() {
+            if !network.hidden {
+                store.remember(&network.bssid, &network.ssid);
+            } else if network.ssid == HIDDEN_SSID {
+                network.remembered_ssid = store.name_for(&network.bssid).map(String::from);
+            }
+        }
+        if self.track_new && !self.networks.contains_key(&network.bssid) {
+            self.discovered.push(network.bssid.clone());
+        }
+        self.networks.insert(network.bssid.clone(), network.clone());
+        network
     }
 
     /// Convert frequency to channel number
@@ -188,29 +805,60 @@ impl WifiScanner {
             5785 => Some(157),
             5805 => Some(161),
             5825 => Some(165),
+            // 6GHz 20 MHz channels 1, 5, 9, ... 233
+            5955..=7115 if (freq - 5955).is_multiple_of(20) => Some((freq - 5950) / 5),
             _ => None,
         }
     }
 
-    #[allow(dead_code)]
+    /// Convert a channel number to its centre frequency; the inverse of `freq_to_channel`
+    pub fn channel_to_freq(channel: u32) -> Option<u32> {
+        let freq = match channel {
+            14 => 2484,
+            1..=13 => 2407 + 5 * channel,
+            // Past 233 there are no channels, and 5 * channel could overflow
+            15..=233 => 5000 + 5 * channel,
+            _ => return None,
+        };
+        (Self::freq_to_channel(freq) == Some(channel)).then_some(freq)
+    }
+
     /// Get cached networks
     pub fn get_cached_networks(&self) -> Vec<WifiNetwork> {
         self.networks.values().cloned().collect()
     }
 
-    #[allow(dead_code)]
     /// Clear cached networks
     pub fn clear_cache(&mut self) {
         self.networks.clear();
     }
 
-    #[allow(dead_code)]
     /// Get network by BSSID
     pub fn get_network(&self, bssid: &str) -> Option<&WifiNetwork> {
-        self.networks.get(bssid)
+        self.networks.get(&normalize_bssid(bssid))
     }
 }
 
+/// Names iw gives the capability field's bits, lowest first
+const CAPABILITY_FLAGS: [&str; 16] = [
+    "ESS",
+    "IBSS",
+    "CfPollable",
+    "CfPollReq",
+    "Privacy",
+    "ShortPreamble",
+    "PBCC",
+    "ChannelAgility",
+    "SpectrumMgmt",
+    "QoS",
+    "ShortSlotTime",
+    "APSD",
+    "RadioMeasure",
+    "DSSS-OFDM",
+    "DelayedBAck",
+    "ImmediateBAck",
+];
+
 /// Builder for WiFi network parsing
 struct WifiNetworkBuilder {
     bssid: String,
@@ -218,7 +866,33 @@ struct WifiNetworkBuilder {
     channel: Option<u32>,
     frequency: Option<u32>,
     signal_strength: Option<i32>,
-    security: SecurityType,
+    beacon_interval_tu: Option<u16>,
+    capability: Option<String>,
+    last_seen_ms: Option<u64>,
+    country_code: Option<String>,
+    station_count: Option<u16>,
+    channel_utilization: Option<u8>,
+    privacy: bool,
+    rsn: bool,
+    wpa: bool,
+    sae: bool,
+    enterprise: bool,
+    tkip: bool,
+    aes: bool,
+    legacy_rate: Option<f64>,
+    ht: bool,
+    /// HE (802.11ax) capabilities seen; 6 GHz APs send these without HT
+    he: bool,
+    ht_streams: Option<u32>,
+    ht40: bool,
+    /// The HT40 secondary channel sits above the primary
+    ht40_above: bool,
+    vht_width_mhz: Option<u32>,
+    /// VHT centre frequency segments 1 and 2 as channel numbers, 0 when unused
+    vht_center: (u32, u32),
+    /// Highest (spatial streams, max MCS) from the VHT RX MCS set
+    vht_mcs: Option<(u32, u32)>,
+    in_vht_rx_mcs: bool,
 }
 
 impl WifiNetworkBuilder {
@@ -229,67 +903,463 @@ impl WifiNetworkBuilder {
             channel: None,
             frequency: None,
             signal_strength: None,
-            security: SecurityType::Open,
+            beacon_interval_tu: None,
+            capability: None,
+            last_seen_ms: None,
+            country_code: None,
+            station_count: None,
+            channel_utilization: None,
+            privacy: false,
+            rsn: false,
+            wpa: false,
+            sae: false,
+            enterprise: false,
+            tkip: false,
+            aes: false,
+            legacy_rate: None,
+            ht: false,
+            he: false,
+            ht_streams: None,
+            ht40: false,
+            ht40_above: false,
+            vht_width_mhz: None,
+            vht_center: (0, 0),
+            vht_mcs: None,
+            in_vht_rx_mcs: false,
+        }
+    }
+
+    /// A builder filled from an nl80211 scan entry, reading the elements `iw` would have printed
+    #[cfg_attr(not(feature = "netlink"), allow(dead_code))]
+    fn from_bss(bss: &ScanBss) -> Self {
+        let bssid = bss.bssid.map(|b| format!("{:02x}", b)).join(":");
+        let mut builder = Self::new(bssid);
+        builder.frequency = Some(bss.frequency);
+        builder.channel = WifiScanner::freq_to_channel(bss.frequency);
+        builder.signal_strength = bss.signal_mbm.map(|mbm| (mbm as f64 / 100.0).round() as i32);
+        builder.beacon_interval_tu = bss.beacon_interval_tu;
+        builder.last_seen_ms = bss.seen_ms_ago.map(u64::from);
+        if let Some(caps) = bss.capability {
+            builder.privacy = caps & 0x0010 != 0;
+            let flags: Vec<&str> = CAPABILITY_FLAGS
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| caps & (1 << bit) != 0)
+                .map(|(_, flag)| *flag)
+                .collect();
+            builder.capability = Some(flags.join(" ")).filter(|f| !f.is_empty());
+        }
+
+        let mut ies = bss.ies.as_slice();
+        while let [id, len, rest @ ..] = ies {
+            let Some(data) = rest.get(..*len as usize) else {
+                break;
+            };
+            builder.update_element(*id, data);
+            ies = &rest[data.len()..];
         }
+        builder
     }
 
-    fn update_security(&mut self, line: &str) {
-        if line.contains("WPA3") {
-            self.security = SecurityType::WPA3;
-        } else if line.contains("RSN") || line.contains("WPA2") {
-            if line.contains("802.1X") || line.contains("EAP") {
-                self.security = SecurityType::WPA2Enterprise;
-            } else if self.security != SecurityType::WPA3 {
-                self.security = SecurityType::WPA2;
+    /// Take what sozin uses from one information element (IEEE 802.11-2020 9.4.2)
+    fn update_element(&mut self, id: u8, data: &[u8]) {
+        match (id, data) {
+            // Hidden APs send an empty SSID or one of NUL bytes
+            (0, ssid) if ssid.iter().any(|b| *b != 0) => self.ssid = Some(String::from_utf8_lossy(ssid).into_owned()),
+            // Supported and extended supported rates, in 500 kb/s units with the top bit marking basic rates
+            (1 | 50, rates) => {
+                let max = rates.iter().map(|r| (r & 0x7f) as f64 / 2.0).fold(None, |acc: Option<f64>, r| {
+                    Some(acc.map_or(r, |a| a.max(r)))
+                });
+                self.legacy_rate = match (self.legacy_rate, max) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+            (3, [channel, ..]) => self.channel = Some(*channel as u32),
+            (7, [a, b, ..]) => self.country_code = Some(String::from_utf8_lossy(&[*a, b & 0x7f]).into_owned()),
+            (11, [count_lo, count_hi, busy, ..]) => {
+                self.station_count = Some(u16::from_le_bytes([*count_lo, *count_hi]));
+                self.channel_utilization = Some(((*busy as u32 * 100 + 127) / 255) as u8);
+            }
+            (45, caps) => {
+                self.ht = true;
+                // RX MCS bitmask: one byte of 8 MCS indexes per spatial stream
+                self.ht_streams = caps
+                    .get(3..7)
+                    .and_then(|mcs| mcs.iter().rposition(|b| *b != 0))
+                    .map(|last| last as u32 + 1);
+            }
+            (48, [_, _, suites @ ..]) => {
+                self.rsn = true;
+                self.update_cipher_suites(suites);
+            }
+            (61, [_, info, ..]) => {
+                self.ht40 = matches!(info & 0x03, 1 | 3);
+                self.ht40_above = info & 0x03 == 1;
+            }
+            (191, caps) => {
+                if let Some(map) = caps.get(4..6).map(|m| u16::from_le_bytes([m[0], m[1]])) {
+                    // Two bits per stream count: 0-2 mean MCS 0-7, 0-8, 0-9 and 3 means not supported
+                    self.vht_mcs = (0..8u32)
+                        .map(|stream| (stream + 1, (map >> (2 * stream)) & 0x03))
+                        .rfind(|(_, mcs)| *mcs != 3)
+                        .map(|(streams, mcs)| (streams, 7 + mcs as u32));
+                }
+            }
+            (192, [width, seg1, seg2, ..]) => {
+                self.vht_width_mhz = match width {
+                    1 => Some(80),
+                    2 | 3 => Some(160),
+                    _ => None,
+                };
+                self.vht_center = (*seg1 as u32, *seg2 as u32);
             }
-        } else if line.contains("WPA") && self.security == SecurityType::Open {
-            self.security = SecurityType::WPA;
-        } else if line.contains("WEP") && self.security == SecurityType::Open {
-            self.security = SecurityType::WEP;
+            // Microsoft's vendor element carries WPA (version 1)
+            (221, [0x00, 0x50, 0xf2, 0x01, _, _, suites @ ..]) => {
+                self.wpa = true;
+                self.update_cipher_suites(suites);
+            }
+            // Extension element 35: HE capabilities
+            (255, [35, ..]) => self.he = true,
+            _ => {}
+        }
+    }
+
+    /// Pairwise ciphers and AKM suites from an RSN or WPA element, after its version field
+    fn update_cipher_suites(&mut self, suites: &[u8]) {
+        // Group cipher, then counted lists of 4-byte pairwise cipher and AKM suites
+        let list = |at: usize| -> Option<(&[u8], usize)> {
+            let count = u16::from_le_bytes(suites.get(at..at + 2)?.try_into().ok()?) as usize;
+            let end = at + 2 + 4 * count;
+            Some((suites.get(at + 2..end)?, end))
+        };
+        let Some((pairwise, akm_at)) = list(4) else {
+            return;
+        };
+        for suite in pairwise.chunks(4) {
+            match suite[3] {
+                2 => self.tkip = true,
+                4 | 8 | 9 | 10 => self.aes = true,
+                _ => {}
+            }
+        }
+        for suite in list(akm_at).map_or(&[][..], |(akms, _)| akms).chunks(4) {
+            match suite[3] {
+                8 | 9 | 24 | 25 => self.sae = true,
+                1 | 3 | 5 | 11 | 12 | 13 => self.enterprise = true,
+                _ => {}
+            }
+        }
+    }
+
+    fn update_auth_suites(&mut self, suites: &str) {
+        for suite in suites.split_whitespace() {
+            if suite.ends_with("SAE") {
+                self.sae = true;
+            } else if suite.ends_with("802.1X") || suite.contains("EAP") {
+                self.enterprise = true;
+            }
+        }
+    }
+
+    fn update_pairwise_ciphers(&mut self, ciphers: &str) {
+        for cipher in ciphers.split_whitespace() {
+            if cipher == "TKIP" {
+                self.tkip = true;
+            } else if cipher.starts_with("CCMP") || cipher.starts_with("GCMP") {
+                self.aes = true;
+            }
+        }
+    }
+
+    /// Pick up rate-related lines: legacy rates, HT/VHT MCS sets and channel widths
+    fn update_rate_info(&mut self, line: &str) {
+        if line.starts_with("VHT RX MCS set:") {
+            self.in_vht_rx_mcs = true;
+            return;
+        }
+        if self.in_vht_rx_mcs {
+            // "2 streams: MCS 0-9" or "3 streams: not supported"
+            if let Some((streams, mcs)) = line.split_once(" streams: MCS 0-") {
+                if let (Ok(streams), Ok(mcs)) = (streams.parse(), mcs.trim().parse()) {
+                    self.vht_mcs = Some((streams, mcs));
+                }
+                return;
+            }
+            if line.contains("streams:") {
+                return;
+            }
+            self.in_vht_rx_mcs = false;
+        }
+
+        if let Some(rates) = line
+            .strip_prefix("Supported rates:")
+            .or_else(|| line.strip_prefix("Extended supported rates:"))
+        {
+            let max = rates
+                .split_whitespace()
+                .filter_map(|r| r.trim_end_matches('*').parse::<f64>().ok())
+                .fold(None, |acc: Option<f64>, r| Some(acc.map_or(r, |a| a.max(r))));
+            self.legacy_rate = match (self.legacy_rate, max) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+        } else if line.starts_with("HT capabilities:") {
+            self.ht = true;
+        } else if line.starts_with("HE capabilities:") {
+            self.he = true;
+        } else if let Some(indexes) = line.strip_prefix("HT RX MCS rate indexes supported:") {
+            // "0-15" means MCS 0-15, i.e. two spatial streams
+            self.ht_streams = indexes
+                .rsplit('-')
+                .next()
+                .and_then(|max| max.trim().parse::<u32>().ok())
+                .map(|max| ((max + 1) / 8).clamp(1, 4));
+        } else if let Some(offset) = line.strip_prefix("* secondary channel offset:") {
+            self.ht40 = matches!(offset.trim(), "above" | "below");
+            self.ht40_above = offset.trim() == "above";
+        } else if let Some(width) = line.strip_prefix("* channel width:") {
+            self.vht_width_mhz = match width.split_whitespace().next() {
+                Some("1") => Some(80),
+                Some("2") | Some("3") => Some(160),
+                _ => None,
+            };
+        } else if let Some(segment) = line.strip_prefix("* center freq segment 1:") {
+            self.vht_center.0 = segment.trim().parse().unwrap_or(0);
+        } else if let Some(segment) = line.strip_prefix("* center freq segment 2:") {
+            self.vht_center.1 = segment.trim().parse().unwrap_or(0);
+        }
+    }
+
+    /// Operating width in MHz and the centre channel of the block it occupies
+    fn channel_layout(&self, primary: u32) -> (u32, u32) {
+        match (self.vht_width_mhz, self.vht_center) {
+            // Newer APs signal 160 MHz as width 80 with segment 2 holding the 160 MHz centre
+            (Some(80), (seg1, seg2)) if seg2 != 0 && seg1.abs_diff(seg2) == 8 => (160, seg2),
+            (Some(width), (seg1, _)) if seg1 != 0 => (width, seg1),
+            _ if self.ht40 && self.ht40_above => (40, primary + 2),
+            _ if self.ht40 => (40, primary.saturating_sub(2)),
+            _ => (20, primary),
+        }
+    }
+
+    /// Estimated maximum PHY rate in Mbps
+    ///
+    /// Deliberately conservative: long guard interval, the AP's advertised RX
+    /// MCS set and the channel width it is operating on, ignoring HE (802.11ax).
+    /// Real throughput is typically well below half of this.
+    fn max_rate_mbps(&self) -> Option<u32> {
+        let ht_width = if self.ht40 { 40 } else { 20 };
+
+        if let Some((streams, mcs)) = self.vht_mcs {
+            let width = self.vht_width_mhz.unwrap_or(ht_width);
+            // Per-stream rates for MCS 7, 8 and 9 (MCS 9 isn't valid at 20 MHz)
+            let per_stream = match (width, mcs) {
+                (20, 7) => 65.0,
+                (20, _) => 78.0,
+                (40, 7) => 135.0,
+                (40, 8) => 162.0,
+                (40, _) => 180.0,
+                (80, 7) => 292.5,
+                (80, 8) => 351.0,
+                (80, _) => 390.0,
+                (_, 7) => 585.0,
+                (_, 8) => 702.0,
+                _ => 780.0,
+            };
+            return Some((per_stream * streams as f64) as u32);
+        }
+
+        if self.ht {
+            let per_stream = if self.ht40 { 135 } else { 65 };
+            return Some(per_stream * self.ht_streams.unwrap_or(1));
+        }
+
+        self.legacy_rate.map(|r| r as u32)
+    }
+
+    /// Only 802.11b/g rates on offer: it advertised legacy rates and nothing newer
+    fn is_legacy(&self) -> bool {
+        self.legacy_rate.is_some() && !self.ht && self.vht_mcs.is_none() && !self.he
+    }
+
+    /// Derive the security type from everything seen in the BSS block
+    ///
+    /// Computed once at the end so the order in which iw prints the RSN/WPA
+    /// elements can't downgrade a stronger detection.
+    fn security(&self) -> SecurityType {
+        if self.sae {
+            SecurityType::WPA3
+        } else if self.enterprise {
+            SecurityType::WPA2Enterprise
+        } else if self.rsn {
+            SecurityType::WPA2
+        } else if self.wpa {
+            SecurityType::WPA
+        } else if self.privacy {
+            SecurityType::WEP
+        } else {
+            SecurityType::Open
         }
     }
 
     fn build(self) -> Option<WifiNetwork> {
+        let security = self.security();
+        let max_rate_mbps = self.max_rate_mbps();
+        let is_legacy = self.is_legacy();
+        let channel = self.channel.unwrap_or(0);
+        let (channel_width_mhz, center_channel) = self.channel_layout(channel);
+
+        // Hidden APs send an empty SSID or one padded with NUL bytes (shown by iw as \x00)
+        let ssid = self
+            .ssid
+            .filter(|s| !s.split("\\x00").all(str::is_empty));
+        let hidden = ssid.is_none();
+        let frequency = self.frequency.unwrap_or(0);
+
         Some(WifiNetwork {
-            ssid: self.ssid.unwrap_or_else(|| "<hidden>".to_string()),
+            ssid: ssid.unwrap_or_else(|| HIDDEN_SSID.to_string()),
             bssid: self.bssid,
-            channel: self.channel.unwrap_or(0),
-            frequency: self.frequency.unwrap_or(0),
+            channel,
+            frequency,
             signal_strength: self.signal_strength.unwrap_or(-100),
-            security: self.security,
+            security,
             mode: "Infrastructure".to_string(),
             last_seen: chrono::Utc::now(),
+            hidden,
+            beacon_interval_tu: self.beacon_interval_tu,
+            capability: self.capability,
+            last_seen_ms: self.last_seen_ms,
+            tkip_only: self.tkip && !self.aes,
+            max_rate_mbps,
+            channel_width_mhz: Some(channel_width_mhz),
+            center_channel: Some(center_channel),
+            psc: Band::from_frequency(frequency) == Some(Band::Six) && is_psc_channel(channel),
+            country_code: self.country_code,
+            remembered_ssid: None,
+            station_count: self.station_count,
+            channel_utilization: self.channel_utilization,
+            is_legacy,
+            seen_by: Vec::new(),
         })
     }
 }
 
-#[allow(dead_code)]
 /// Continuous scanner for real-time monitoring
 pub struct ContinuousScanner {
     scanner: WifiScanner,
-    scan_interval: Duration,
+    scan_interval: ScanInterval,
+    passive: bool,
+    alert: Option<AlertDebounce>,
+}
+
+/// Shortest pause allowed between continuous sweeps, so the adapter isn't scanning non-stop
+pub const MIN_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pause between continuous sweeps, shared so it can change while `run` is looping
+///
+/// Clones refer to the same value; a change applies from the next pause on.
+#[derive(Debug, Clone)]
+pub struct ScanInterval(std::sync::Arc<std::sync::atomic::AtomicU64>);
+
+impl ScanInterval {
+    pub fn new(interval: Duration) -> Self {
+        let handle = Self(std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)));
+        handle.set(interval);
+        handle
+    }
+
+    pub fn get(&self) -> Duration {
+        Duration::from_millis(self.0.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    /// Change the interval, raising it to `MIN_SCAN_INTERVAL` if shorter; returns the value used
+    pub fn set(&self, interval: Duration) -> Duration {
+        let interval = interval.max(MIN_SCAN_INTERVAL);
+        self.0.store(interval.as_millis() as u64, std::sync::atomic::Ordering::Relaxed);
+        interval
+    }
+}
+
+/// Minimum gap between new-network alerts, so a burst of discoveries rings once
+pub const ALERT_DEBOUNCE_SECS: u64 = 10;
+
+/// Rate limit for new-network alerts
+#[derive(Debug)]
+pub struct AlertDebounce {
+    interval: Duration,
+    last: Option<std::time::Instant>,
+}
+
+impl AlertDebounce {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, last: None }
+    }
+
+    /// Whether an alert may fire at `now`; records the time when it may
+    pub fn ready(&mut self, now: std::time::Instant) -> bool {
+        if self.last.is_some_and(|last| now.duration_since(last) < self.interval) {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
+
+impl Default for AlertDebounce {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(ALERT_DEBOUNCE_SECS))
+    }
 }
 
-#[allow(dead_code)]
 impl ContinuousScanner {
     pub fn new(interface: &str, scan_interval_secs: u64) -> Self {
         Self {
             scanner: WifiScanner::new(interface),
-            scan_interval: Duration::from_secs(scan_interval_secs),
+            scan_interval: ScanInterval::new(Duration::from_secs(scan_interval_secs)),
+            passive: false,
+            alert: None,
         }
     }
 
-    /// Run continuous scanning
+    /// Flag sweeps that find a BSSID not seen earlier this session, debounced; see `run`
+    pub fn set_alert(&mut self, alert: bool) {
+        self.alert = alert.then(AlertDebounce::default);
+    }
+
+    /// Change the pause between sweeps (at least `MIN_SCAN_INTERVAL`); returns the value used
+    pub fn set_interval(&mut self, interval: Duration) -> Duration {
+        self.scan_interval.set(interval)
+    }
+
+    /// Handle for changing the interval from another task while `run` is going
+    pub fn interval(&self) -> ScanInterval {
+        self.scan_interval.clone()
+    }
+
+    /// Use passive scans for every sweep
+    pub fn set_passive(&mut self, passive: bool) {
+        self.passive = passive;
+    }
+
+    /// Run continuous scanning until the callback returns an error
+    ///
+    /// The callback gets every sweep, failed ones included, and decides whether a
+    /// failure is worth stopping for. Its second argument is true when alerts are
+    /// on and the sweep found new BSSIDs; how to alert is up to the caller.
     pub async fn run<F>(&mut self, mut callback: F) -> Result<()>
     where
-        F: FnMut(Vec<WifiNetwork>),
+        F: FnMut(Result<Vec<WifiNetwork>>, bool) -> Result<()>,
     {
         loop {
-            match self.scanner.scan().await {
-                Ok(networks) => callback(networks),
-                Err(e) => eprintln!("Scan error: {}", e),
-            }
-            tokio::time::sleep(self.scan_interval).await;
+            let sweep = self.scanner.scan(self.passive).await;
+            let alert = sweep.is_ok()
+                && !self.scanner.discovered().is_empty()
+                && self.alert.as_mut().is_some_and(|alert| alert.ready(std::time::Instant::now()));
+            callback(sweep, alert)?;
+            // Read every cycle so a change made through `interval()` applies without a restart
+            tokio::time::sleep(self.scan_interval.get()).await;
         }
     }
 
@@ -304,6 +1374,49 @@ impl ContinuousScanner {
     }
 }
 
+/// A single network observation emitted by streaming scans
+#[derive(Debug, Serialize)]
+pub struct SweepRecord<'a> {
+    pub sweep_id: u64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub network: &'a WifiNetwork,
+}
+
+/// dBm boundaries between good, fair and weak signal, shared by the CLI and TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalThresholds {
+    /// Signals above this are good
+    pub good: i32,
+    /// Signals above this (and not good) are fair; the rest are weak
+    pub fair: i32,
+}
+
+impl Default for SignalThresholds {
+    fn default() -> Self {
+        Self { good: -50, fair: -70 }
+    }
+}
+
+/// How a signal rates against `SignalThresholds`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalLevel {
+    Good,
+    Fair,
+    Weak,
+}
+
+/// Rate a signal; every place that colors a signal goes through here
+pub fn signal_level(signal_dbm: i32, thresholds: &SignalThresholds) -> SignalLevel {
+    if signal_dbm > thresholds.good {
+        SignalLevel::Good
+    } else if signal_dbm > thresholds.fair {
+        SignalLevel::Fair
+    } else {
+        SignalLevel::Weak
+    }
+}
+
 /// Signal strength to quality percentage
 pub fn signal_to_quality(signal_dbm: i32) -> u8 {
     if signal_dbm >= -50 {
@@ -326,3 +1439,1219 @@ pub fn signal_to_bars(signal_dbm: i32) -> &'static str {
         _ => "░░░░",
     }
 }
+
+/// One entry per BSSID, keeping the strongest signal, sorted strongest first
+///
+/// The `seen_by` lists of all readings of a BSSID are combined.
+pub fn merge_strongest(networks: impl IntoIterator<Item = WifiNetwork>) -> Vec<WifiNetwork> {
+    let mut by_bssid: HashMap<String, WifiNetwork> = HashMap::new();
+    for mut network in networks {
+        match by_bssid.get_mut(&network.bssid) {
+            Some(seen) => {
+                let mut seen_by = std::mem::take(&mut seen.seen_by);
+                seen_by.append(&mut network.seen_by);
+                seen_by.sort();
+                seen_by.dedup();
+                if network.signal_strength > seen.signal_strength {
+                    *seen = network;
+                }
+                seen.seen_by = seen_by;
+            }
+            None => {
+                by_bssid.insert(network.bssid.clone(), network);
+            }
+        }
+    }
+    let mut merged: Vec<WifiNetwork> = by_bssid.into_values().collect();
+    merged.sort_by_key(|n| std::cmp::Reverse(n.signal_strength));
+    merged
+}
+
+/// Scan on every interface in `interfaces` at once and merge the results by BSSID
+///
+/// Each network lists the interfaces that saw it in `seen_by` and keeps the strongest
+/// reading. An interface whose scan fails is returned with its error instead of
+/// failing the rest.
+pub async fn scan_all(interfaces: &[String]) -> (Vec<WifiNetwork>, Vec<(String, anyhow::Error)>) {
+    let scans = interfaces.iter().map(|interface| async move {
+        let result = WifiScanner::new(interface).scan(false).await;
+        (interface.clone(), result)
+    });
+    merge_interface_scans(futures::future::join_all(scans).await)
+}
+
+/// Tag each network with the interface that scanned it, then merge; failures are passed through
+fn merge_interface_scans(
+    results: Vec<(String, Result<Vec<WifiNetwork>>)>,
+) -> (Vec<WifiNetwork>, Vec<(String, anyhow::Error)>) {
+    let mut found = Vec::new();
+    let mut failed = Vec::new();
+    for (interface, result) in results {
+        match result {
+            Ok(networks) => found.extend(networks.into_iter().map(|mut network| {
+                network.seen_by = vec![interface.clone()];
+                network
+            })),
+            Err(e) => failed.push((interface, e)),
+        }
+    }
+    (merge_strongest(found), failed)
+}
+
+/// The `limit` strongest networks, strongest first
+pub fn strongest(networks: &[WifiNetwork], limit: usize) -> Vec<WifiNetwork> {
+    let mut sorted = networks.to_vec();
+    sorted.sort_by_key(|n| std::cmp::Reverse(n.signal_strength));
+    sorted.truncate(limit);
+    sorted
+}
+
+/// Order for printed scan results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SortKey {
+    /// Strongest first
+    #[default]
+    Signal,
+    /// By name, case-insensitive
+    Ssid,
+    /// Lowest channel first
+    Channel,
+    /// Weakest security first: open, WEP, WPA, WPA2, enterprise, WPA3
+    Security,
+    /// Best `WifiNetwork::quality_score` first
+    Quality,
+}
+
+impl SortKey {
+    /// Compare two networks by this key; ties go to the stronger signal
+    pub fn compare(self, a: &WifiNetwork, b: &WifiNetwork) -> std::cmp::Ordering {
+        let rank = |s: SecurityType| match s {
+            SecurityType::Open => 0,
+            SecurityType::WEP => 1,
+            SecurityType::WPA => 2,
+            SecurityType::WPA2 => 3,
+            SecurityType::WPA2Enterprise => 4,
+            SecurityType::WPA3 => 5,
+            SecurityType::Unknown => 6,
+        };
+        let by_key = match self {
+            SortKey::Signal => std::cmp::Ordering::Equal,
+            SortKey::Ssid => a.ssid.to_lowercase().cmp(&b.ssid.to_lowercase()),
+            SortKey::Channel => (a.frequency, a.channel).cmp(&(b.frequency, b.channel)),
+            SortKey::Security => rank(a.security).cmp(&rank(b.security)),
+            SortKey::Quality => b.quality_score().cmp(&a.quality_score()),
+        };
+        by_key.then(b.signal_strength.cmp(&a.signal_strength))
+    }
+}
+
+/// Sort `networks` by `key`, or in the opposite order with `reverse`
+pub fn sort_networks(networks: &mut [WifiNetwork], key: SortKey, reverse: bool) {
+    networks.sort_by(|a, b| {
+        let order = key.compare(a, b);
+        if reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+}
+
+/// Which tool produced the results of `scan_nondisruptive`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanMethod {
+    /// `iw scan`, which may briefly take an associated interface off its channel
+    Iw,
+    /// NetworkManager's background rescan, used while associated
+    NetworkManager,
+}
+
+/// WiFi frequency band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Band {
+    TwoPointFour,
+    Five,
+    Six,
+}
+
+impl Band {
+    pub fn from_frequency(freq: u32) -> Option<Band> {
+        match freq {
+            2400..=2500 => Some(Band::TwoPointFour),
+            5150..=5925 => Some(Band::Five),
+            5926..=7125 => Some(Band::Six),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Band {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Band::TwoPointFour => write!(f, "2.4 GHz"),
+            Band::Five => write!(f, "5 GHz"),
+            Band::Six => write!(f, "6 GHz"),
+        }
+    }
+}
+
+/// 5 GHz channels subject to radar detection (DFS) that an AP may have to vacate
+pub fn is_dfs_channel(channel: u32) -> bool {
+    matches!(channel, 52..=64 | 100..=144)
+}
+
+/// Canonical cache key for a BSSID: lowercase hex pairs joined by colons
+///
+/// iw prints lowercase and nmcli uppercase; `-` separated or bare forms are accepted too.
+/// Anything that isn't six octets is only trimmed and lowercased.
+fn normalize_bssid(s: &str) -> String {
+    let hex: String = s.trim().chars().filter(|c| !matches!(c, ':' | '-' | '.')).collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return s.trim().to_lowercase();
+    }
+    hex.to_lowercase()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// 6 GHz Preferred Scanning Channels: every fourth 20 MHz channel, 5 through 229
+pub fn is_psc_channel(channel: u32) -> bool {
+    (5..=229).contains(&channel) && channel % 16 == 5
+}
+
+/// Non-overlapping 2.4 GHz channels worth recommending
+const CHANNELS_2GHZ: [u32; 3] = [1, 6, 11];
+/// Every 2.4 GHz channel outside Japan's channel 14
+pub const SPECTRUM_CHANNELS_2GHZ: [u32; 13] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+/// 20 MHz 5 GHz channels
+pub const CHANNELS_5GHZ: [u32; 25] = [
+    36, 40, 44, 48, 52, 56, 60, 64, 100, 104, 108, 112, 116, 120, 124, 128, 132, 136, 140, 144,
+    149, 153, 157, 161, 165,
+];
+/// Extra load charged to DFS channels so a clean non-DFS channel wins a near tie
+const DFS_PENALTY: u32 = 25;
+
+/// Signal swings smaller than this are treated as noise by `diff_scans`
+pub const SIGNAL_CHANGE_THRESHOLD_DBM: i32 = 6;
+
+/// What changed between two scans of the same area, matched by BSSID
+#[derive(Debug, Default, Serialize)]
+pub struct ScanDiff {
+    pub appeared: Vec<WifiNetwork>,
+    pub disappeared: Vec<WifiNetwork>,
+    pub changed: Vec<SignalChange>,
+}
+
+/// An access point present in both scans whose signal moved noticeably
+#[derive(Debug, Serialize)]
+pub struct SignalChange {
+    #[serde(flatten)]
+    pub network: WifiNetwork,
+    pub old_signal: i32,
+    /// New minus old, in dB; positive means stronger
+    pub delta: i32,
+}
+
+/// Compare two scans: new BSSIDs, vanished BSSIDs and signal changes beyond the threshold
+pub fn diff_scans(old: &[WifiNetwork], new: &[WifiNetwork]) -> ScanDiff {
+    let old_by_bssid: HashMap<&str, &WifiNetwork> = old.iter().map(|n| (n.bssid.as_str(), n)).collect();
+    let new_by_bssid: HashMap<&str, &WifiNetwork> = new.iter().map(|n| (n.bssid.as_str(), n)).collect();
+
+    let mut diff = ScanDiff::default();
+    for (bssid, network) in &new_by_bssid {
+        match old_by_bssid.get(bssid) {
+            None => diff.appeared.push((*network).clone()),
+            Some(before) => {
+                let delta = network.signal_strength - before.signal_strength;
+                if delta.abs() >= SIGNAL_CHANGE_THRESHOLD_DBM {
+                    diff.changed.push(SignalChange {
+                        network: (*network).clone(),
+                        old_signal: before.signal_strength,
+                        delta,
+                    });
+                }
+            }
+        }
+    }
+    diff.disappeared = old_by_bssid
+        .iter()
+        .filter(|(bssid, _)| !new_by_bssid.contains_key(*bssid))
+        .map(|(_, n)| (*n).clone())
+        .collect();
+
+    diff.appeared.sort_by_key(|n| std::cmp::Reverse(n.signal_strength));
+    diff.disappeared.sort_by_key(|n| std::cmp::Reverse(n.signal_strength));
+    diff.changed.sort_by_key(|c| std::cmp::Reverse(c.delta.abs()));
+    diff
+}
+
+/// Congestion on one 20 MHz channel: the summed signal quality (0-100) of every AP in `band` overlapping it
+///
+/// An AP covers its whole occupied block, so an 80 MHz AP loads four 5 GHz channels.
+/// On 2.4 GHz, where 20 MHz channels are only 5 MHz apart, anything within four
+/// channels of the block also counts.
+pub fn channel_load(networks: &[WifiNetwork], band: Band, channel: u32) -> u32 {
+    overlapping(networks, band, channel)
+        .map(|n| signal_to_quality(n.signal_strength) as u32)
+        .sum()
+}
+
+/// Number of APs in `band` overlapping a 20 MHz channel, counted the same way as `channel_load`
+pub fn channel_ap_count(networks: &[WifiNetwork], band: Band, channel: u32) -> usize {
+    overlapping(networks, band, channel).count()
+}
+
+fn overlapping(networks: &[WifiNetwork], band: Band, channel: u32) -> impl Iterator<Item = &WifiNetwork> {
+    let overlap = if band == Band::TwoPointFour { 4 } else { 0 };
+    networks
+        .iter()
+        .filter(move |n| Band::from_frequency(n.frequency) == Some(band))
+        .filter(move |n| {
+            let (first, last) = n.occupied_channels();
+            channel + overlap >= first && channel <= last + overlap
+        })
+}
+
+/// Busiest utilization (percent) reported in a BSS Load element by an AP overlapping `channel`
+///
+/// This is airtime the AP measured, so it also covers non-WiFi noise and clients
+/// that a scan can't see. APs that don't send the element count as 0.
+pub fn reported_utilization(networks: &[WifiNetwork], band: Band, channel: u32) -> u32 {
+    overlapping(networks, band, channel)
+        .filter_map(|n| n.channel_utilization)
+        .max()
+        .map_or(0, u32::from)
+}
+
+/// Networks per band, leaving out empty bands
+pub fn band_counts(networks: &[WifiNetwork]) -> Vec<(Band, usize)> {
+    [Band::TwoPointFour, Band::Five, Band::Six]
+        .into_iter()
+        .map(|band| (band, networks.iter().filter(|n| Band::from_frequency(n.frequency) == Some(band)).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// Pick the least congested channel in a band
+///
+/// Ranks channels by `channel_load` plus `reported_utilization`. DFS channels
+/// carry a small penalty so they are only chosen when clearly quieter.
+pub fn recommend_channel(networks: &[WifiNetwork], band: Band) -> Option<u32> {
+    let candidates: &[u32] = match band {
+        Band::TwoPointFour => &CHANNELS_2GHZ,
+        Band::Five => &CHANNELS_5GHZ,
+        Band::Six => return None,
+    };
+
+    candidates.iter().copied().min_by_key(|&candidate| {
+        let load = channel_load(networks, band, candidate) + reported_utilization(networks, band, candidate);
+        load + if is_dfs_channel(candidate) { DFS_PENALTY } else { 0 }
+    })
+}
+
+/// Append everything read from `reader` to `buf`, chunk by chunk
+///
+/// Unlike `read_to_end`, bytes already appended stay in `buf` if the future is dropped.
+async fn read_chunks<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut Vec<u8>) -> std::io::Result<()> {
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// Networks per security category, from an auditor's point of view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SecuritySummary {
+    pub open: usize,
+    pub wep: usize,
+    /// WPA2/WPA3 networks that only offer TKIP
+    pub tkip: usize,
+    pub wpa: usize,
+    /// WPA2/WPA3 with CCMP or GCMP
+    pub modern: usize,
+    pub unknown: usize,
+}
+
+impl SecuritySummary {
+    /// Open, WEP, TKIP-only and WPA networks together
+    pub fn insecure(&self) -> usize {
+        self.open + self.wep + self.tkip + self.wpa
+    }
+}
+
+impl std::fmt::Display for SecuritySummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.insecure() {
+            0 => write!(f, "No insecure networks detected"),
+            n => write!(
+                f,
+                "{} insecure network{} detected ({} open, {} WEP, {} TKIP, {} WPA)",
+                n,
+                if n == 1 { "" } else { "s" },
+                self.open,
+                self.wep,
+                self.tkip,
+                self.wpa
+            ),
+        }
+    }
+}
+
+/// Whether a network uses no encryption or a deprecated scheme
+pub fn is_insecure(network: &WifiNetwork) -> bool {
+    match network.security {
+        SecurityType::Open | SecurityType::WEP | SecurityType::WPA => true,
+        SecurityType::WPA2 | SecurityType::WPA3 | SecurityType::WPA2Enterprise => network.tkip_only,
+        SecurityType::Unknown => false,
+    }
+}
+
+/// Count networks per security category
+pub fn security_summary(networks: &[WifiNetwork]) -> SecuritySummary {
+    let mut summary = SecuritySummary::default();
+    for network in networks {
+        let bucket = match network.security {
+            SecurityType::Open => &mut summary.open,
+            SecurityType::WEP => &mut summary.wep,
+            SecurityType::WPA => &mut summary.wpa,
+            SecurityType::Unknown => &mut summary.unknown,
+            _ if network.tkip_only => &mut summary.tkip,
+            _ => &mut summary.modern,
+        };
+        *bucket += 1;
+    }
+    summary
+}
+
+/// Longest `monitor_signal` waits for the frames it was asked for
+#[cfg(feature = "radiotap")]
+const MONITOR_SIGNAL_TIMEOUT_MS: u64 = 1500;
+
+/// Signal of the last `samples` frames sent by `bssid`, from their radiotap headers
+///
+/// Unlike an `iw scan` snapshot this is one reading per beacon, which helps when walking
+/// towards a transmitter. `interface` must be in monitor mode and tuned to the AP's channel,
+/// and opening the packet socket needs root (CAP_NET_RAW). Returns what was heard within
+/// `MONITOR_SIGNAL_TIMEOUT_MS`, failing only if that is nothing.
+#[cfg(feature = "radiotap")]
+pub async fn monitor_signal(interface: &str, bssid: &str, samples: usize) -> Result<Vec<i32>> {
+    let target = mac_bytes(bssid).ok_or_else(|| anyhow!("Invalid BSSID {}", bssid))?;
+    if NetworkManager::get_wireless_mode(interface).ok() != Some(crate::network::WirelessMode::Monitor) {
+        return Err(anyhow!("{} must be in monitor mode to read per-frame signal", interface));
+    }
+
+    let name = interface.to_string();
+    let limit = Duration::from_millis(MONITOR_SIGNAL_TIMEOUT_MS);
+    let readings = tokio::task::spawn_blocking(move || capture_signal(&name, &target, samples, limit)).await??;
+    if readings.is_empty() {
+        return Err(anyhow!(
+            "No frames from {} heard on {} within {}ms; is it on the AP's channel?",
+            bssid,
+            interface,
+            MONITOR_SIGNAL_TIMEOUT_MS
+        ));
+    }
+    Ok(readings)
+}
+
+#[cfg(not(feature = "radiotap"))]
+pub async fn monitor_signal(_interface: &str, _bssid: &str, _samples: usize) -> Result<Vec<i32>> {
+    Err(anyhow!("Per-frame signal needs sozin built with --features radiotap"))
+}
+
+/// Read frames from a raw packet socket on `interface` until `samples` came from `bssid` or `limit` passed
+#[cfg(feature = "radiotap")]
+fn capture_signal(interface: &str, bssid: &[u8; 6], samples: usize, limit: Duration) -> Result<Vec<i32>> {
+    // Wake up regularly so the deadline is checked even when the channel is quiet
+    let socket = crate::capture::PacketSocket::open(interface, Duration::from_millis(200))?;
+    let deadline = std::time::Instant::now() + limit;
+    let mut buf = vec![0u8; 4096];
+    let mut readings = Vec::new();
+    while readings.len() < samples && std::time::Instant::now() < deadline {
+        if let Some(dbm) = socket.recv(&mut buf)?.and_then(|(frame, _)| frame_signal(frame, bssid)) {
+            readings.push(dbm);
+        }
+    }
+    Ok(readings)
+}
+
+/// "aa:bb:cc:dd:ee:ff" as bytes
+#[cfg_attr(not(feature = "radiotap"), allow(dead_code))]
+fn mac_bytes(mac: &str) -> Option<[u8; 6]> {
+    let mut bytes = [0u8; 6];
+    let mut parts = mac.split(':');
+    for byte in &mut bytes {
+        *byte = u8::from_str_radix(parts.next()?, 16).ok()?;
+    }
+    parts.next().is_none().then_some(bytes)
+}
+
+/// Antenna signal (dBm) of a radiotap-wrapped 802.11 frame transmitted by `bssid`
+///
+/// `None` for frames from anyone else, frames the driver flagged with a bad FCS, and
+/// headers without a signal field.
+#[cfg_attr(not(feature = "radiotap"), allow(dead_code))]
+fn frame_signal(frame: &[u8], bssid: &[u8; 6]) -> Option<i32> {
+    if frame.len() < 8 || frame[0] != 0 {
+        return None;
+    }
+    let header_len = usize::from(u16::from_le_bytes([frame[2], frame[3]]));
+    let present = u32::from_le_bytes(frame[4..8].try_into().ok()?);
+    if frame.len() < header_len || present & (1 << 5) == 0 {
+        return None;
+    }
+
+    // Bit 31 chains further present words; fields start after the last one
+    let mut offset = 8;
+    let mut word = present;
+    while word & (1 << 31) != 0 {
+        word = u32::from_le_bytes(frame.get(offset..offset + 4)?.try_into().ok()?);
+        offset += 4;
+    }
+
+    // (size, alignment) of TSFT, Flags, Rate, Channel and FHSS, the fields before antenna signal
+    const FIELDS: [(usize, usize); 5] = [(8, 8), (1, 1), (1, 1), (4, 2), (2, 1)];
+    for (bit, (size, align)) in FIELDS.into_iter().enumerate() {
+        if present & (1 << bit) == 0 {
+            continue;
+        }
+        offset = offset.next_multiple_of(align);
+        // Flags 0x40: frame failed its FCS check, so the addresses can't be trusted
+        if bit == 1 && frame.get(offset)? & 0x40 != 0 {
+            return None;
+        }
+        offset += size;
+    }
+    if offset >= header_len {
+        return None;
+    }
+    let dbm = i32::from(frame[offset] as i8);
+
+    // Transmitter address (addr2) of the 802.11 header
+    let transmitter = frame.get(header_len + 10..header_len + 16)?;
+    (transmitter == bssid).then_some(dbm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(channel: u32, frequency: u32, signal_strength: i32) -> WifiNetwork {
+        WifiNetwork {
+            ssid: format!("ch{}", channel),
+            bssid: format!("00:00:00:00:{:02x}:{:02x}", channel, signal_strength.unsigned_abs()),
+            channel,
+            frequency,
+            signal_strength,
+            security: SecurityType::WPA2,
+            mode: "Infrastructure".to_string(),
+            last_seen: chrono::Utc::now(),
+            hidden: false,
+            beacon_interval_tu: None,
+            capability: None,
+            last_seen_ms: None,
+            tkip_only: false,
+            max_rate_mbps: None,
+            channel_width_mhz: None,
+            center_channel: None,
+            psc: false,
+            country_code: None,
+            remembered_ssid: None,
+            station_count: None,
+            channel_utilization: None,
+            is_legacy: false,
+            seen_by: Vec::new(),
+        }
+    }
+
+    fn parse(output: &str) -> Vec<WifiNetwork> {
+        WifiScanner::new("wlan0").parse_scan_results(output).unwrap()
+    }
+
+    fn parse_one(output: &str) -> WifiNetwork {
+        let mut networks = parse(output);
+        assert_eq!(networks.len(), 1);
+        networks.remove(0)
+    }
+
+    #[test]
+    fn parses_open_network() {
+        let net = parse_one(include_str!("../tests/fixtures/open.txt"));
+        assert_eq!(net.ssid, "CoffeeShop");
+        assert_eq!(net.bssid, "00:11:22:33:44:01");
+        assert_eq!(net.frequency, 2412);
+        assert_eq!(net.channel, 1);
+        assert_eq!(net.signal_strength, -61);
+        assert_eq!(net.security, SecurityType::Open);
+        assert!(!net.hidden);
+    }
+
+    #[test]
+    fn parses_wpa2_psk() {
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_psk.txt"));
+        assert_eq!(net.ssid, "HomeNet");
+        assert_eq!(net.bssid, "aa:bb:cc:dd:ee:02");
+        assert_eq!(net.channel, 6);
+        assert_eq!(net.signal_strength, -45);
+        assert_eq!(net.security, SecurityType::WPA2);
+    }
+
+    #[test]
+    fn parses_beacon_capability_and_last_seen() {
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_enterprise.txt"));
+        assert_eq!(net.beacon_interval_tu, Some(102));
+        assert_eq!(net.capability.as_deref(), Some("ESS Privacy ShortSlotTime RadioMeasure"));
+        assert_eq!(net.last_seen_ms, Some(300));
+
+        // Blocks without these lines leave them unset
+        let net = parse_one("BSS 00:11:22:33:44:0c(on wlan0)\n\tfreq: 2412\n\tSSID: Bare\n");
+        assert_eq!(net.beacon_interval_tu, None);
+        assert_eq!(net.capability, None);
+        assert_eq!(net.last_seen_ms, None);
+    }
+
+    #[test]
+    fn parses_wpa2_enterprise_from_auth_suites() {
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_enterprise.txt"));
+        assert_eq!(net.ssid, "CorpWiFi");
+        assert_eq!(net.channel, 11);
+        assert_eq!(net.security, SecurityType::WPA2Enterprise);
+    }
+
+    #[test]
+    fn parses_wpa3_sae() {
+        let net = parse_one(include_str!("../tests/fixtures/wpa3_sae.txt"));
+        assert_eq!(net.ssid, "Modern");
+        assert_eq!(net.security, SecurityType::WPA3);
+    }
+
+    #[test]
+    fn parses_5ghz_network() {
+        let net = parse_one(include_str!("../tests/fixtures/5ghz.txt"));
+        assert_eq!(net.ssid, "HomeNet-5G");
+        assert_eq!(net.frequency, 5180);
+        assert_eq!(net.channel, 36);
+        assert_eq!(net.signal_strength, -58);
+        assert_eq!(net.security, SecurityType::WPA2);
+    }
+
+    #[test]
+    fn estimates_max_rate() {
+        // 802.11ac, 2 streams up to MCS 9 on an 80 MHz channel
+        let net = parse_one(include_str!("../tests/fixtures/5ghz.txt"));
+        assert_eq!(net.max_rate_mbps, Some(780));
+
+        // 802.11g only: highest legacy rate
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_psk.txt"));
+        assert_eq!(net.max_rate_mbps, Some(54));
+    }
+
+    #[test]
+    fn ds_parameter_channel_takes_first_integer() {
+        assert_eq!(WifiScanner::parse_ds_channel("DS Parameter set: channel 11"), Some(11));
+        assert_eq!(WifiScanner::parse_ds_channel("DS Parameter set: channel 11 (primary)"), Some(11));
+        assert_eq!(WifiScanner::parse_ds_channel("DS Parameter set: channel"), None);
+    }
+
+    #[test]
+    fn ds_parameter_overrides_only_when_valid() {
+        let net = parse_one("BSS 00:11:22:33:44:0a(on wlan0)\n\tfreq: 2462\n\tDS Parameter set: channel 11\n");
+        assert_eq!(net.channel, 11);
+
+        let net = parse_one("BSS 00:11:22:33:44:0b(on wlan0)\n\tfreq: 2437\n\tDS Parameter set: channel ?\n");
+        assert_eq!(net.channel, 6);
+
+        // 5 GHz APs carry no DS parameter; the channel comes from the frequency
+        let net = parse_one(include_str!("../tests/fixtures/5ghz.txt"));
+        assert_eq!(net.channel, 36);
+    }
+
+    #[test]
+    fn hidden_networks_stay_distinct() {
+        let networks = parse(include_str!("../tests/fixtures/hidden.txt"));
+        assert_eq!(networks.len(), 2);
+        assert!(networks.iter().all(|n| n.hidden && n.ssid == HIDDEN_SSID));
+        assert_eq!(networks[0].bssid, "de:ad:be:ef:00:05");
+        assert_eq!(networks[0].security, SecurityType::WPA2);
+        assert_eq!(networks[1].bssid, "de:ad:be:ef:00:06");
+        assert_eq!(networks[1].security, SecurityType::Open);
+    }
+
+    #[test]
+    fn hidden_network_resolved_from_cache() {
+        let mut scanner = WifiScanner::new("wlan0");
+        scanner
+            .parse_scan_results("BSS de:ad:be:ef:00:05(on wlan0)\n\tSSID: Backroom\n")
+            .unwrap();
+        let networks = scanner
+            .parse_scan_results(include_str!("../tests/fixtures/hidden.txt"))
+            .unwrap();
+        let resolved = networks.iter().find(|n| n.bssid == "de:ad:be:ef:00:05").unwrap();
+        assert_eq!(resolved.ssid, "Backroom");
+        assert!(resolved.hidden);
+    }
+
+    #[test]
+    fn hidden_network_shows_name_remembered_from_an_earlier_run() {
+        let mut store = ScanStore::default();
+        store.remember("de:ad:be:ef:00:05", "Backroom");
+        let mut scanner = WifiScanner::new("wlan0").with_store(store);
+        let networks = scanner
+            .parse_scan_results(include_str!("../tests/fixtures/hidden.txt"))
+            .unwrap();
+
+        let remembered = networks.iter().find(|n| n.bssid == "de:ad:be:ef:00:05").unwrap();
+        assert_eq!((remembered.ssid.as_str(), remembered.display_ssid()), (HIDDEN_SSID, "(Backroom)".to_string()));
+        let unknown = networks.iter().find(|n| n.bssid != "de:ad:be:ef:00:05").unwrap();
+        assert_eq!(unknown.display_ssid(), HIDDEN_SSID);
+    }
+
+    #[test]
+    fn security_is_independent_of_element_order() {
+        let networks = parse(include_str!("../tests/fixtures/wpa_mixed.txt"));
+        let mixed = networks.iter().find(|n| n.ssid == "Legacy-Mixed").unwrap();
+        assert_eq!(mixed.security, SecurityType::WPA2);
+
+        // Same AP with the WPA element printed before RSN
+        let reordered = include_str!("../tests/fixtures/wpa_mixed.txt")
+            .replacen("RSN:", "XXX:", 1)
+            .replacen("WPA:", "RSN:", 1)
+            .replacen("XXX:", "WPA:", 1);
+        let networks = parse(&reordered);
+        let mixed = networks.iter().find(|n| n.ssid == "Legacy-Mixed").unwrap();
+        assert_eq!(mixed.security, SecurityType::WPA2);
+
+        let ancient = networks.iter().find(|n| n.ssid == "Ancient").unwrap();
+        assert_eq!(ancient.security, SecurityType::WEP);
+    }
+
+    #[test]
+    fn summarises_insecure_networks() {
+        let output = [
+            include_str!("../tests/fixtures/open.txt"),
+            include_str!("../tests/fixtures/wpa2_psk.txt"),
+            include_str!("../tests/fixtures/wpa3_sae.txt"),
+            // Legacy-Mixed offers CCMP alongside TKIP, so only Ancient (WEP) counts
+            include_str!("../tests/fixtures/wpa_mixed.txt"),
+        ]
+        .concat();
+        let summary = security_summary(&parse(&output));
+        assert_eq!(summary.open, 1);
+        assert_eq!(summary.wep, 1);
+        assert_eq!(summary.tkip, 0);
+        assert_eq!(summary.modern, 3);
+        assert_eq!(summary.to_string(), "2 insecure networks detected (1 open, 1 WEP, 0 TKIP, 0 WPA)");
+
+        let tkip_only = include_str!("../tests/fixtures/wpa_mixed.txt").replace("CCMP TKIP", "TKIP");
+        let networks = parse(&tkip_only);
+        let mixed = networks.iter().find(|n| n.ssid == "Legacy-Mixed").unwrap();
+        assert!(mixed.tkip_only && is_insecure(mixed));
+        assert_eq!(security_summary(&networks).tkip, 1);
+    }
+
+    #[test]
+    fn parse_saved_sniffs_format() {
+        let raw = include_str!("../tests/fixtures/wpa2_psk.txt");
+        let mut scanner = WifiScanner::new("wlan0");
+        let from_raw = scanner.parse_saved(raw).unwrap();
+        assert_eq!(from_raw.len(), 1);
+
+        let json = serde_json::to_string_pretty(&from_raw).unwrap();
+        let from_json = scanner.parse_saved(&json).unwrap();
+        assert_eq!(from_json[0].bssid, from_raw[0].bssid);
+
+        let jsonl = format!("{}\n", serde_json::to_string(&from_raw[0]).unwrap());
+        assert_eq!(scanner.parse_saved(&jsonl).unwrap().len(), 1);
+
+        let enveloped = crate::output::to_json(&from_raw).unwrap();
+        assert_eq!(scanner.parse_saved(&enveloped).unwrap()[0].bssid, from_raw[0].bssid);
+        let enveloped_lines = format!("{0}\n{0}\n", crate::output::to_json_line(&from_raw[0]).unwrap());
+        assert_eq!(scanner.parse_saved(&enveloped_lines).unwrap().len(), 2);
+
+        assert!(scanner.parse_saved("hello world\n").is_err());
+        assert!(scanner.parse_saved("[{\"ssid\": 1}]").is_err());
+    }
+
+    #[test]
+    fn truncated_output_keeps_complete_networks() {
+        let output = [
+            include_str!("../tests/fixtures/open.txt"),
+            include_str!("../tests/fixtures/wpa2_psk.txt"),
+        ]
+        .concat();
+        // Killed partway through the second BSS, mid-line
+        let cut = output.find("signal: -45").unwrap() + "signal: -4".len();
+        let networks = WifiScanner::new("wlan0").parse_truncated(&output[..cut]).unwrap();
+        assert_eq!(networks.len(), 2);
+        let partial = networks.iter().find(|n| n.bssid == "aa:bb:cc:dd:ee:02").unwrap();
+        assert_eq!(partial.signal_strength, -100, "half-written signal line must be ignored");
+
+        assert!(WifiScanner::new("wlan0").parse_truncated("BSS 00:11").unwrap().is_empty());
+    }
+
+    #[test]
+    fn results_sorted_strongest_first() {
+        let output = [
+            include_str!("../tests/fixtures/open.txt"),
+            include_str!("../tests/fixtures/wpa2_psk.txt"),
+            include_str!("../tests/fixtures/wpa2_enterprise.txt"),
+        ]
+        .concat();
+        let signals: Vec<i32> = parse(&output).iter().map(|n| n.signal_strength).collect();
+        assert_eq!(signals, [-45, -61, -67]);
+    }
+
+    #[test]
+    fn scan_args_default_to_active() {
+        assert_eq!(WifiScanner::scan_args("wlan0", false), ["dev", "wlan0", "scan"]);
+    }
+
+    #[test]
+    fn scan_args_passive() {
+        assert_eq!(
+            WifiScanner::scan_args("wlan0", true),
+            ["dev", "wlan0", "scan", "passive"]
+        );
+    }
+
+    #[test]
+    fn merge_strongest_keeps_best_reading_per_bssid() {
+        let weak = network(36, 5180, -80);
+        let mut strong = weak.clone();
+        strong.signal_strength = -55;
+        let other = network(6, 2437, -60);
+
+        let merged = merge_strongest([weak, other, strong]);
+        let signals: Vec<i32> = merged.iter().map(|n| n.signal_strength).collect();
+        assert_eq!(signals, [-55, -60]);
+    }
+
+    #[test]
+    fn scans_from_several_interfaces_merge_with_who_saw_what() {
+        let shared = network(6, 2437, -70);
+        let mut nearer = shared.clone();
+        nearer.signal_strength = -50;
+        let only_wlan1 = network(36, 5180, -60);
+
+        let (merged, failed) = merge_interface_scans(vec![
+            ("wlan0".to_string(), Ok(vec![shared])),
+            ("wlan1".to_string(), Ok(vec![nearer, only_wlan1])),
+            ("wlan2".to_string(), Err(anyhow!("Scan failed: Network is down"))),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].signal_strength, -50);
+        assert_eq!(merged[0].seen_by, ["wlan0", "wlan1"]);
+        assert_eq!(merged[1].seen_by, ["wlan1"]);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "wlan2");
+    }
+
+    #[test]
+    fn radiotap_signal_is_read_for_the_watched_bssid_only() {
+        let bssid = mac_bytes("00:11:22:33:44:55").unwrap();
+        // TSFT, Flags, Rate, Channel and antenna signal (-42 dBm), padded to 24 bytes
+        let mut frame = vec![0, 0, 24, 0, 0x2f, 0, 0, 0];
+        frame.extend([0; 8]);
+        frame.extend([0x00, 0x02, 0x6c, 0x09, 0xa0, 0x00, (-42i8) as u8, 0]);
+        // Beacon: frame control, duration, addr1 (broadcast), addr2 and addr3 (the BSSID)
+        frame.extend([0x80, 0, 0, 0]);
+        frame.extend([0xff; 6]);
+        frame.extend(bssid);
+        frame.extend(bssid);
+        frame.extend([0, 0]);
+        assert_eq!(frame_signal(&frame, &bssid), Some(-42));
+        assert_eq!(frame_signal(&frame, &mac_bytes("00:11:22:33:44:66").unwrap()), None);
+
+        // Bad FCS
+        frame[16] = 0x40;
+        assert_eq!(frame_signal(&frame, &bssid), None);
+        assert_eq!(frame_signal(&frame[..6], &bssid), None);
+        assert_eq!(mac_bytes("00:11:22:33:44"), None);
+    }
+
+    #[test]
+    fn supported_scan_keeps_6ghz_apart_from_2_4ghz() {
+        let phy = include_str!("../tests/fixtures/phy_dualband_6ghz.txt");
+        let freqs = NetworkManager::parse_supported_frequencies(phy);
+        assert_eq!(
+            WifiScanner::freq_scan_args("wlan0", &freqs),
+            ["dev", "wlan0", "scan", "freq", "2412", "2437", "2462", "5955", "5975", "6035"]
+        );
+    }
+
+    #[test]
+    fn nl80211_entries_read_like_iw_output() {
+        let rsn_sae = [1, 0, 0x00, 0x0f, 0xac, 4, 1, 0, 0x00, 0x0f, 0xac, 4, 1, 0, 0x00, 0x0f, 0xac, 8];
+        let mut ies = vec![0, 3, b'L', b'a', b'b', 3, 1, 1, 7, 3, b'D', b'E', 0x20, 11, 5, 4, 0, 128, 0, 0];
+        ies.extend([48, rsn_sae.len() as u8]);
+        ies.extend(rsn_sae);
+        ies.extend([255, 1, 35]);
+        let bss = ScanBss {
+            bssid: [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e],
+            frequency: 5955,
+            signal_mbm: Some(-4725),
+            beacon_interval_tu: Some(100),
+            capability: Some(0x0011),
+            seen_ms_ago: Some(40),
+            ies,
+        };
+        let network = WifiNetworkBuilder::from_bss(&bss).build().unwrap();
+        assert_eq!((network.ssid.as_str(), network.bssid.as_str()), ("Lab", "00:1a:2b:3c:4d:5e"));
+        assert_eq!((network.channel, network.signal_strength), (1, -47));
+        assert_eq!(network.security, SecurityType::WPA3);
+        assert_eq!(network.capability.as_deref(), Some("ESS Privacy"));
+        assert_eq!(network.country_code.as_deref(), Some("DE"));
+        assert_eq!((network.station_count, network.channel_utilization), (Some(4), Some(50)));
+        assert_eq!(network.last_seen_ms, Some(40));
+
+        // WPA1 with only TKIP, behind a hidden SSID
+        let wpa_tkip = [0x00, 0x50, 0xf2, 1, 1, 0, 0x00, 0x50, 0xf2, 2, 1, 0, 0x00, 0x50, 0xf2, 2, 1, 0, 0x00, 0x50, 0xf2, 2];
+        let mut ies = vec![0, 2, 0, 0, 221, wpa_tkip.len() as u8];
+        ies.extend(wpa_tkip);
+        let bss = ScanBss { frequency: 2437, capability: Some(0x0011), ies, ..bss };
+        let network = WifiNetworkBuilder::from_bss(&bss).build().unwrap();
+        assert!(network.hidden);
+        assert_eq!(network.security, SecurityType::WPA);
+        assert!(network.tkip_only);
+        assert_eq!(network.channel, 6);
+    }
+
+    #[test]
+    fn channel_to_freq_inverts_freq_to_channel() {
+        for freq in (2400..5900).step_by(5).chain([2484]) {
+            if let Some(ch) = WifiScanner::freq_to_channel(freq) {
+                assert_eq!(WifiScanner::channel_to_freq(ch), Some(freq));
+            }
+        }
+        assert_eq!(WifiScanner::channel_to_freq(6), Some(2437));
+        assert_eq!(WifiScanner::channel_to_freq(15), None);
+        assert_eq!(WifiScanner::channel_to_freq(37), None);
+        assert_eq!(WifiScanner::channel_to_freq(0), None);
+        assert_eq!(WifiScanner::channel_to_freq(u32::MAX), None);
+    }
+
+    #[test]
+    fn diff_scans_reports_appeared_gone_and_changed() {
+        let steady = network(1, 2412, -60);
+        let fading = network(6, 2437, -50);
+        let gone = network(11, 2462, -70);
+        let old = vec![steady.clone(), fading.clone(), gone.clone()];
+
+        let mut jitter = steady.clone();
+        jitter.signal_strength = -63;
+        let mut faded = fading.clone();
+        faded.signal_strength = -75;
+        let fresh = network(36, 5180, -55);
+        let new = vec![jitter, faded, fresh.clone()];
+
+        let diff = diff_scans(&old, &new);
+        let bssids = |nets: &[WifiNetwork]| nets.iter().map(|n| n.bssid.clone()).collect::<Vec<_>>();
+        assert_eq!(bssids(&diff.appeared), [fresh.bssid]);
+        assert_eq!(bssids(&diff.disappeared), [gone.bssid]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!((diff.changed[0].network.bssid.as_str(), diff.changed[0].old_signal, diff.changed[0].delta), (fading.bssid.as_str(), -50, -25));
+    }
+
+    #[test]
+    fn signal_level_uses_thresholds() {
+        let default = SignalThresholds::default();
+        assert_eq!(signal_level(-45, &default), SignalLevel::Good);
+        assert_eq!(signal_level(-50, &default), SignalLevel::Fair);
+        assert_eq!(signal_level(-70, &default), SignalLevel::Weak);
+
+        let lenient = SignalThresholds { good: -60, fair: -80 };
+        assert_eq!(signal_level(-55, &lenient), SignalLevel::Good);
+        assert_eq!(signal_level(-75, &lenient), SignalLevel::Fair);
+    }
+
+    #[test]
+    fn dfs_channel_ranges() {
+        for ch in [52, 56, 60, 64, 100, 116, 132, 144] {
+            assert!(is_dfs_channel(ch), "channel {} should be DFS", ch);
+        }
+        for ch in [1, 6, 11, 36, 40, 44, 48, 149, 153, 157, 161, 165] {
+            assert!(!is_dfs_channel(ch), "channel {} should not be DFS", ch);
+        }
+    }
+
+    #[test]
+    fn strongest_keeps_top_n_by_signal() {
+        let networks = vec![network(1, 2412, -80), network(6, 2437, -40), network(11, 2462, -60)];
+        let top: Vec<u32> = strongest(&networks, 2).iter().map(|n| n.channel).collect();
+        assert_eq!(top, [6, 11]);
+        assert_eq!(strongest(&networks, 10).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn busy_scan_falls_back_to_scan_dump() {
+        use crate::runner::mock::MockRunner;
+
+        let runner = MockRunner::new().stdout_for("scan dump", include_str!("../tests/fixtures/open.txt"));
+        let busy = anyhow!("Scan failed: command failed: Device or resource busy (-16)");
+        let mut scanner = WifiScanner::new("wlan0");
+        let networks = scanner.recover_busy_with(&runner, busy, Duration::ZERO).await.unwrap();
+        assert_eq!(networks[0].ssid, "CoffeeShop");
+        assert_eq!(runner.calls(), ["iw dev wlan0 scan dump"]);
+
+        // Any other failure is passed through untouched
+        let runner = MockRunner::new();
+        let down = anyhow!("Scan failed: command failed: Network is down (-100)");
+        let err = scanner.recover_busy_with(&runner, down, Duration::ZERO).await.unwrap_err();
+        assert!(err.to_string().contains("Network is down"));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn nmcli_scan_parses_terse_list() {
+        use crate::runner::mock::MockRunner;
+
+        let list = "AA\\:BB\\:CC\\:DD\\:EE\\:01:HomeNet:6:2437 MHz:90:WPA2\n\
+                    AA\\:BB\\:CC\\:DD\\:EE\\:02::36:5180 MHz:40:WPA2 802.1X\n\
+                    AA\\:BB\\:CC\\:DD\\:EE\\:03:Cafe\\: Free:11:2462 MHz:60:\n";
+        let runner = MockRunner::new().stdout_for("wifi list", list);
+        let mut scanner = WifiScanner::new("wlan0");
+        let networks = scanner.scan_nmcli_with(&runner).await.unwrap();
+
+        assert_eq!(runner.calls()[0], "nmcli device wifi rescan ifname wlan0");
+        let summary: Vec<(&str, &str, u32, i32, SecurityType)> = networks
+            .iter()
+            .map(|n| (n.ssid.as_str(), n.bssid.as_str(), n.channel, n.signal_strength, n.security))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("HomeNet", "aa:bb:cc:dd:ee:01", 6, -55, SecurityType::WPA2),
+                ("Cafe: Free", "aa:bb:cc:dd:ee:03", 11, -70, SecurityType::Open),
+                (HIDDEN_SSID, "aa:bb:cc:dd:ee:02", 36, -80, SecurityType::WPA2Enterprise),
+            ]
+        );
+        assert!(networks[2].hidden);
+    }
+
+    #[test]
+    fn bssid_case_and_separators_share_one_cache_entry() {
+        assert_eq!(normalize_bssid(" AA-BB-CC-DD-EE-0F "), "aa:bb:cc:dd:ee:0f");
+        assert_eq!(normalize_bssid("aabbccddee0f"), "aa:bb:cc:dd:ee:0f");
+
+        let mut scanner = WifiScanner::new("wlan0");
+        scanner
+            .parse_scan_results("BSS AA:BB:CC:DD:EE:0F(on wlan0)\n\tfreq: 2437\n\tsignal: -50.00 dBm\n\tSSID: HomeNet\n")
+            .unwrap();
+        scanner.parse_nmcli_list("AA\\:BB\\:CC\\:DD\\:EE\\:0F:HomeNet:6:2437 MHz:90:WPA2\n");
+        scanner
+            .parse_scan_results("BSS aa:bb:cc:dd:ee:0f(on wlan0)\n\tfreq: 2437\n\tsignal: -48.00 dBm\n\tSSID: HomeNet\n")
+            .unwrap();
+
+        assert_eq!(scanner.networks.len(), 1);
+        assert_eq!(scanner.get_network("AA:BB:CC:DD:EE:0F").map(|n| n.signal_strength), Some(-48));
+    }
+
+    #[test]
+    fn parses_country_element() {
+        let net = parse_one(include_str!("../tests/fixtures/country.txt"));
+        assert_eq!(net.country_code.as_deref(), Some("DE"));
+        assert_eq!(net.channel, 100);
+        assert_eq!(net.security, SecurityType::WPA2);
+
+        // Absent element leaves it unset
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_psk.txt"));
+        assert_eq!(net.country_code, None);
+    }
+
+    #[test]
+    fn parses_bss_load_element() {
+        let net = parse_one(include_str!("../tests/fixtures/bss_load.txt"));
+        assert_eq!(net.station_count, Some(23));
+        assert_eq!(net.channel_utilization, Some(60));
+        assert_eq!(net.security, SecurityType::WPA2Enterprise);
+
+        // Most APs don't send one
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_psk.txt"));
+        assert_eq!((net.station_count, net.channel_utilization), (None, None));
+    }
+
+    #[test]
+    fn quality_score_prefers_strong_modern_networks() {
+        let mut strong = network(36, 5180, -45);
+        strong.security = SecurityType::WPA3;
+        let mut weak_open = network(1, 2412, -85);
+        weak_open.security = SecurityType::Open;
+        assert_eq!(strong.quality_score(), 97);
+        assert_eq!(weak_open.quality_score(), 23);
+
+        // A decent WPA2 AP beats a louder open one
+        let mut loud_open = network(6, 2437, -50);
+        loud_open.security = SecurityType::Open;
+        let wpa2 = network(11, 2462, -60);
+        assert!(wpa2.quality_score() > loud_open.quality_score());
+
+        let mut networks = vec![weak_open, wpa2, strong];
+        sort_networks(&mut networks, SortKey::Quality, false);
+        assert_eq!(networks.iter().map(|n| n.channel).collect::<Vec<_>>(), [36, 11, 1]);
+    }
+
+    #[test]
+    fn bg_only_aps_are_flagged_legacy() {
+        let bg = parse_one(include_str!("../tests/fixtures/open.txt"));
+        assert!(bg.is_legacy);
+        assert_eq!(bg.max_rate_mbps, Some(54));
+
+        // Same b/g rates, plus HT
+        let n = parse_one(include_str!("../tests/fixtures/ht_24ghz.txt"));
+        assert!(!n.is_legacy);
+        assert_eq!(n.max_rate_mbps, Some(65));
+
+        assert!(!parse_one(include_str!("../tests/fixtures/5ghz.txt")).is_legacy);
+    }
+
+    #[test]
+    fn reported_utilization_steers_channel_recommendation() {
+        // Equal signal everywhere; without BSS Load the first channel would win
+        let mut networks = vec![network(1, 2412, -60), network(6, 2437, -60), network(11, 2462, -60)];
+        networks[0].channel_utilization = Some(80);
+        networks[1].channel_utilization = Some(45);
+        assert_eq!(recommend_channel(&networks, Band::TwoPointFour), Some(11));
+        assert_eq!(reported_utilization(&networks, Band::TwoPointFour, 6), 45);
+    }
+
+    #[test]
+    fn psc_channel_set() {
+        let psc: Vec<u32> = (1..=233).filter(|ch| is_psc_channel(*ch)).collect();
+        assert_eq!(psc, [5, 21, 37, 53, 69, 85, 101, 117, 133, 149, 165, 181, 197, 213, 229]);
+    }
+
+    #[test]
+    fn six_ghz_networks_are_flagged_psc() {
+        // 6 GHz beacons carry no DS parameter set
+        let six = include_str!("../tests/fixtures/open.txt")
+            .replace("freq: 2412", "freq: 6035")
+            .replace("DS Parameter set: channel 1", "");
+        let net = parse_one(&six);
+        assert_eq!((net.channel, net.psc), (17, false));
+
+        let net = parse_one(&six.replace("freq: 6035", "freq: 6055"));
+        assert_eq!((net.channel, net.psc), (21, true));
+
+        // Same channel number on 5 GHz is not a PSC
+        let net = parse_one(include_str!("../tests/fixtures/5ghz.txt"));
+        assert!(!net.psc);
+    }
+
+    #[test]
+    fn recommend_avoids_busy_2ghz_channels() {
+        let networks = [network(1, 2412, -40), network(6, 2437, -45), network(11, 2462, -85)];
+        assert_eq!(recommend_channel(&networks, Band::TwoPointFour), Some(11));
+    }
+
+    #[test]
+    fn discovered_lists_bssids_new_since_the_first_scan() {
+        let mut scanner = WifiScanner::new("wlan0");
+        scanner.begin_scan();
+        scanner.parse_scan_results(include_str!("../tests/fixtures/open.txt")).unwrap();
+        assert!(scanner.discovered().is_empty(), "first scan is the baseline");
+
+        scanner.begin_scan();
+        let found = scanner.parse_scan_results(include_str!("../tests/fixtures/5ghz.txt")).unwrap();
+        assert_eq!(scanner.discovered(), [found[0].bssid.clone()]);
+
+        scanner.begin_scan();
+        scanner.parse_scan_results(include_str!("../tests/fixtures/5ghz.txt")).unwrap();
+        assert!(scanner.discovered().is_empty());
+    }
+
+    #[test]
+    fn alert_debounce_swallows_bursts() {
+        let mut debounce = AlertDebounce::new(Duration::from_secs(10));
+        let start = std::time::Instant::now();
+        assert!(debounce.ready(start));
+        assert!(!debounce.ready(start + Duration::from_secs(3)));
+        assert!(debounce.ready(start + Duration::from_secs(11)));
+    }
+
+    #[test]
+    fn sort_keys_order_networks_with_signal_as_tiebreak() {
+        let mut networks = vec![network(11, 2462, -70), network(1, 2412, -40), network(36, 5180, -55), network(1, 2412, -60)];
+        networks[0].ssid = "cafe".to_string();
+        networks[1].ssid = "Bakery".to_string();
+        networks[2].security = SecurityType::Open;
+
+        let channels = |nets: &[WifiNetwork]| nets.iter().map(|n| (n.channel, n.signal_strength)).collect::<Vec<_>>();
+        sort_networks(&mut networks, SortKey::Channel, false);
+        assert_eq!(channels(&networks), [(1, -40), (1, -60), (11, -70), (36, -55)]);
+        sort_networks(&mut networks, SortKey::Signal, false);
+        assert_eq!(networks[0].signal_strength, -40);
+        sort_networks(&mut networks, SortKey::Signal, true);
+        assert_eq!(networks[0].signal_strength, -70);
+        sort_networks(&mut networks, SortKey::Security, false);
+        assert_eq!(networks[0].security, SecurityType::Open);
+        sort_networks(&mut networks, SortKey::Ssid, false);
+        let names: Vec<&str> = networks.iter().map(|n| n.ssid.as_str()).collect();
+        assert_eq!(names, ["Bakery", "cafe", "ch1", "ch36"]);
+    }
+
+    #[test]
+    fn scan_interval_is_clamped_and_shared_with_handles() {
+        let mut continuous = ContinuousScanner::new("wlan0", 5);
+        let handle = continuous.interval();
+        assert_eq!(handle.get(), Duration::from_secs(5));
+
+        assert_eq!(continuous.set_interval(Duration::from_millis(200)), MIN_SCAN_INTERVAL);
+        assert_eq!(handle.get(), MIN_SCAN_INTERVAL);
+        handle.set(Duration::from_secs(8));
+        assert_eq!(continuous.interval().get(), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn wide_channels_occupy_their_whole_block() {
+        let net = parse_one(include_str!("../tests/fixtures/5ghz.txt"));
+        assert_eq!((net.channel_width_mhz, net.center_channel), (Some(80), Some(42)));
+        assert_eq!(net.occupied_channels(), (36, 48));
+
+        // 802.11g only: a single 20 MHz channel
+        let net = parse_one(include_str!("../tests/fixtures/wpa2_psk.txt"));
+        assert_eq!(net.occupied_channels(), (net.channel, net.channel));
+    }
+
+    #[test]
+    fn wide_ap_shadows_every_channel_it_covers() {
+        let mut wide = network(36, 5180, -50);
+        wide.channel_width_mhz = Some(80);
+        wide.center_channel = Some(42);
+        let networks = [wide, network(149, 5745, -90)];
+
+        for ch in [36, 40, 44, 48] {
+            assert_eq!(channel_load(&networks, Band::Five, ch), 100, "channel {}", ch);
+        }
+        assert_eq!(channel_load(&networks, Band::Five, 52), 0);
+
+        // Treated as 20 MHz, 40-48 would look idle and win over the weak AP on 149
+        let pick = recommend_channel(&networks, Band::Five).unwrap();
+        assert!(!(36..=48).contains(&pick));
+    }
+
+    #[test]
+    fn recommend_prefers_non_dfs_on_similar_load() {
+        // Every non-DFS channel carries one weak AP; DFS channels are empty
+        let networks: Vec<WifiNetwork> = [36, 40, 44, 48, 149, 153, 157, 161, 165]
+            .iter()
+            .map(|&ch| network(ch, 5000 + ch * 5, -90))
+            .collect();
+        let pick = recommend_channel(&networks, Band::Five).unwrap();
+        assert!(!is_dfs_channel(pick));
+
+        // A strong AP on every non-DFS channel makes a DFS channel worth it
+        let networks: Vec<WifiNetwork> = [36, 40, 44, 48, 149, 153, 157, 161, 165]
+            .iter()
+            .map(|&ch| network(ch, 5000 + ch * 5, -50))
+            .collect();
+        let pick = recommend_channel(&networks, Band::Five).unwrap();
+        assert!(is_dfs_channel(pick));
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+
+/// `$XDG_DATA_HOME/sozin`, falling back to `~/.local/share/sozin`
+fn data_dir_from(xdg_data_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+    match xdg_data_home.filter(|d| !d.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir).join("sozin")),
+        None => home.map(|h| PathBuf::from(h).join(".local/share/sozin")),
+    }
+}
+
+/// Where sozin keeps state that should survive between runs
+pub fn data_dir() -> Option<PathBuf> {
+    data_dir_from(std::env::var("XDG_DATA_HOME").ok(), std::env::var("HOME").ok())
+}
+
+/// `$XDG_CONFIG_HOME/sozin`, falling back to `~/.config/sozin`
+fn config_dir_from(xdg_config_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+    match xdg_config_home.filter(|d| !d.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir).join("sozin")),
+        None => home.map(|h| PathBuf::from(h).join(".config/sozin")),
+    }
+}
+
+/// Where sozin reads user settings such as `keys.toml`
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(std::env::var("XDG_CONFIG_HOME").ok(), std::env::var("HOME").ok())
+}
+
+/// Network names seen per BSSID across runs, kept in `known-networks.json` in the data dir
+///
+/// Only used to put a probable name on hidden networks; a missing or unreadable file
+/// just means nothing is remembered yet.
+#[derive(Debug, Default)]
+pub struct ScanStore {
+    path: Option<PathBuf>,
+    names: BTreeMap<String, String>,
+    dirty: bool,
+}
+
+impl ScanStore {
+    /// Load the store from the data dir
+    pub fn open() -> Self {
+        match data_dir() {
+            Some(dir) => Self::open_at(dir.join("known-networks.json")),
+            None => Self::default(),
+        }
+    }
+
+    fn open_at(path: PathBuf) -> Self {
+        let names = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            names,
+            dirty: false,
+        }
+    }
+
+    /// Last name broadcast by `bssid`, if it was ever seen unhidden
+    pub fn name_for(&self, bssid: &str) -> Option<&str> {
+        self.names.get(bssid).map(String::as_str)
+    }
+
+    /// Record the name an AP is broadcasting
+    pub fn remember(&mut self, bssid: &str, ssid: &str) {
+        if self.name_for(bssid) != Some(ssid) {
+            self.names.insert(bssid.to_string(), ssid.to_string());
+            self.dirty = true;
+        }
+    }
+
+    /// Write the store back if anything new was remembered
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = self.path.as_ref().filter(|_| self.dirty) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.names)?)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Interfaces pinned to the top of the TUI list, from `favorites.json` in the data dir
+pub fn load_favorites() -> HashSet<String> {
+    data_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join("favorites.json")).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Replace the saved favorites with `favorites`
+pub fn save_favorites(favorites: &HashSet<String>) -> Result<()> {
+    let dir = data_dir().ok_or_else(|| anyhow!("No data directory: neither XDG_DATA_HOME nor HOME is set"))?;
+    std::fs::create_dir_all(&dir).map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join("favorites.json");
+    // Sorted so the file doesn't churn between saves
+    let sorted: BTreeSet<&String> = favorites.iter().collect();
+    std::fs::write(&path, serde_json::to_string_pretty(&sorted)?)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+/// User notes per MAC address, from `notes.toml` in the config dir
+///
+/// Keyed by MAC so a note stays with the adapter when it is renamed or plugged in elsewhere.
+///
+/// A missing or unreadable file shows no notes.
+pub fn load_notes() -> BTreeMap<String, String> {
+    config_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join("notes.toml")).ok())
+        .and_then(|contents| parse_notes(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// `"aa:bb:cc:dd:ee:ff" = "note"` lines, with MACs lowercased
+fn parse_notes(contents: &str) -> Result<BTreeMap<String, String>> {
+    let notes: BTreeMap<String, String> = toml::from_str(contents).map_err(|e| anyhow!("{}", e.message()))?;
+    Ok(notes.into_iter().map(|(mac, note)| (mac.to_lowercase(), note)).collect())
+}
+
+/// Set the note for `mac`, or remove it when `note` is blank
+pub fn save_note(mac: &str, note: &str) -> Result<()> {
+    let dir = config_dir().ok_or_else(|| anyhow!("No config directory: neither XDG_CONFIG_HOME nor HOME is set"))?;
+    std::fs::create_dir_all(&dir).map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+    save_note_at(&dir.join("notes.toml"), mac, note)
+}
+
+/// `save_note` on the file at `path`, which is left alone if it exists but doesn't parse
+fn save_note_at(path: &std::path::Path, mac: &str, note: &str) -> Result<()> {
+    let mut notes = match std::fs::read_to_string(path) {
+        Ok(contents) => parse_notes(&contents)
+            .map_err(|e| anyhow!("Not saving, fix {} first: {}", path.display(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(anyhow!("Failed to read {}: {}", path.display(), e)),
+    };
+    match note.trim() {
+        "" => notes.remove(&mac.to_lowercase()),
+        note => notes.insert(mac.to_lowercase(), note.to_string()),
+    };
+    std::fs::write(path, toml::to_string(&notes)?).map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_dir_prefers_xdg_data_home() {
+        let dir = |xdg: Option<&str>, home: Option<&str>| data_dir_from(xdg.map(String::from), home.map(String::from));
+        assert_eq!(dir(Some("/data"), Some("/root")), Some(PathBuf::from("/data/sozin")));
+        assert_eq!(dir(Some(""), Some("/root")), Some(PathBuf::from("/root/.local/share/sozin")));
+        assert_eq!(dir(None, None), None);
+    }
+
+    #[test]
+    fn config_dir_prefers_xdg_config_home() {
+        let dir = |xdg: Option<&str>, home: Option<&str>| config_dir_from(xdg.map(String::from), home.map(String::from));
+        assert_eq!(dir(Some("/cfg"), Some("/root")), Some(PathBuf::from("/cfg/sozin")));
+        assert_eq!(dir(None, Some("/root")), Some(PathBuf::from("/root/.config/sozin")));
+    }
+
+    #[test]
+    fn notes_are_keyed_by_lowercase_mac() {
+        let notes = parse_notes("\"AA:BB:CC:DD:EE:01\" = \"left USB port\"\n").unwrap();
+        assert_eq!(notes.get("aa:bb:cc:dd:ee:01").map(String::as_str), Some("left USB port"));
+        assert!(parse_notes("not toml [").is_err());
+    }
+
+    #[test]
+    fn saving_a_note_never_clobbers_a_broken_file() {
+        let path = std::env::temp_dir().join(format!("sozin-notes-{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        save_note_at(&path, "AA:BB:CC:DD:EE:01", "left port").unwrap();
+        save_note_at(&path, "aa:bb:cc:dd:ee:02", "dongle").unwrap();
+        save_note_at(&path, "aa:bb:cc:dd:ee:02", " ").unwrap();
+        assert_eq!(parse_notes(&std::fs::read_to_string(&path).unwrap()).unwrap().len(), 1);
+
+        let broken = "\"aa:bb:cc:dd:ee:01\" = \"left port\n";
+        std::fs::write(&path, broken).unwrap();
+        assert!(save_note_at(&path, "aa:bb:cc:dd:ee:02", "dongle").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn remembered_names_survive_a_reload() {
+        let path = std::env::temp_dir().join(format!("sozin-store-{}.json", std::process::id()));
+        let mut store = ScanStore::open_at(path.clone());
+        store.remember("aa:bb:cc:dd:ee:01", "HomeNet");
+        store.save().unwrap();
+
+        let reloaded = ScanStore::open_at(path.clone());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.name_for("aa:bb:cc:dd:ee:01"), Some("HomeNet"));
+        assert_eq!(reloaded.name_for("aa:bb:cc:dd:ee:02"), None);
+    }
+}
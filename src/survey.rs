@@ -0,0 +1,217 @@
+use crate::scanner::{self, Band, SecuritySummary, SecurityType, WifiNetwork};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Site survey produced by `sozin survey`: what was heard and what it means
+///
+/// Built only from its inputs, so the same scan, time and tool versions always
+/// give the same report.
+#[derive(Debug, Serialize)]
+pub struct SurveyReport {
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub sozin_version: &'static str,
+    /// First line of each tool's version output, `None` when not installed
+    pub tools: BTreeMap<String, Option<String>>,
+    pub interface: String,
+    /// Scans merged into `networks`, keeping the strongest reading per BSSID
+    pub sweeps: u32,
+    pub security: SecuritySummary,
+    pub bands: Vec<BandSurvey>,
+    pub duplicate_ssids: Vec<DuplicateSsid>,
+    /// Sorted by BSSID
+    pub networks: Vec<WifiNetwork>,
+}
+
+/// Networks heard in one band and its least congested channel
+#[derive(Debug, Serialize)]
+pub struct BandSurvey {
+    pub band: Band,
+    pub networks: usize,
+    /// `None` on 6 GHz, where sozin doesn't recommend channels
+    pub recommended_channel: Option<u32>,
+    pub recommended_dfs: bool,
+}
+
+/// An SSID broadcast by more than one BSSID
+///
+/// Usually a multi-AP network, but when the APs disagree on security it may be
+/// an evil twin.
+#[derive(Debug, Serialize)]
+pub struct DuplicateSsid {
+    pub ssid: String,
+    pub bssids: Vec<String>,
+    pub mixed_security: bool,
+}
+
+impl SurveyReport {
+    /// Summarize `networks` heard on `interface` over `sweeps` scans
+    pub fn build(
+        interface: &str,
+        sweeps: u32,
+        networks: Vec<WifiNetwork>,
+        generated_at: chrono::DateTime<chrono::Utc>,
+        tools: Vec<(String, Option<String>)>,
+    ) -> Self {
+        let mut networks = scanner::merge_strongest(networks);
+        networks.sort_by(|a, b| a.bssid.cmp(&b.bssid));
+
+        let bands = scanner::band_counts(&networks)
+            .into_iter()
+            .map(|(band, count)| {
+                let recommended_channel = scanner::recommend_channel(&networks, band);
+                BandSurvey {
+                    band,
+                    networks: count,
+                    recommended_dfs: recommended_channel.is_some_and(scanner::is_dfs_channel),
+                    recommended_channel,
+                }
+            })
+            .collect();
+
+        Self {
+            generated_at,
+            sozin_version: env!("CARGO_PKG_VERSION"),
+            tools: tools.into_iter().collect(),
+            interface: interface.to_string(),
+            sweeps,
+            security: scanner::security_summary(&networks),
+            bands,
+            duplicate_ssids: duplicate_ssids(&networks),
+            networks,
+        }
+    }
+
+    /// The report as a Markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        // Writing to a String can't fail
+        let _ = writeln!(md, "# WiFi survey on {}\n", self.interface);
+        let _ = writeln!(
+            md,
+            "Generated {} by sozin {} from {} sweep{}.\n",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.sozin_version,
+            self.sweeps,
+            if self.sweeps == 1 { "" } else { "s" }
+        );
+
+        let _ = writeln!(md, "## Security\n\n{}.\n", self.security);
+        let _ = writeln!(md, "| Open | WEP | TKIP | WPA | WPA2/WPA3 | Unknown |");
+        let _ = writeln!(md, "|------|-----|------|-----|-----------|---------|");
+        let s = &self.security;
+        let _ = writeln!(md, "| {} | {} | {} | {} | {} | {} |\n", s.open, s.wep, s.tkip, s.wpa, s.modern, s.unknown);
+
+        let _ = writeln!(md, "## Bands\n");
+        let _ = writeln!(md, "| Band | Networks | Least congested channel |");
+        let _ = writeln!(md, "|------|----------|-------------------------|");
+        for band in &self.bands {
+            let channel = match band.recommended_channel {
+                Some(ch) if band.recommended_dfs => format!("{} (DFS)", ch),
+                Some(ch) => ch.to_string(),
+                None => "-".to_string(),
+            };
+            let _ = writeln!(md, "| {} | {} | {} |", band.band, band.networks, channel);
+        }
+
+        let _ = writeln!(md, "\n## Duplicate SSIDs\n");
+        if self.duplicate_ssids.is_empty() {
+            let _ = writeln!(md, "None.");
+        }
+        for dup in &self.duplicate_ssids {
+            let warning = if dup.mixed_security { " ⚠ mixed security, possible evil twin" } else { "" };
+            let _ = writeln!(md, "- **{}**: {}{}", dup.ssid, dup.bssids.join(", "), warning);
+        }
+
+        let _ = writeln!(md, "\n## Networks\n");
+        let _ = writeln!(md, "| SSID | BSSID | Channel | Signal | Security |");
+        let _ = writeln!(md, "|------|-------|---------|--------|----------|");
+        for n in &self.networks {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} dBm | {} |",
+                n.display_ssid().replace('|', "\\|"),
+                n.bssid,
+                n.channel,
+                n.signal_strength,
+                n.security
+            );
+        }
+
+        let _ = writeln!(md, "\n## Tools\n");
+        for (tool, version) in &self.tools {
+            let _ = writeln!(md, "- {}: {}", tool, version.as_deref().unwrap_or("not installed"));
+        }
+        md
+    }
+}
+
+/// SSIDs heard from several BSSIDs, by name; hidden networks are left out
+fn duplicate_ssids(networks: &[WifiNetwork]) -> Vec<DuplicateSsid> {
+    let mut by_ssid: BTreeMap<&str, Vec<&WifiNetwork>> = BTreeMap::new();
+    for network in networks.iter().filter(|n| !n.hidden && !n.ssid.is_empty()) {
+        by_ssid.entry(&network.ssid).or_default().push(network);
+    }
+    by_ssid
+        .into_iter()
+        .filter(|(_, aps)| aps.len() > 1)
+        .map(|(ssid, aps)| {
+            let mut bssids: Vec<String> = aps.iter().map(|n| n.bssid.clone()).collect();
+            bssids.sort();
+            let first: (SecurityType, bool) = (aps[0].security, aps[0].tkip_only);
+            DuplicateSsid {
+                ssid: ssid.to_string(),
+                bssids,
+                mixed_security: aps.iter().any(|n| (n.security, n.tkip_only) != first),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn networks() -> Vec<WifiNetwork> {
+        let ap = |ssid: &str, bssid: &str, channel: u32, frequency: u32, signal: i32, security: &str| {
+            serde_json::json!({
+                "ssid": ssid, "bssid": bssid, "channel": channel, "frequency": frequency,
+                "signal_strength": signal, "security": security, "mode": "Infrastructure",
+                "last_seen": "2025-12-07T10:00:00Z",
+            })
+        };
+        serde_json::from_value(serde_json::json!([
+            ap("Cafe", "00:00:00:00:00:02", 6, 2437, -60, "WPA2"),
+            ap("Cafe", "00:00:00:00:00:01", 1, 2412, -50, "Open"),
+            ap("Office", "00:00:00:00:00:03", 36, 5180, -70, "WPA3"),
+            ap("Office", "00:00:00:00:00:04", 149, 5745, -65, "WPA3"),
+            // The same AP heard again in a later sweep
+            ap("Office", "00:00:00:00:00:04", 149, 5745, -58, "WPA3"),
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn survey_flags_twins_and_is_reproducible() {
+        let at = chrono::Utc.with_ymd_and_hms(2025, 12, 7, 10, 0, 0).unwrap();
+        let tools = vec![("iw".to_string(), Some("iw version 6.9".to_string())), ("nmcli".to_string(), None)];
+        let report = SurveyReport::build("wlan0", 2, networks(), at, tools.clone());
+
+        assert_eq!(report.networks.len(), 4);
+        assert_eq!(report.networks[0].bssid, "00:00:00:00:00:01");
+        assert_eq!(report.networks[3].signal_strength, -58);
+        assert_eq!((report.security.open, report.security.modern), (1, 3));
+        assert_eq!(report.bands.iter().map(|b| b.networks).collect::<Vec<_>>(), [2, 2]);
+
+        assert_eq!(report.duplicate_ssids.len(), 2);
+        assert_eq!(report.duplicate_ssids[0].ssid, "Cafe");
+        assert!(report.duplicate_ssids[0].mixed_security);
+        assert!(!report.duplicate_ssids[1].mixed_security);
+
+        let again = SurveyReport::build("wlan0", 2, networks().into_iter().rev().collect(), at, tools);
+        assert_eq!(serde_json::to_string(&report).unwrap(), serde_json::to_string(&again).unwrap());
+        assert_eq!(report.to_markdown(), again.to_markdown());
+        assert!(report.to_markdown().contains("- **Cafe**: 00:00:00:00:00:01, 00:00:00:00:00:02 ⚠"));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::scanner::{signal_level, SignalLevel, SignalThresholds};
+use clap::ValueEnum;
+use ratatui::style::Color;
+
+/// Named TUI palettes selectable with `--theme` or `SOZIN_THEME`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ThemeName {
+    #[default]
+    Default,
+    Light,
+    HighContrast,
+    Colorblind,
+}
+
+/// Colors used by the TUI, grouped by role rather than hue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Titles, borders and key hints
+    pub accent: Color,
+    /// Regular values
+    pub text: Color,
+    /// Labels and secondary text
+    pub muted: Color,
+    /// Section headings in popups
+    pub heading: Color,
+    /// Wireless mode and other highlighted values
+    pub emphasis: Color,
+    /// Healthy state: interface up, strong signal
+    pub good: Color,
+    /// Degraded state: unknown, fair signal, caveats
+    pub warn: Color,
+    /// Failure state: interface down, weak signal, errors
+    pub bad: Color,
+    /// Background of the selected row
+    pub selection_bg: Color,
+    /// Text drawn on top of a `bad` background badge
+    pub badge_fg: Color,
+}
+
+impl Theme {
+    pub fn from_name(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self {
+                accent: Color::Cyan,
+                text: Color::White,
+                muted: Color::DarkGray,
+                heading: Color::Yellow,
+                emphasis: Color::Magenta,
+                good: Color::Green,
+                warn: Color::Yellow,
+                bad: Color::Red,
+                selection_bg: Color::DarkGray,
+                badge_fg: Color::Black,
+            },
+            ThemeName::Light => Self {
+                accent: Color::Blue,
+                text: Color::Black,
+                muted: Color::Gray,
+                heading: Color::Magenta,
+                emphasis: Color::Magenta,
+                good: Color::Green,
+                warn: Color::Rgb(176, 110, 0),
+                bad: Color::Red,
+                selection_bg: Color::Rgb(210, 210, 210),
+                badge_fg: Color::White,
+            },
+            ThemeName::HighContrast => Self {
+                accent: Color::LightYellow,
+                text: Color::White,
+                muted: Color::Gray,
+                heading: Color::LightCyan,
+                emphasis: Color::LightMagenta,
+                good: Color::LightGreen,
+                warn: Color::LightYellow,
+                bad: Color::LightRed,
+                selection_bg: Color::Blue,
+                badge_fg: Color::Black,
+            },
+            // Okabe-Ito palette: distinguishable with the common forms of colour blindness
+            ThemeName::Colorblind => Self {
+                accent: Color::Rgb(86, 180, 233),
+                text: Color::White,
+                muted: Color::DarkGray,
+                heading: Color::Rgb(240, 228, 66),
+                emphasis: Color::Rgb(204, 121, 167),
+                good: Color::Rgb(0, 114, 178),
+                warn: Color::Rgb(240, 228, 66),
+                bad: Color::Rgb(213, 94, 0),
+                selection_bg: Color::DarkGray,
+                badge_fg: Color::Black,
+            },
+        }
+    }
+
+    /// Color for a signal level in dBm
+    pub fn signal_color(&self, signal_dbm: i32, thresholds: &SignalThresholds) -> Color {
+        match signal_level(signal_dbm, thresholds) {
+            SignalLevel::Good => self.good,
+            SignalLevel::Fair => self.warn,
+            SignalLevel::Weak => self.bad,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from_name(ThemeName::Default)
+    }
+}
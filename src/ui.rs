@@ -1,20 +1,118 @@
-use crate::network::{InterfaceState, InterfaceType, NetworkInterface, NetworkManager, WirelessMode};
-use crate::scanner::{signal_to_bars, WifiNetwork, WifiScanner};
+use crate::capture::CaptureSession;
+use crate::network::{
+    ChannelActivity, ChannelWidth, InterfaceState, InterfaceStats, InterfaceType, MacPersistence, ManagementBackend,
+    NetworkInterface, NetworkManager, ResetStep, SavedConnection, WirelessMode, type_icon,
+};
+use crate::scanner::{
+    AlertDebounce, Band, CHANNELS_5GHZ, ContinuousScanner, HIDDEN_SSID, SPECTRUM_CHANNELS_2GHZ, ScanInterval,
+    ScanMethod, SecurityType, SignalThresholds, WifiNetwork, WifiScanner, channel_ap_count, is_dfs_channel,
+    is_insecure, monitor_signal, security_summary, signal_to_bars,
+};
+use crate::keys::{Action, KeyBindings};
+use crate::store::ScanStore;
+use crate::theme::Theme;
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Cell, Clear, LineGauge, List, ListItem, ListState, Paragraph, Row, Sparkline, Table, TableState, Tabs},
     Frame, Terminal,
 };
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::io;
+use std::time::{Duration, Instant};
+
+/// `selected` moved by `delta` within `0..count`, clamped rather than wrapped
+fn clamped_step(selected: Option<usize>, count: usize, delta: isize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    let current = selected.unwrap_or(0) as isize;
+    Some(current.saturating_add(delta).clamp(0, count as isize - 1) as usize)
+}
+
+/// Rows of a `count`-long list on the page holding `selected`, or all of them without a limit
+fn page_range(selected: Option<usize>, count: usize, limit: Option<usize>) -> std::ops::Range<usize> {
+    match limit {
+        Some(limit) if limit > 0 => {
+            let start = selected.unwrap_or(0) / limit * limit;
+            start.min(count)..(start + limit).min(count)
+        }
+        _ => 0..count,
+    }
+}
+
+/// Where the selection lands after a list is reloaded: on the same key if it is still there,
+/// nowhere if it vanished, and on the first row if nothing was selected
+fn reselect(previous: Option<&str>, keys: &[&str]) -> Option<usize> {
+    match previous {
+        Some(key) => keys.iter().position(|k| *k == key),
+        None if keys.is_empty() => None,
+        None => Some(0),
+    }
+}
+
+/// Move favorite interfaces to the top; the rest, and the favorites among themselves, keep their order
+fn favorites_first(interfaces: &mut [NetworkInterface], favorites: &HashSet<String>) {
+    interfaces.sort_by_key(|i| !favorites.contains(&i.name));
+}
+
+/// Case-insensitive substring match used by the TUI search
+fn matches_query(text: &str, query: &str) -> bool {
+    text.to_lowercase().contains(&query.to_lowercase())
+}
+
+/// Tab titles, in the order the number keys select them
+const TAB_TITLES: [&str; 4] = ["Interfaces", "Networks", "Info", "Spectrum"];
+
+/// Below this many columns the Interfaces tab stacks the list above the details
+const NARROW_WIDTH: u16 = 100;
+
+/// Seconds an info or success toast stays on screen
+const TOAST_SECS: u64 = 4;
+/// Errors stay up longer so they aren't missed during a burst of actions
+const ERROR_TOAST_SECS: u64 = 10;
+/// Toasts shown at once; the oldest is dropped first
+const MAX_TOASTS: usize = 4;
+
+/// Seconds between interface refreshes when auto-refresh is on
+const AUTO_REFRESH_SECS: u64 = 3;
+
+/// Milliseconds between counter samples for the Details pane throughput gauges
+const THROUGHPUT_SAMPLE_MS: u64 = 1000;
+
+/// Samples kept per watched network (two minutes at the default interval)
+const WATCH_HISTORY_LEN: usize = 60;
+/// Seconds between re-scans while a network is being watched
+const WATCH_INTERVAL_SECS: u64 = 2;
+/// Frames read per sweep when a monitor interface watches a network
+const WATCH_FRAME_SAMPLES: usize = 5;
+/// Seconds between sweeps when the live scan starts
+const LIVE_SCAN_SECS: u64 = 5;
+/// Longest pause `+` can set between live sweeps
+const LIVE_SCAN_MAX_SECS: u64 = 60;
+/// A live-scanned AP missing from sweeps for this long is shown dimmed
+const LIVE_STALE_SECS: i64 = 30;
+/// ...and dropped from the list after this long
+const LIVE_FORGET_SECS: i64 = 300;
+
+/// What this session changed on one interface, undone on quit unless `--no-restore-on-exit`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionChange {
+    /// Put in monitor mode with `m` and not switched back
+    pub monitor: bool,
+    /// MAC spoofed with `M`
+    pub mac_spoofed: bool,
+    /// Address before the first spoof, if it was known
+    pub original_mac: Option<String>,
+}
 
 /// Application state
 pub struct App {
@@ -24,24 +122,328 @@ pub struct App {
     pub interface_state: ListState,
     pub networks: Vec<WifiNetwork>,
     pub network_state: TableState,
-    pub status_message: String,
+    pub scanner: Option<WifiScanner>,
+    pub security_filter: Option<SecurityType>,
+    pub toasts: Vec<Toast>,
     pub show_help: bool,
+    pub show_network_details: bool,
     pub input_mode: InputMode,
     pub input_buffer: String,
+    pub is_root: bool,
+    pub theme: Theme,
+    pub thresholds: SignalThresholds,
+    pub watch: Option<SignalWatch>,
+    pub auto_refresh: bool,
+    pub last_refresh: Instant,
+    /// Rows that fit in each list, recorded at draw time for PageUp/PageDown
+    pub interface_page: std::cell::Cell<usize>,
+    pub network_page: std::cell::Cell<usize>,
+    /// Networks shown per page, strongest first; `None` shows them all
+    pub network_limit: Option<usize>,
+    /// Interface and SSID waiting on a passphrase before connecting
+    pub connect_target: Option<(String, String)>,
+    /// External tool versions for the Info tab, probed once at startup
+    pub tool_versions: Vec<(String, Option<String>)>,
+    pub capture: Option<CaptureSession>,
+    /// Kernel link/address change notifications (with the `netlink` feature)
+    pub link_events: Option<std::sync::mpsc::Receiver<()>>,
+    /// Saved WiFi profiles, `Some` while the profiles popup is open
+    pub saved_connections: Option<Vec<SavedConnection>>,
+    pub connection_state: ListState,
+    /// Destructive action waiting on a yes/no answer in `InputMode::Confirm`
+    pub confirm: Option<ConfirmAction>,
+    /// Active search text; matches are highlighted, nothing is hidden
+    pub search_query: Option<String>,
+    /// Rows in the current tab's list that match `search_query`
+    pub search_matches: Vec<usize>,
+    /// Position in `search_matches` that `n`/`N` last jumped to
+    pub search_cursor: usize,
+    /// Set while new-network alerts are on
+    pub new_network_alert: Option<AlertDebounce>,
+    /// Why the last interface refresh failed, shown in place of the list
+    pub interfaces_error: Option<String>,
+    /// Wireless interfaces found in monitor mode at the last refresh, flagged in the header
+    pub monitor_interfaces: Vec<String>,
+    /// Service configuring each interface at the last refresh, shown in the Details pane
+    pub management: HashMap<String, ManagementBackend>,
+    /// Latest per-channel frame counts from a monitor-mode sample, for the Spectrum tab
+    pub activity: Vec<ChannelActivity>,
+    /// Background actions still running; pressing their key again is refused until they finish
+    pub busy: HashSet<BusyAction>,
+    /// Throughput of the selected interface for the Details pane gauges
+    pub throughput: Option<Throughput>,
+    /// Full scale of the throughput gauges in bytes per second; `None` scales to the peak seen
+    pub gauge_max: Option<u64>,
+    /// Remember network names across runs to label hidden networks (`--remember-networks`)
+    pub remember_networks: bool,
+    /// Interfaces pinned to the top of the list with `*`, saved between runs
+    pub favorites: HashSet<String>,
+    /// ASCII interface icons instead of emoji (`--ascii`)
+    pub ascii: bool,
+    /// Keys for the rebindable actions, from `keys.toml`
+    pub keys: KeyBindings,
+    /// Interfaces left in monitor mode or with a spoofed MAC by this session
+    pub session_changes: BTreeMap<String, SessionChange>,
+    /// Offer to undo `session_changes` on quit; off with `--no-restore-on-exit`
+    pub restore_on_exit: bool,
+    /// Outcome of each restore, printed once the terminal is back to normal
+    pub exit_report: Vec<String>,
+    completion_tx: std::sync::mpsc::Sender<Completion>,
+    completion_rx: std::sync::mpsc::Receiver<Completion>,
+    /// Interface and channels to sample once the "sampling" toast has been drawn
+    pub activity_request: Option<(String, Vec<u32>)>,
+    /// Background sweep loop started with `S`, feeding the Networks tab until stopped
+    pub live_scan: Option<LiveScan>,
+}
+
+/// A `ContinuousScanner` running on its own task, and the sweeps it has sent back
+pub struct LiveScan {
+    pub interface: String,
+    /// Pause between sweeps, changed with `+`/`-` while the scan runs
+    pub interval: ScanInterval,
+    task: tokio::task::JoinHandle<()>,
+    sweeps: std::sync::mpsc::Receiver<Result<Vec<WifiNetwork>, String>>,
+}
+
+/// Fold a live sweep into the networks heard so far
+///
+/// Fresh readings replace older ones by BSSID. APs missing from the sweep stay,
+/// below the fresh ones, until `LIVE_FORGET_SECS` after they were last heard.
+fn merge_live(mut networks: Vec<WifiNetwork>, sweep: Vec<WifiNetwork>, now: chrono::DateTime<chrono::Utc>) -> Vec<WifiNetwork> {
+    let fresh: HashSet<&str> = sweep.iter().map(|n| n.bssid.as_str()).collect();
+    networks.retain(|n| !fresh.contains(n.bssid.as_str()) && (now - n.last_seen).num_seconds() < LIVE_FORGET_SECS);
+    networks.extend(sweep);
+    networks.sort_by_key(|n| (is_stale(n, now), std::cmp::Reverse(n.signal_strength)));
+    networks
+}
+
+/// Not heard by the live scan for `LIVE_STALE_SECS`
+fn is_stale(network: &WifiNetwork, now: chrono::DateTime<chrono::Utc>) -> bool {
+    (now - network.last_seen).num_seconds() >= LIVE_STALE_SECS
+}
+
+/// Actions that ask before running
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfirmAction {
+    /// Delete the named NetworkManager profile
+    DeleteConnection(String),
+    /// Down, managed mode, flush addresses and up on the named interface
+    ResetInterface(String),
+    /// Unload and reload the named interface's driver
+    ReloadDriver(String),
+    /// Quit, first undoing this many interfaces' session changes
+    RestoreOnExit(usize),
+}
+
+impl ConfirmAction {
+    fn prompt(&self) -> String {
+        match self {
+            ConfirmAction::DeleteConnection(name) => format!("Delete saved network \"{}\"?", name),
+            ConfirmAction::ResetInterface(name) => format!("Reset {} (down, managed mode, flush addresses, up)?", name),
+            ConfirmAction::ReloadDriver(name) => {
+                format!("Reload the driver of {}? Every interface using it drops for a moment", name)
+            }
+            ConfirmAction::RestoreOnExit(count) => format!(
+                "Restore {} modified interface{} (managed mode, original MAC) before quitting?",
+                count,
+                if *count == 1 { "" } else { "s" }
+            ),
+        }
+    }
+
+    fn hint(&self) -> &'static str {
+        match self {
+            ConfirmAction::RestoreOnExit(_) => "y/Enter to restore and quit, n to quit as is, any other key to stay",
+            _ => "y/Enter to confirm, any other key to cancel",
+        }
+    }
+}
+
+/// Slow actions that run in the background, at most one of each kind at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusyAction {
+    Monitor,
+    Scan,
+    Mac,
+    Driver,
+}
+
+impl BusyAction {
+    fn label(self) -> &'static str {
+        match self {
+            BusyAction::Monitor => "Monitor mode change",
+            BusyAction::Scan => "Scan",
+            BusyAction::Mac => "MAC change",
+            BusyAction::Driver => "Driver reload",
+        }
+    }
+}
+
+/// Outcome of a background action, handed back to the event loop
+pub enum Completion {
+    Monitor { interface: String, enabled: bool, result: Result<()> },
+    /// The scanner is moved into the task and returned with its cache updated
+    Scan { scanner: WifiScanner, result: Result<(Vec<WifiNetwork>, ScanMethod)> },
+    /// `previous` is the address before the change, for restoring on quit
+    Mac { interface: String, mac: String, previous: Option<String>, result: Result<MacPersistence> },
+    /// `result` holds the interface's name after the reload
+    Driver { interface: String, result: Result<String> },
+}
+
+impl Completion {
+    fn action(&self) -> BusyAction {
+        match self {
+            Completion::Monitor { .. } => BusyAction::Monitor,
+            Completion::Scan { .. } => BusyAction::Scan,
+            Completion::Mac { .. } => BusyAction::Mac,
+            Completion::Driver { .. } => BusyAction::Driver,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Error,
+}
+
+/// A short-lived message shown above the status bar
+pub struct Toast {
+    pub text: String,
+    pub level: ToastLevel,
+    pub expires_at: Instant,
+}
+
+/// Signal history of a single access point, tracked by BSSID across sweeps
+pub struct SignalWatch {
+    pub bssid: String,
+    pub ssid: String,
+    pub samples: VecDeque<(chrono::DateTime<chrono::Utc>, i32)>,
+    /// Whether the AP showed up in the most recent sweep
+    pub seen: bool,
+    /// Samples come from radiotap headers on a monitor interface rather than `iw scan`
+    pub per_frame: bool,
+    last_sweep: Instant,
+}
+
+impl SignalWatch {
+    pub fn new(net: &WifiNetwork) -> Self {
+        let mut watch = Self {
+            bssid: net.bssid.clone(),
+            ssid: net.ssid.clone(),
+            samples: VecDeque::with_capacity(WATCH_HISTORY_LEN),
+            seen: true,
+            per_frame: false,
+            last_sweep: Instant::now(),
+        };
+        watch.push(net.signal_strength);
+        watch
+    }
+
+    /// Record a sample, dropping the oldest once the buffer is full
+    pub fn push(&mut self, signal_dbm: i32) {
+        if self.samples.len() == WATCH_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((chrono::Utc::now(), signal_dbm));
+    }
+
+    fn is_due(&self) -> bool {
+        self.last_sweep.elapsed() >= Duration::from_secs(WATCH_INTERVAL_SECS)
+    }
+}
+
+/// RX/TX rate of the selected interface, worked out from sysfs counter deltas
+pub struct Throughput {
+    pub interface: String,
+    last: Option<(Instant, InterfaceStats)>,
+    /// Bytes per second over the last sample interval
+    pub rx_rate: u64,
+    pub tx_rate: u64,
+    /// Highest rate seen in either direction, the gauge scale when no max was given
+    pub peak: u64,
+}
+
+impl Throughput {
+    pub fn new(interface: &str) -> Self {
+        Self {
+            interface: interface.to_string(),
+            last: None,
+            rx_rate: 0,
+            tx_rate: 0,
+            peak: 0,
+        }
+    }
+
+    /// Record a counter reading; rates stay at zero until there are two to compare
+    ///
+    /// Counters that went backwards (driver reload, interface re-created) count as no traffic.
+    pub fn record(&mut self, at: Instant, stats: Option<InterfaceStats>) {
+        let Some(stats) = stats else {
+            self.rx_rate = 0;
+            self.tx_rate = 0;
+            return;
+        };
+        if let Some((then, prev)) = self.last {
+            let millis = at.duration_since(then).as_millis().max(1) as u64;
+            self.rx_rate = stats.rx_bytes.saturating_sub(prev.rx_bytes) * 1000 / millis;
+            self.tx_rate = stats.tx_bytes.saturating_sub(prev.tx_bytes) * 1000 / millis;
+            self.peak = self.peak.max(self.rx_rate).max(self.tx_rate);
+        }
+        self.last = Some((at, stats));
+    }
+
+    fn is_due(&self) -> bool {
+        self.last
+            .is_none_or(|(then, _)| then.elapsed() >= Duration::from_millis(THROUGHPUT_SAMPLE_MS))
+    }
+
+    /// Share of `max` (bytes per second) that `rate` fills, 0.0 when there is no scale yet
+    pub fn ratio(rate: u64, max: u64) -> f64 {
+        if max == 0 {
+            0.0
+        } else {
+            (rate as f64 / max as f64).min(1.0)
+        }
+    }
+}
+
+/// Bytes per second as a bit rate with a readable unit
+fn format_bitrate(bytes_per_sec: u64) -> String {
+    let bits = bytes_per_sec as f64 * 8.0;
+    if bits >= 1e9 {
+        format!("{:.1} Gbit/s", bits / 1e9)
+    } else if bits >= 1e6 {
+        format!("{:.1} Mbit/s", bits / 1e6)
+    } else if bits >= 1e3 {
+        format!("{:.1} kbit/s", bits / 1e3)
+    } else {
+        format!("{} bit/s", bits as u64)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
     Normal,
     Rename,
+    /// Passphrase for the network in `App::connect_target`
+    Password,
+    /// Yes/no answer for `App::confirm`
+    Confirm,
+    /// Search text, applied as it is typed
+    Search,
+    /// Note for the selected interface's adapter
+    Note,
     #[allow(dead_code)]
     MacInput,
-    #[allow(dead_code)]
+    /// Channel and optional width for the selected interface's radio
     ChannelInput,
 }
 
 impl Default for App {
     fn default() -> Self {
+        let (completion_tx, completion_rx) = std::sync::mpsc::channel();
         Self {
             running: true,
             current_tab: 0,
@@ -49,41 +451,410 @@ impl Default for App {
             interface_state: ListState::default(),
             networks: Vec::new(),
             network_state: TableState::default(),
-            status_message: String::new(),
+            scanner: None,
+            security_filter: None,
+            toasts: Vec::new(),
             show_help: false,
+            show_network_details: false,
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
+            is_root: nix::unistd::Uid::effective().is_root(),
+            theme: Theme::default(),
+            thresholds: SignalThresholds::default(),
+            watch: None,
+            auto_refresh: false,
+            last_refresh: Instant::now(),
+            interface_page: std::cell::Cell::new(1),
+            network_page: std::cell::Cell::new(1),
+            network_limit: None,
+            connect_target: None,
+            tool_versions: Vec::new(),
+            capture: None,
+            link_events: None,
+            saved_connections: None,
+            connection_state: ListState::default(),
+            confirm: None,
+            search_query: None,
+            search_matches: Vec::new(),
+            search_cursor: 0,
+            new_network_alert: None,
+            activity: Vec::new(),
+            activity_request: None,
+            live_scan: None,
+            interfaces_error: None,
+            monitor_interfaces: Vec::new(),
+            management: HashMap::new(),
+            busy: HashSet::new(),
+            throughput: None,
+            gauge_max: None,
+            remember_networks: false,
+            favorites: HashSet::new(),
+            ascii: false,
+            keys: KeyBindings::default(),
+            session_changes: BTreeMap::new(),
+            restore_on_exit: true,
+            exit_report: Vec::new(),
+            completion_tx,
+            completion_rx,
         }
     }
 }
 
 impl App {
     pub fn new() -> Self {
-        let mut app = Self::default();
+        let (keys, key_warnings) = KeyBindings::load();
+        let mut app = Self {
+            favorites: crate::store::load_favorites(),
+            keys,
+            ..Self::default()
+        };
+        for warning in key_warnings {
+            app.push_toast(ToastLevel::Error, warning);
+        }
         app.refresh_interfaces();
+        app.tool_versions = NetworkManager::tool_versions();
+        app.link_events = NetworkManager::watch_link_events();
         app
     }
 
+    /// Queue a message, dropping the oldest if too many are already showing
+    pub fn push_toast(&mut self, level: ToastLevel, text: impl Into<String>) {
+        let secs = if level == ToastLevel::Error { ERROR_TOAST_SECS } else { TOAST_SECS };
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        self.toasts.push(Toast {
+            text: text.into(),
+            level,
+            expires_at: Instant::now() + Duration::from_secs(secs),
+        });
+    }
+
+    /// Drop toasts whose time is up
+    pub fn expire_toasts(&mut self) {
+        let now = Instant::now();
+        self.toasts.retain(|t| t.expires_at > now);
+    }
+
     pub fn refresh_interfaces(&mut self) {
+        self.last_refresh = Instant::now();
         match NetworkManager::get_interfaces() {
             Ok(interfaces) => {
+                // Follow the interface by name; an index could now point at a different adapter
+                let previous = self.selected_interface().map(|i| i.name.clone());
                 self.interfaces = interfaces;
-                if self.interface_state.selected().is_none() && !self.interfaces.is_empty() {
-                    self.interface_state.select(Some(0));
+                favorites_first(&mut self.interfaces, &self.favorites);
+                let names: Vec<&str> = self.interfaces.iter().map(|i| i.name.as_str()).collect();
+                let selected = reselect(previous.as_deref(), &names);
+                self.interface_state.select(selected);
+                self.interfaces_error = None;
+                // Only wireless interfaces have a mode worth asking iw about
+                self.monitor_interfaces = self
+                    .interfaces
+                    .iter()
+                    .filter(|i| i.interface_type == InterfaceType::Wireless)
+                    .filter(|i| NetworkManager::get_wireless_mode(&i.name).ok() == Some(WirelessMode::Monitor))
+                    .map(|i| i.name.clone())
+                    .collect();
+                let names: Vec<&str> = self.interfaces.iter().map(|i| i.name.as_str()).collect();
+                self.management = NetworkManager::get_management_backends(&names);
+                if let (Some(name), None) = (previous, selected) {
+                    self.push_toast(ToastLevel::Error, format!("Interface {} removed", name));
                 }
             }
             Err(e) => {
-                self.status_message = format!("Error: {}", e);
+                self.interfaces_error = Some(e.to_string());
+                self.push_toast(ToastLevel::Error, format!("Error: {}", e));
             }
         }
     }
 
+    /// Pin the selected interface to the top of the list, or unpin it
+    pub fn toggle_favorite(&mut self) {
+        let Some(name) = self.selected_interface().map(|i| i.name.clone()) else {
+            return;
+        };
+        if !self.favorites.remove(&name) {
+            self.favorites.insert(name.clone());
+        }
+        favorites_first(&mut self.interfaces, &self.favorites);
+        let names: Vec<&str> = self.interfaces.iter().map(|i| i.name.as_str()).collect();
+        self.interface_state.select(reselect(Some(&name), &names));
+        if let Err(e) = crate::store::save_favorites(&self.favorites) {
+            self.push_toast(ToastLevel::Error, format!("Couldn't save favorites: {}", e));
+        }
+    }
+
+    /// Mark `action` as running, or say it already is and refuse
+    pub fn try_begin(&mut self, action: BusyAction) -> bool {
+        if self.busy.insert(action) {
+            return true;
+        }
+        self.push_toast(ToastLevel::Info, format!("{} already in progress", action.label()));
+        false
+    }
+
+    /// Run a job claimed with `try_begin` on its own task; its result arrives through `poll_completions`
+    pub fn spawn_busy<F>(&mut self, job: F)
+    where
+        F: std::future::Future<Output = Completion> + Send + 'static,
+    {
+        let tx = self.completion_tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(job.await);
+        });
+    }
+
+    /// Apply the results of background actions that have finished, releasing their busy slot
+    pub fn poll_completions(&mut self) {
+        while let Ok(completion) = self.completion_rx.try_recv() {
+            self.busy.remove(&completion.action());
+            match completion {
+                Completion::Monitor { interface, enabled, result: Ok(()) } => {
+                    self.note_monitor(&interface, enabled);
+                    self.push_toast(
+                        ToastLevel::Success,
+                        format!("Monitor mode {} on {}", if enabled { "enabled" } else { "disabled" }, interface),
+                    );
+                    self.refresh_interfaces();
+                }
+                Completion::Scan { mut scanner, result } => {
+                    let truncated = scanner.last_scan_truncated();
+                    if let Err(e) = scanner.save_store() {
+                        self.push_toast(ToastLevel::Error, format!("Couldn't save known networks: {}", e));
+                    }
+                    self.scanner = Some(scanner);
+                    match result {
+                        Ok((networks, method)) => self.finish_scan(networks, method, truncated),
+                        Err(e) => self.push_toast(ToastLevel::Error, format!("Scan error: {}", e)),
+                    }
+                }
+                Completion::Mac { interface, mac, previous, result: Ok(_) } => {
+                    self.note_mac_spoof(&interface, previous);
+                    self.push_toast(ToastLevel::Success, format!("MAC on {} changed to {}", interface, mac));
+                    self.refresh_interfaces();
+                }
+                Completion::Driver { interface, result: Ok(name) } => {
+                    let text = if name == interface {
+                        format!("Driver reloaded; {} is back", name)
+                    } else {
+                        format!("Driver reloaded; {} is now {}", interface, name)
+                    };
+                    self.push_toast(ToastLevel::Success, text);
+                    self.refresh_interfaces();
+                }
+                Completion::Monitor { result: Err(e), .. }
+                | Completion::Mac { result: Err(e), .. }
+                | Completion::Driver { result: Err(e), .. } => {
+                    self.push_toast(ToastLevel::Error, format!("Error: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Record a monitor mode change made from the TUI
+    fn note_monitor(&mut self, interface: &str, enabled: bool) {
+        let change = self.session_changes.entry(interface.to_string()).or_default();
+        change.monitor = enabled;
+        if !change.monitor && !change.mac_spoofed {
+            self.session_changes.remove(interface);
+        }
+    }
+
+    /// Record a MAC spoof, keeping the address from before the first one
+    fn note_mac_spoof(&mut self, interface: &str, previous: Option<String>) {
+        let change = self.session_changes.entry(interface.to_string()).or_default();
+        if !change.mac_spoofed {
+            change.mac_spoofed = true;
+            change.original_mac = previous;
+        }
+    }
+
+    /// Refuse `action` with a toast if it needs root and sozin doesn't have it
+    pub fn refuse_unprivileged(&mut self, action: Action) -> bool {
+        if self.is_root || !action.requires_root() {
+            return false;
+        }
+        self.push_toast(ToastLevel::Error, "Requires root: restart sozin with sudo to change interfaces");
+        true
+    }
+
+    /// Stop the loop, first asking whether to undo this session's interface changes
+    pub fn request_quit(&mut self) {
+        if self.restore_on_exit && !self.session_changes.is_empty() {
+            self.confirm = Some(ConfirmAction::RestoreOnExit(self.session_changes.len()));
+            self.input_mode = InputMode::Confirm;
+        } else {
+            self.running = false;
+        }
+    }
+
+    /// Put every interface changed this session back in managed mode with its old MAC
+    async fn restore_session_changes(&mut self) {
+        for (name, change) in std::mem::take(&mut self.session_changes) {
+            if change.monitor {
+                self.exit_report.push(match NetworkManager::disable_monitor_mode(&name, false).await {
+                    Ok(()) => format!("Restored managed mode on {}", name),
+                    Err(e) => format!("Couldn't restore managed mode on {}: {}", name, e),
+                });
+            }
+            if change.mac_spoofed {
+                let restored = match &change.original_mac {
+                    Some(mac) => NetworkManager::spoof_mac(&name, mac, false).await.map(|_| ()),
+                    None => NetworkManager::restore_mac(&name).await.map(|_| ()),
+                };
+                self.exit_report.push(match restored {
+                    Ok(()) => format!("Restored the original MAC on {}", name),
+                    Err(e) => format!("Couldn't restore the MAC on {}: {}", name, e),
+                });
+            }
+        }
+    }
+
+    /// Start the live scan on the selected wireless interface, or stop the running one
+    pub fn toggle_live_scan(&mut self) {
+        if let Some(live) = self.live_scan.take() {
+            live.task.abort();
+            self.push_toast(ToastLevel::Info, format!("Live scan on {} stopped", live.interface));
+            return;
+        }
+        let Some(interface) = self
+            .selected_interface()
+            .filter(|i| i.interface_type == InterfaceType::Wireless)
+            .map(|i| i.name.clone())
+        else {
+            self.push_toast(ToastLevel::Error, "Select a wireless interface first");
+            return;
+        };
+
+        let (tx, sweeps) = std::sync::mpsc::channel();
+        let mut continuous = ContinuousScanner::new(&interface, LIVE_SCAN_SECS);
+        let interval = continuous.interval();
+        let task = tokio::spawn(async move {
+            // Stops by itself once the receiver is dropped
+            let _ = continuous
+                .run(|sweep, _| tx.send(sweep.map_err(|e| e.to_string())).map_err(|_| anyhow::anyhow!("Live scan stopped")))
+                .await;
+        });
+        self.push_toast(
+            ToastLevel::Info,
+            format!("Live scan on {} every {}s; {} stops it", interface, LIVE_SCAN_SECS, self.keys.label(Action::LiveScan)),
+        );
+        self.live_scan = Some(LiveScan { interface, interval, task, sweeps });
+        self.current_tab = 1;
+    }
+
+    /// Lengthen or shorten the pause between live sweeps by a second, within 1-60s
+    pub fn adjust_live_scan(&mut self, slower: bool) {
+        let Some(live) = self.live_scan.as_ref() else {
+            let key = self.keys.label(Action::LiveScan);
+            self.push_toast(ToastLevel::Error, format!("No live scan running; {} starts one", key));
+            return;
+        };
+        let secs = live.interval.get().as_secs();
+        let secs = if slower { secs + 1 } else { secs.saturating_sub(1) };
+        let used = live.interval.set(Duration::from_secs(secs.min(LIVE_SCAN_MAX_SECS)));
+        self.push_toast(ToastLevel::Info, format!("Live scan every {}s", used.as_secs()));
+    }
+
+    /// Merge the sweeps the live scan has finished since the last frame
+    pub fn poll_live_scan(&mut self) {
+        let Some(live) = self.live_scan.as_ref() else {
+            return;
+        };
+        let sweeps: Vec<_> = live.sweeps.try_iter().collect();
+        for sweep in sweeps {
+            match sweep {
+                Ok(found) => {
+                    let networks = merge_live(self.networks.clone(), found, chrono::Utc::now());
+                    self.replace_networks(networks);
+                }
+                Err(e) => self.push_toast(ToastLevel::Error, format!("Live scan: {}", e)),
+            }
+        }
+    }
+
+    /// Show a finished scan and switch to the Networks tab
+    fn finish_scan(&mut self, networks: Vec<WifiNetwork>, method: ScanMethod, truncated: bool) {
+        self.networks = networks;
+        let visible = self.visible_networks().len();
+        self.network_state.select(if visible == 0 { None } else { Some(0) });
+        let summary = security_summary(&self.networks);
+        self.push_toast(
+            ToastLevel::Success,
+            match method {
+                ScanMethod::Iw => format!("Found {} networks", self.networks.len()),
+                ScanMethod::NetworkManager => {
+                    format!("Found {} networks (via NetworkManager, connection kept)", self.networks.len())
+                }
+            },
+        );
+        self.push_toast(ToastLevel::Info, summary.to_string());
+        self.announce_discoveries();
+        if truncated {
+            self.push_toast(ToastLevel::Error, "Scan timed out; showing partial results");
+        }
+        self.current_tab = 1; // Switch to networks tab
+    }
+
+    /// Refresh interfaces if auto-refresh is on and the interval has passed, or the kernel reported a change
+    ///
+    /// Skipped while the user is typing so a rename target can't shift underneath them;
+    /// pending link events wait until input is finished.
+    pub fn auto_refresh_tick(&mut self) {
+        if self.input_mode != InputMode::Normal {
+            return;
+        }
+        // Drain the whole burst: plugging in an adapter emits several messages
+        let link_changed = self.link_events.as_ref().is_some_and(|rx| rx.try_iter().count() > 0);
+        if link_changed
+            || (self.auto_refresh && self.last_refresh.elapsed() >= Duration::from_secs(AUTO_REFRESH_SECS))
+        {
+            self.refresh_interfaces();
+        }
+    }
+
+    /// Sample counters of the selected interface for the throughput gauges
+    ///
+    /// Selecting another interface starts over, so its gauges don't inherit the old peak.
+    pub fn sample_throughput(&mut self) {
+        let Some(name) = self.selected_interface().map(|i| i.name.clone()) else {
+            self.throughput = None;
+            return;
+        };
+        let throughput = match &mut self.throughput {
+            Some(t) if t.interface == name => t,
+            slot => slot.insert(Throughput::new(&name)),
+        };
+        if throughput.is_due() {
+            throughput.record(Instant::now(), NetworkManager::get_interface_stats(&name));
+        }
+    }
+
     pub fn selected_interface(&self) -> Option<&NetworkInterface> {
         self.interface_state
             .selected()
             .and_then(|i| self.interfaces.get(i))
     }
 
+    /// Next row of the current tab's list, wrapping at the end
+    pub fn select_next(&mut self) {
+        if self.current_tab == 0 {
+            self.next_interface();
+        } else if self.current_tab == 1 {
+            self.next_network();
+        }
+    }
+
+    /// Previous row of the current tab's list, wrapping at the start
+    pub fn select_previous(&mut self) {
+        if self.current_tab == 0 {
+            self.previous_interface();
+        } else if self.current_tab == 1 {
+            self.previous_network();
+        }
+    }
+
     pub fn next_interface(&mut self) {
         if self.interfaces.is_empty() {
             return;
@@ -118,13 +889,63 @@ impl App {
         self.interface_state.select(Some(i));
     }
 
+    /// Networks that pass the active security filter
+    pub fn visible_networks(&self) -> Vec<&WifiNetwork> {
+        self.networks
+            .iter()
+            .filter(|n| self.security_filter.is_none_or(|s| n.security == s))
+            .collect()
+    }
+
+    pub fn selected_network(&self) -> Option<&WifiNetwork> {
+        self.network_state
+            .selected()
+            .and_then(|i| self.visible_networks().get(i).copied())
+    }
+
+    /// Swap in a new scan result, keeping the selection on the same BSSID if it is still visible
+    pub fn replace_networks(&mut self, networks: Vec<WifiNetwork>) {
+        let previous = self.selected_network().map(|n| n.bssid.clone());
+        self.networks = networks;
+        let visible = self.visible_networks();
+        let bssids: Vec<&str> = visible.iter().map(|n| n.bssid.as_str()).collect();
+        let selected = reselect(previous.as_deref(), &bssids);
+        self.network_state.select(selected);
+    }
+
+    /// Apply a security filter and reset the selection to the first match
+    pub fn set_security_filter(&mut self, filter: Option<SecurityType>) {
+        self.security_filter = filter;
+        let count = self.visible_networks().len();
+        self.network_state.select(if count == 0 { None } else { Some(0) });
+        let text = match filter {
+            Some(s) => format!("Showing {} networks ({} of {})", s, count, self.networks.len()),
+            None => "Showing all networks".to_string(),
+        };
+        self.push_toast(ToastLevel::Info, text);
+    }
+
+    /// Advance the filter: All → Open → WEP → WPA2 → WPA3 → Enterprise → All
+    pub fn cycle_security_filter(&mut self) {
+        let next = match self.security_filter {
+            None => Some(SecurityType::Open),
+            Some(SecurityType::Open) => Some(SecurityType::WEP),
+            Some(SecurityType::WEP) => Some(SecurityType::WPA2),
+            Some(SecurityType::WPA2) => Some(SecurityType::WPA3),
+            Some(SecurityType::WPA3) => Some(SecurityType::WPA2Enterprise),
+            Some(_) => None,
+        };
+        self.set_security_filter(next);
+    }
+
     pub fn next_network(&mut self) {
-        if self.networks.is_empty() {
+        let count = self.visible_networks().len();
+        if count == 0 {
             return;
         }
         let i = match self.network_state.selected() {
             Some(i) => {
-                if i >= self.networks.len() - 1 {
+                if i >= count - 1 {
                     0
                 } else {
                     i + 1
@@ -136,13 +957,14 @@ impl App {
     }
 
     pub fn previous_network(&mut self) {
-        if self.networks.is_empty() {
+        let count = self.visible_networks().len();
+        if count == 0 {
             return;
         }
         let i = match self.network_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.networks.len() - 1
+                    count - 1
                 } else {
                     i - 1
                 }
@@ -152,21 +974,471 @@ impl App {
         self.network_state.select(Some(i));
     }
 
-    pub fn next_tab(&mut self) {
-        self.current_tab = (self.current_tab + 1) % 3;
+    /// Move the selection in the current tab's list by `delta` rows, stopping at either end
+    pub fn move_selection(&mut self, delta: isize) {
+        match self.current_tab {
+            0 => {
+                let target = clamped_step(self.interface_state.selected(), self.interfaces.len(), delta);
+                self.interface_state.select(target);
+            }
+            1 => {
+                let target = clamped_step(self.network_state.selected(), self.visible_networks().len(), delta);
+                self.network_state.select(target);
+            }
+            _ => {}
+        }
     }
 
-    pub fn previous_tab(&mut self) {
-        if self.current_tab == 0 {
-            self.current_tab = 2;
+    /// One screenful of rows in the current tab's list
+    pub fn page_size(&self) -> isize {
+        let rows = match self.current_tab {
+            0 => self.interface_page.get(),
+            _ => self.network_limit.unwrap_or_else(|| self.network_page.get()),
+        };
+        rows.max(1) as isize
+    }
+
+    /// Connect to the selected network, asking for a passphrase first unless it is open
+    pub async fn start_connect(&mut self) {
+        let Some(net) = self.selected_network() else {
+            return;
+        };
+        let (ssid, security) = (net.ssid.clone(), net.security);
+        let Some(interface) = self.scanner.as_ref().map(|s| s.interface().to_string()) else {
+            return;
+        };
+
+        if ssid == HIDDEN_SSID {
+            self.push_toast(ToastLevel::Error, "Can't connect: the network's name is unknown");
+            return;
+        }
+        if security == SecurityType::WPA2Enterprise {
+            self.push_toast(ToastLevel::Error, "Enterprise networks need a NetworkManager profile");
+            return;
+        }
+
+        self.connect_target = Some((interface, ssid.clone()));
+        if security == SecurityType::Open {
+            self.finish_connect(None).await;
         } else {
-            self.current_tab -= 1;
+            self.input_mode = InputMode::Password;
+            self.input_buffer.clear();
+            self.push_toast(ToastLevel::Info, format!("Enter passphrase for {}", ssid));
+        }
+    }
+
+    /// Join `connect_target`, then refresh so the new link shows up
+    pub async fn finish_connect(&mut self, password: Option<&str>) {
+        let Some((interface, ssid)) = self.connect_target.take() else {
+            return;
+        };
+        match NetworkManager::connect(&interface, &ssid, password).await {
+            Ok(()) => {
+                self.push_toast(ToastLevel::Success, format!("Connected to {} on {}", ssid, interface));
+                self.refresh_interfaces();
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+        }
+    }
+
+    /// Disconnect the selected interface through NetworkManager
+    pub async fn disconnect_selected(&mut self) {
+        let Some(name) = self.selected_interface().map(|i| i.name.clone()) else {
+            return;
+        };
+        match NetworkManager::disconnect(&name).await {
+            Ok(()) => {
+                self.push_toast(ToastLevel::Success, format!("Disconnected {}", name));
+                self.refresh_interfaces();
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+        }
+    }
+
+    /// Open the saved WiFi profiles popup
+    pub fn open_saved_connections(&mut self) {
+        match NetworkManager::list_saved_connections() {
+            Ok(connections) => {
+                let wifi: Vec<SavedConnection> =
+                    connections.into_iter().filter(|c| c.kind == "802-11-wireless").collect();
+                self.connection_state.select(if wifi.is_empty() { None } else { Some(0) });
+                self.saved_connections = Some(wifi);
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+        }
+    }
+
+    fn selected_connection(&self) -> Option<&SavedConnection> {
+        let connections = self.saved_connections.as_ref()?;
+        self.connection_state.selected().and_then(|i| connections.get(i))
+    }
+
+    /// Ask before deleting the selected saved profile
+    pub fn request_delete_connection(&mut self) {
+        if let Some(name) = self.selected_connection().map(|c| c.name.clone()) {
+            self.confirm = Some(ConfirmAction::DeleteConnection(name));
+            self.input_mode = InputMode::Confirm;
+        }
+    }
+
+    /// Run the action the user just said yes to
+    pub async fn run_confirmed(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::DeleteConnection(name) => match NetworkManager::delete_connection(&name).await {
+                Ok(()) => {
+                    self.push_toast(ToastLevel::Success, format!("Forgot {}", name));
+                    self.open_saved_connections();
+                }
+                Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+            },
+            ConfirmAction::ReloadDriver(name) => {
+                if self.try_begin(BusyAction::Driver) {
+                    self.push_toast(ToastLevel::Info, format!("Reloading the driver of {}...", name));
+                    self.spawn_busy(async move {
+                        let result = NetworkManager::reload_driver(&name).await;
+                        Completion::Driver { interface: name, result }
+                    });
+                }
+            }
+            ConfirmAction::ResetInterface(name) => {
+                let steps = NetworkManager::reset_interface(&name).await;
+                let failed: Vec<&ResetStep> = steps.iter().filter(|s| s.outcome.is_err()).collect();
+                if failed.is_empty() {
+                    self.push_toast(ToastLevel::Success, format!("{} reset ({} steps)", name, steps.len()));
+                }
+                for step in failed {
+                    let error = step.outcome.as_ref().err().cloned().unwrap_or_default();
+                    self.push_toast(ToastLevel::Error, format!("{}: {} failed: {}", name, step.description, error));
+                }
+                self.refresh_interfaces();
+            }
+            ConfirmAction::RestoreOnExit(_) => {
+                self.restore_session_changes().await;
+                self.running = false;
+            }
+        }
+    }
+
+    /// Start capturing on the selected monitor-mode interface, or stop the running capture
+    pub fn toggle_capture(&mut self) {
+        if let Some(session) = self.capture.take() {
+            let path = session.path.display().to_string();
+            match session.stop() {
+                Ok(summary) => self.push_toast(
+                    ToastLevel::Success,
+                    format!("Saved {} ({} frames, {} bytes)", path, summary.frames, summary.bytes),
+                ),
+                Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+            }
+            return;
+        }
+
+        let Some(name) = self.selected_interface().map(|i| i.name.clone()) else {
+            return;
+        };
+        if NetworkManager::get_wireless_mode(&name).ok() != Some(WirelessMode::Monitor) {
+            self.push_toast(ToastLevel::Error, format!("Put {} in monitor mode first ({})", name, self.keys.label(Action::ToggleMonitor)));
+            return;
+        }
+
+        let path = std::path::PathBuf::from(format!(
+            "sozin-{}-{}.pcap",
+            name,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        match CaptureSession::start(&name, &path, None) {
+            Ok(session) => {
+                self.push_toast(ToastLevel::Info, format!("Capturing {} to {}", name, path.display()));
+                self.capture = Some(session);
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+        }
+    }
+
+    /// Ring the bell and toast when the last scan found BSSIDs new to this session, if alerts are on
+    pub fn announce_discoveries(&mut self) {
+        let (Some(alert), Some(scanner)) = (self.new_network_alert.as_mut(), self.scanner.as_ref()) else {
+            return;
+        };
+        let discovered = scanner.discovered();
+        if discovered.is_empty() || !alert.ready(Instant::now()) {
+            return;
+        }
+        let text = match discovered {
+            [bssid] => {
+                let ssid = self.networks.iter().find(|n| &n.bssid == bssid).map_or(HIDDEN_SSID, |n| &n.ssid);
+                format!("New network: {} ({})", ssid, bssid)
+            }
+            _ => format!("{} new networks", discovered.len()),
+        };
+        use std::io::Write;
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        self.push_toast(ToastLevel::Success, text);
+    }
+
+    /// Queue a channel activity sample on the selected interface, which must be in monitor mode
+    pub fn request_activity_sample(&mut self) {
+        let Some(name) = self.selected_interface().map(|i| i.name.clone()) else {
+            self.push_toast(ToastLevel::Error, "Select a monitor-mode interface on the Interfaces tab first");
+            return;
+        };
+        if NetworkManager::get_wireless_mode(&name).ok() != Some(WirelessMode::Monitor) {
+            self.push_toast(ToastLevel::Error, format!("Put {} in monitor mode first ({})", name, self.keys.label(Action::ToggleMonitor)));
+            return;
+        }
+
+        let supported = NetworkManager::get_supported_channels(&name);
+        let channels: Vec<u32> = SPECTRUM_CHANNELS_2GHZ
+            .iter()
+            .chain(&CHANNELS_5GHZ)
+            .copied()
+            .filter(|ch| supported.is_empty() || supported.contains(ch))
+            .collect();
+        // Each channel costs the dwell plus roughly as long again to start and stop the counter
+        let secs = channels.len() as u64 * 2 * crate::network::ACTIVITY_DWELL_MS / 1000;
+        self.push_toast(
+            ToastLevel::Info,
+            format!("Sampling {} channels on {} (~{}s)...", channels.len(), name, secs),
+        );
+        self.activity_request = Some((name, channels));
+    }
+
+    /// Run a queued activity sample; the UI is frozen while the radio hops
+    pub async fn run_activity_sample(&mut self) {
+        let Some((name, channels)) = self.activity_request.take() else {
+            return;
+        };
+        let dwell = Duration::from_millis(crate::network::ACTIVITY_DWELL_MS);
+        match NetworkManager::sample_channel_activity(&name, &channels, dwell).await {
+            Ok(activity) => {
+                let frames: u64 = activity.iter().map(|a| a.frames).sum();
+                self.push_toast(ToastLevel::Success, format!("Counted {} frames on {}", frames, name));
+                self.activity = activity;
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+        }
+    }
+
+    /// Tune the selected interface to the channel typed as `<channel> [width]`, HT20 by default
+    pub async fn set_channel(&mut self, input: &str) {
+        let Some(name) = self.selected_interface().map(|i| i.name.clone()) else {
+            return;
+        };
+        let mut words = input.split_whitespace();
+        let channel = match words.next().map(str::parse::<u32>) {
+            Some(Ok(channel)) => channel,
+            _ => {
+                self.push_toast(ToastLevel::Error, format!("Not a channel number: {}", input.trim()));
+                return;
+            }
+        };
+        let width = match words.next().map(str::parse::<ChannelWidth>).transpose() {
+            Ok(width) => width,
+            Err(e) => {
+                self.push_toast(ToastLevel::Error, e);
+                return;
+            }
+        };
+        match NetworkManager::set_channel(&name, channel, width).await {
+            Ok(()) => self.push_toast(
+                ToastLevel::Success,
+                format!("{} on channel {} ({})", name, channel, width.unwrap_or_default()),
+            ),
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Error: {}", e)),
+        }
+    }
+
+    /// Open the note editor for the selected interface, filled with its current note
+    pub fn start_note(&mut self) {
+        let Some(iface) = self.selected_interface() else {
+            return;
+        };
+        let name = iface.name.clone();
+        let Some(mac) = iface.hardware_mac().map(str::to_string) else {
+            self.push_toast(ToastLevel::Error, format!("{} has no MAC address to keep a note under", name));
+            return;
+        };
+        self.input_buffer = crate::store::load_notes().remove(&mac.to_lowercase()).unwrap_or_default();
+        self.input_mode = InputMode::Note;
+        self.push_toast(ToastLevel::Info, format!("Note for {} (leave empty to remove it)", name));
+    }
+
+    /// Save the typed note under the selected interface's factory MAC
+    pub fn save_note(&mut self) {
+        let note = std::mem::take(&mut self.input_buffer);
+        let Some((name, Some(mac))) = self.selected_interface().map(|i| (i.name.clone(), i.hardware_mac().map(str::to_string))) else {
+            return;
+        };
+        match crate::store::save_note(&mac, &note) {
+            Ok(()) => {
+                let text = if note.trim().is_empty() {
+                    format!("Note removed from {}", name)
+                } else {
+                    format!("Note saved for {}", name)
+                };
+                self.push_toast(ToastLevel::Success, text);
+                self.refresh_interfaces();
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, format!("Couldn't save note: {}", e)),
+        }
+    }
+
+    /// Start tracking the signal of the selected network
+    pub fn start_watch(&mut self) {
+        if let Some(net) = self.selected_network() {
+            let watch = SignalWatch::new(net);
+            let text = format!("Watching {} ({}), Esc to stop", watch.ssid, watch.bssid);
+            self.watch = Some(watch);
+            self.push_toast(ToastLevel::Info, text);
+        }
+    }
+
+    /// Re-scan and record the watched network's signal if the interval has passed
+    pub async fn sweep_watch(&mut self) {
+        let (Some(watch), Some(scanner)) = (self.watch.as_mut(), self.scanner.as_mut()) else {
+            return;
+        };
+        if !watch.is_due() {
+            return;
+        }
+
+        // A monitor interface can't scan, but with the radiotap feature it can read every beacon's signal
+        if cfg!(feature = "radiotap") && self.monitor_interfaces.iter().any(|m| m == scanner.interface()) {
+            let result = monitor_signal(scanner.interface(), &watch.bssid, WATCH_FRAME_SAMPLES).await;
+            watch.per_frame = true;
+            watch.last_sweep = Instant::now();
+            match result {
+                Ok(readings) => {
+                    watch.seen = true;
+                    readings.into_iter().for_each(|dbm| watch.push(dbm));
+                }
+                Err(e) => {
+                    watch.seen = false;
+                    self.push_toast(ToastLevel::Error, format!("Signal error: {}", e));
+                }
+            }
+            return;
+        }
+
+        let (mut error, mut fresh) = (None, None);
+        match scanner.scan(false).await {
+            Ok(networks) => {
+                let found = networks.iter().find(|n| n.bssid == watch.bssid);
+                watch.seen = found.is_some();
+                if let Some(net) = found {
+                    watch.push(net.signal_strength);
+                    if net.ssid != HIDDEN_SSID {
+                        watch.ssid = net.ssid.clone();
+                    }
+                }
+                fresh = Some(networks);
+            }
+            Err(e) => error = Some(format!("Scan error: {}", e)),
         }
+        watch.last_sweep = Instant::now();
+
+        if let Some(networks) = fresh {
+            self.replace_networks(networks);
+            self.announce_discoveries();
+        }
+        if let Some(error) = error {
+            self.push_toast(ToastLevel::Error, error);
+        }
+    }
+
+    /// Recompute `search_matches` for the current tab; lists and tabs change under an active search
+    pub fn update_search(&mut self) {
+        let Some(query) = self.search_query.as_deref() else {
+            self.search_matches.clear();
+            return;
+        };
+        self.search_matches = match self.current_tab {
+            0 => self
+                .interfaces
+                .iter()
+                .enumerate()
+                .filter(|(_, i)| matches_query(&i.name, query))
+                .map(|(i, _)| i)
+                .collect(),
+            1 => self
+                .visible_networks()
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| matches_query(&n.ssid, query) || matches_query(&n.bssid, query))
+                .map(|(i, _)| i)
+                .collect(),
+            _ => Vec::new(),
+        };
+        if self.search_cursor >= self.search_matches.len() {
+            self.search_cursor = 0;
+        }
+    }
+
+    /// Set the search text and select the first match at or below the current row
+    pub fn set_search(&mut self, query: &str) {
+        self.search_query = Some(query.to_string()).filter(|q| !q.is_empty());
+        self.update_search();
+        let selected = match self.current_tab {
+            0 => self.interface_state.selected(),
+            _ => self.network_state.selected(),
+        }
+        .unwrap_or(0);
+        self.search_cursor = self.search_matches.iter().position(|&i| i >= selected).unwrap_or(0);
+        self.jump_to_match(0);
+    }
+
+    /// Move `delta` matches from the current one, wrapping around, and select it
+    pub fn jump_to_match(&mut self, delta: isize) {
+        let count = self.search_matches.len();
+        if count == 0 {
+            return;
+        }
+        self.search_cursor = (self.search_cursor as isize + delta).rem_euclid(count as isize) as usize;
+        let row = Some(self.search_matches[self.search_cursor]);
+        match self.current_tab {
+            0 => self.interface_state.select(row),
+            1 => self.network_state.select(row),
+            _ => {}
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query = None;
+        self.search_matches.clear();
+        self.search_cursor = 0;
+    }
+
+    // Each tab keeps its own ListState/TableState, so switching tabs never
+    // touches the selection and coming back lands on the same row.
+
+    /// Jump to a tab by index, ignoring indexes past the last tab
+    pub fn select_tab(&mut self, index: usize) {
+        if index < TAB_TITLES.len() {
+            self.current_tab = index;
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.select_tab((self.current_tab + 1) % TAB_TITLES.len());
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.select_tab((self.current_tab + TAB_TITLES.len() - 1) % TAB_TITLES.len());
     }
 }
 
 /// Run the TUI application
-pub async fn run_tui() -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub async fn run_tui(
+    theme: Theme,
+    thresholds: SignalThresholds,
+    network_limit: Option<usize>,
+    gauge_max: Option<u64>,
+    remember_networks: bool,
+    ascii: bool,
+    restore_on_exit: bool,
+) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -176,6 +1448,13 @@ pub async fn run_tui() -> Result<()> {
 
     // Create app state
     let mut app = App::new();
+    app.theme = theme;
+    app.thresholds = thresholds;
+    app.network_limit = network_limit;
+    app.gauge_max = gauge_max;
+    app.remember_networks = remember_networks;
+    app.ascii = ascii;
+    app.restore_on_exit = restore_on_exit;
 
     // Main loop
     let res = run_app(&mut terminal, &mut app).await;
@@ -189,6 +1468,9 @@ pub async fn run_tui() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
+    for line in &app.exit_report {
+        println!("{}", line);
+    }
     if let Err(err) = res {
         eprintln!("Error: {}", err);
     }
@@ -201,177 +1483,313 @@ async fn run_app<B: ratatui::backend::Backend>(
     app: &mut App,
 ) -> Result<()> {
     loop {
+        app.poll_completions();
+        app.poll_live_scan();
+        app.expire_toasts();
+        app.auto_refresh_tick();
+        app.sample_throughput();
+        app.update_search();
         terminal.draw(|f| ui(f, app))?;
+        app.sweep_watch().await;
+        app.run_activity_sample().await;
 
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     match app.input_mode {
-                        InputMode::Normal => match key.code {
-                            KeyCode::Char('q') => {
-                                app.running = false;
-                                return Ok(());
-                            }
-                            KeyCode::Char('?') | KeyCode::F(1) => {
-                                app.show_help = !app.show_help;
+                        InputMode::Confirm => {
+                            let action = app.confirm.take();
+                            app.input_mode = InputMode::Normal;
+                            match (action, key.code) {
+                                (Some(action), KeyCode::Char('y') | KeyCode::Enter) => app.run_confirmed(action).await,
+                                (Some(ConfirmAction::RestoreOnExit(_)), KeyCode::Char('n')) => app.running = false,
+                                _ => {}
                             }
-                            KeyCode::Tab => app.next_tab(),
-                            KeyCode::BackTab => app.previous_tab(),
+                        }
+                        InputMode::Normal if app.saved_connections.is_some() => match key.code {
                             KeyCode::Down | KeyCode::Char('j') => {
-                                if app.current_tab == 0 {
-                                    app.next_interface();
-                                } else if app.current_tab == 1 {
-                                    app.next_network();
-                                }
+                                let count = app.saved_connections.as_ref().map_or(0, Vec::len);
+                                let target = clamped_step(app.connection_state.selected(), count, 1);
+                                app.connection_state.select(target);
                             }
                             KeyCode::Up | KeyCode::Char('k') => {
-                                if app.current_tab == 0 {
-                                    app.previous_interface();
-                                } else if app.current_tab == 1 {
-                                    app.previous_network();
+                                let count = app.saved_connections.as_ref().map_or(0, Vec::len);
+                                let target = clamped_step(app.connection_state.selected(), count, -1);
+                                app.connection_state.select(target);
+                            }
+                            KeyCode::Char('x') | KeyCode::Delete => app.request_delete_connection(),
+                            KeyCode::Esc | KeyCode::Char('C') | KeyCode::Char('q') => {
+                                app.saved_connections = None;
+                            }
+                            _ => {}
+                        },
+                        InputMode::Normal => match key.code {
+                            KeyCode::F(1) => {
+                                app.show_help = !app.show_help;
+                            }
+                            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.input_mode = InputMode::Search;
+                                app.input_buffer = app.search_query.clone().unwrap_or_default();
+                            }
+                            KeyCode::Char('n') if app.search_query.is_some() => app.jump_to_match(1),
+                            KeyCode::Char('N') if app.search_query.is_some() => app.jump_to_match(-1),
+                            KeyCode::Esc => {
+                                app.show_help = false;
+                                app.show_network_details = false;
+                                app.clear_search();
+                                if app.watch.take().is_some() {
+                                    app.push_toast(ToastLevel::Info, "Stopped watching");
                                 }
                             }
-                            KeyCode::Char('r') => {
-                                app.refresh_interfaces();
-                                app.status_message = "Interfaces refreshed".to_string();
+                            KeyCode::Enter if app.current_tab == 1 => {
+                                app.show_network_details =
+                                    !app.show_network_details && app.selected_network().is_some();
                             }
-                            KeyCode::Char('m') => {
-                                // Toggle monitor mode
-                                if let Some(iface) = app.selected_interface() {
-                                    if iface.interface_type == InterfaceType::Wireless {
-                                        let name = iface.name.clone();
-                                        let mode = NetworkManager::get_wireless_mode(&name)
-                                            .unwrap_or(WirelessMode::Unknown);
-                                        
-                                        app.status_message = format!("Toggling monitor mode on {}...", name);
-                                        
-                                        let result = if mode == WirelessMode::Monitor {
-                                            NetworkManager::disable_monitor_mode(&name).await
+                            KeyCode::Tab => app.next_tab(),
+                            KeyCode::Char(c @ '1'..='9') => app.select_tab(c as usize - '1' as usize),
+                            KeyCode::BackTab => app.previous_tab(),
+                            KeyCode::Down => app.select_next(),
+                            KeyCode::Up => app.select_previous(),
+                            KeyCode::PageDown => app.move_selection(app.page_size()),
+                            KeyCode::PageUp => app.move_selection(-app.page_size()),
+                            KeyCode::Home => app.move_selection(isize::MIN),
+                            KeyCode::End => app.move_selection(isize::MAX),
+                            code => match app.keys.action_for(code) {
+                                Some(action) if app.refuse_unprivileged(action) => {}
+                                Some(Action::Quit) => app.request_quit(),
+                                Some(Action::Help) => {
+                                    app.show_help = !app.show_help;
+                                }
+                                Some(Action::MoveDown) => app.select_next(),
+                                Some(Action::MoveUp) => app.select_previous(),
+                                Some(Action::Favorite) if app.current_tab == 0 => app.toggle_favorite(),
+                                Some(Action::AutoRefresh) => {
+                                    app.auto_refresh = !app.auto_refresh;
+                                    app.push_toast(
+                                        ToastLevel::Info,
+                                        if app.auto_refresh {
+                                            format!("Auto-refresh on (every {}s)", AUTO_REFRESH_SECS)
                                         } else {
-                                            NetworkManager::enable_monitor_mode(&name).await
-                                        };
-
-                                        match result {
+                                            "Auto-refresh off".to_string()
+                                        },
+                                    );
+                                }
+                                Some(Action::NewNetworkAlerts) => {
+                                    app.new_network_alert = match app.new_network_alert {
+                                        Some(_) => None,
+                                        None => Some(AlertDebounce::default()),
+                                    };
+                                    app.push_toast(
+                                        ToastLevel::Info,
+                                        if app.new_network_alert.is_some() {
+                                            "New-network alerts on"
+                                        } else {
+                                            "New-network alerts off"
+                                        },
+                                    );
+                                }
+                                Some(Action::Refresh) => {
+                                    app.refresh_interfaces();
+                                    app.push_toast(ToastLevel::Info, "Interfaces refreshed");
+                                }
+                                Some(Action::ToggleMonitor) => {
+                                    // Toggle monitor mode
+                                    if let Some(iface) = app.selected_interface() {
+                                        if iface.interface_type == InterfaceType::Wireless {
+                                            let name = iface.name.clone();
+                                            if app.try_begin(BusyAction::Monitor) {
+                                                let enabled = NetworkManager::get_wireless_mode(&name)
+                                                    .unwrap_or(WirelessMode::Unknown)
+                                                    != WirelessMode::Monitor;
+                                                app.spawn_busy(async move {
+                                                    let result = if enabled {
+                                                        NetworkManager::enable_monitor_mode(&name, false).await
+                                                    } else {
+                                                        NetworkManager::disable_monitor_mode(&name, false).await
+                                                    };
+                                                    Completion::Monitor { interface: name, enabled, result }
+                                                });
+                                            }
+                                        } else {
+                                            app.push_toast(ToastLevel::Error, "Not a wireless interface");
+                                        }
+                                    }
+                                }
+                                Some(Action::InterfaceUp) => {
+                                    // Bring interface up
+                                    if let Some(iface) = app.selected_interface() {
+                                        let name = iface.name.clone();
+                                        match NetworkManager::bring_up(&name).await {
                                             Ok(_) => {
-                                                app.status_message = format!(
-                                                    "Monitor mode {} on {}",
-                                                    if mode == WirelessMode::Monitor { "disabled" } else { "enabled" },
-                                                    name
-                                                );
+                                                app.push_toast(ToastLevel::Success, format!("{} is now UP", name));
                                                 app.refresh_interfaces();
                                             }
                                             Err(e) => {
-                                                app.status_message = format!("Error: {}", e);
+                                                app.push_toast(ToastLevel::Error, format!("Error: {}", e));
                                             }
                                         }
-                                    } else {
-                                        app.status_message = "Not a wireless interface".to_string();
                                     }
                                 }
-                            }
-                            KeyCode::Char('u') => {
-                                // Bring interface up
-                                if let Some(iface) = app.selected_interface() {
-                                    let name = iface.name.clone();
-                                    match NetworkManager::bring_up(&name).await {
-                                        Ok(_) => {
-                                            app.status_message = format!("{} is now UP", name);
-                                            app.refresh_interfaces();
+                                Some(Action::InterfaceDown) => {
+                                    // Bring interface down
+                                    if let Some(iface) = app.selected_interface() {
+                                        let name = iface.name.clone();
+                                        match NetworkManager::bring_down(&name).await {
+                                            Ok(_) => {
+                                                app.push_toast(ToastLevel::Success, format!("{} is now DOWN", name));
+                                                app.refresh_interfaces();
+                                            }
+                                            Err(e) => {
+                                                app.push_toast(ToastLevel::Error, format!("Error: {}", e));
+                                            }
                                         }
-                                        Err(e) => {
-                                            app.status_message = format!("Error: {}", e);
+                                    }
+                                }
+                                Some(Action::Scan) if app.live_scan.is_some() => {
+                                    let key = app.keys.label(Action::LiveScan);
+                                    app.push_toast(ToastLevel::Info, format!("Live scan is running; {} stops it", key));
+                                }
+                                Some(Action::LiveScan) => app.toggle_live_scan(),
+                                Some(Action::LiveScanSlower) => app.adjust_live_scan(true),
+                                Some(Action::LiveScanFaster) => app.adjust_live_scan(false),
+                                Some(Action::Scan) => {
+                                    // Scan for networks
+                                    if let Some(iface) = app.selected_interface() {
+                                        if iface.interface_type == InterfaceType::Wireless {
+                                            let name = iface.name.clone();
+                                            if app.try_begin(BusyAction::Scan) {
+                                                // Keep the scanner across scans so hidden SSIDs can be resolved from its cache
+                                                let mut scanner = match app.scanner.take() {
+                                                    Some(scanner) if scanner.interface() == name => scanner,
+                                                    _ if app.remember_networks => WifiScanner::new(&name).with_store(ScanStore::open()),
+                                                    _ => WifiScanner::new(&name),
+                                                };
+                                                app.push_toast(ToastLevel::Info, format!("Scanning on {}...", name));
+                                                app.spawn_busy(async move {
+                                                    // Associated interfaces scan through NetworkManager so the link stays up
+                                                    let result = scanner.scan_nondisruptive().await;
+                                                    Completion::Scan { scanner, result }
+                                                });
+                                            }
+                                        } else {
+                                            app.push_toast(ToastLevel::Error, "Select a wireless interface first");
                                         }
                                     }
                                 }
-                            }
-                            KeyCode::Char('d') => {
-                                // Bring interface down
-                                if let Some(iface) = app.selected_interface() {
-                                    let name = iface.name.clone();
-                                    match NetworkManager::bring_down(&name).await {
+                                Some(Action::RestartNetworkManager) => {
+                                    // Restart NetworkManager
+                                    match NetworkManager::restart_network_manager().await {
                                         Ok(_) => {
-                                            app.status_message = format!("{} is now DOWN", name);
+                                            app.push_toast(ToastLevel::Success, "NetworkManager restarted");
+                                            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
                                             app.refresh_interfaces();
                                         }
                                         Err(e) => {
-                                            app.status_message = format!("Error: {}", e);
+                                            app.push_toast(ToastLevel::Error, format!("Error: {}", e));
                                         }
                                     }
                                 }
-                            }
-                            KeyCode::Char('s') => {
-                                // Scan for networks
-                                if let Some(iface) = app.selected_interface() {
-                                    if iface.interface_type == InterfaceType::Wireless {
+                                Some(Action::SpoofMac) => {
+                                    // Spoof MAC address
+                                    if let Some(iface) = app.selected_interface() {
                                         let name = iface.name.clone();
-                                        app.status_message = format!("Scanning on {}...", name);
-                                        
-                                        let mut scanner = WifiScanner::new(&name);
-                                        match scanner.scan().await {
-                                            Ok(networks) => {
-                                                app.networks = networks;
-                                                if !app.networks.is_empty() {
-                                                    app.network_state.select(Some(0));
-                                                }
-                                                app.status_message = format!(
-                                                    "Found {} networks",
-                                                    app.networks.len()
-                                                );
-                                                app.current_tab = 1; // Switch to networks tab
-                                            }
-                                            Err(e) => {
-                                                app.status_message = format!("Scan error: {}", e);
-                                            }
+                                        let previous = iface.mac_address.clone();
+                                        if app.try_begin(BusyAction::Mac) {
+                                            let mac = NetworkManager::generate_random_mac();
+                                            app.spawn_busy(async move {
+                                                let result = NetworkManager::spoof_mac(&name, &mac, false).await;
+                                                Completion::Mac { interface: name, mac, previous, result }
+                                            });
                                         }
-                                    } else {
-                                        app.status_message = "Select a wireless interface first".to_string();
                                     }
                                 }
-                            }
-                            KeyCode::Char('n') => {
-                                // Restart NetworkManager
-                                app.status_message = "Restarting NetworkManager...".to_string();
-                                match NetworkManager::restart_network_manager().await {
-                                    Ok(_) => {
-                                        app.status_message = "NetworkManager restarted".to_string();
-                                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                                        app.refresh_interfaces();
+                                Some(Action::ResetInterface) if app.current_tab == 0 => {
+                                    if let Some(name) = app.selected_interface().map(|i| i.name.clone()) {
+                                        app.confirm = Some(ConfirmAction::ResetInterface(name));
+                                        app.input_mode = InputMode::Confirm;
                                     }
-                                    Err(e) => {
-                                        app.status_message = format!("Error: {}", e);
+                                }
+                                Some(Action::ReloadDriver) if app.current_tab == 0 => {
+                                    if let Some(name) = app.selected_interface().map(|i| i.name.clone()) {
+                                        app.confirm = Some(ConfirmAction::ReloadDriver(name));
+                                        app.input_mode = InputMode::Confirm;
                                     }
                                 }
-                            }
-                            KeyCode::Char('M') => {
-                                // Spoof MAC address
-                                if let Some(iface) = app.selected_interface() {
-                                    let name = iface.name.clone();
-                                    let new_mac = NetworkManager::generate_random_mac();
-                                    app.status_message = format!("Spoofing MAC on {} to {}...", name, new_mac);
-
-                                    match NetworkManager::spoof_mac(&name, &new_mac).await {
-                                        Ok(_) => {
-                                            app.status_message = format!("MAC changed to {}", new_mac);
-                                            app.refresh_interfaces();
-                                        }
-                                        Err(e) => {
-                                            app.status_message = format!("Error: {}", e);
-                                        }
+                                Some(Action::Disconnect) if app.current_tab == 0 => {
+                                    app.disconnect_selected().await;
+                                }
+                                Some(Action::SavedNetworks) => app.open_saved_connections(),
+                                Some(Action::Capture) if app.current_tab == 0 || app.capture.is_some() => {
+                                    app.toggle_capture();
+                                }
+                                Some(Action::CycleFilter) if app.current_tab == 1 => {
+                                    app.cycle_security_filter();
+                                }
+                                Some(Action::Watch) if app.current_tab == 1 => {
+                                    app.show_network_details = false;
+                                    app.start_watch();
+                                }
+                                Some(Action::Connect) if app.current_tab == 1 => {
+                                    app.show_network_details = false;
+                                    app.start_connect().await;
+                                }
+                                Some(Action::OpenOnly) if app.current_tab == 1 => {
+                                    app.set_security_filter(Some(SecurityType::Open));
+                                }
+                                Some(Action::SampleActivity) if app.current_tab == 3 => app.request_activity_sample(),
+                                Some(Action::EditNote) => app.start_note(),
+                                Some(Action::SetChannel) => {
+                                    if let Some(name) = app.selected_interface().map(|i| i.name.clone()) {
+                                        app.input_mode = InputMode::ChannelInput;
+                                        app.input_buffer.clear();
+                                        app.push_toast(
+                                            ToastLevel::Info,
+                                            format!("Channel for {}, optionally with a width, e.g. 36 80MHz", name),
+                                        );
                                     }
                                 }
-                            }
-                            KeyCode::Char('R') => {
-                                // Enter rename mode
-                                let iface_name = app.selected_interface().map(|i| i.name.clone());
-                                if let Some(name) = iface_name {
-                                    app.input_mode = InputMode::Rename;
-                                    app.input_buffer = name.clone();
-                                    app.status_message = format!("Enter new name for {} (Press Enter to confirm)", name);
+                                Some(Action::Rename) => {
+                                    // Enter rename mode
+                                    let iface_name = app.selected_interface().map(|i| i.name.clone());
+                                    if let Some(name) = iface_name {
+                                        app.input_mode = InputMode::Rename;
+                                        app.input_buffer = name.clone();
+                                        app.push_toast(ToastLevel::Info, format!("Enter new name for {} (Press Enter to confirm)", name));
+                                    }
+                                }
+                                _ => {}
+                            },
+                        },
+                        InputMode::Search => match key.code {
+                            KeyCode::Enter => {
+                                app.input_mode = InputMode::Normal;
+                                app.input_buffer.clear();
+                                if app.search_query.is_some() && app.search_matches.is_empty() {
+                                    app.push_toast(ToastLevel::Info, "No matches");
                                 }
                             }
+                            KeyCode::Esc => {
+                                app.input_mode = InputMode::Normal;
+                                app.input_buffer.clear();
+                                app.clear_search();
+                            }
+                            KeyCode::Char(c) => {
+                                app.input_buffer.push(c);
+                                let query = app.input_buffer.clone();
+                                app.set_search(&query);
+                            }
+                            KeyCode::Backspace => {
+                                app.input_buffer.pop();
+                                let query = app.input_buffer.clone();
+                                app.set_search(&query);
+                            }
                             _ => {}
                         },
-                        InputMode::Rename | InputMode::MacInput | InputMode::ChannelInput => {
+                        InputMode::Rename
+                        | InputMode::Password
+                        | InputMode::Note
+                        | InputMode::MacInput
+                        | InputMode::ChannelInput => {
                             match key.code {
                                 KeyCode::Enter => {
                                     // Process input
@@ -382,16 +1800,31 @@ async fn run_app<B: ratatui::backend::Backend>(
 
                                             if !new_name.is_empty() && new_name != old_name {
                                                 match NetworkManager::rename_interface(&old_name, &new_name).await {
-                                                    Ok(_) => {
-                                                        app.status_message = format!("Renamed {} to {}", old_name, new_name);
+                                                    Ok(warning) => {
+                                                        app.push_toast(ToastLevel::Success, format!("Renamed {} to {}", old_name, new_name));
+                                                        if let Some(warning) = warning {
+                                                            app.push_toast(ToastLevel::Error, warning);
+                                                        }
+                                                        // Rename the cached row too so the refresh keeps it selected
+                                                        if let Some(iface) = app.interfaces.iter_mut().find(|i| i.name == old_name) {
+                                                            iface.name = new_name.clone();
+                                                        }
                                                         app.refresh_interfaces();
                                                     }
                                                     Err(e) => {
-                                                        app.status_message = format!("Error: {}", e);
+                                                        app.push_toast(ToastLevel::Error, format!("Error: {}", e));
                                                     }
                                                 }
                                             }
                                         }
+                                    } else if app.input_mode == InputMode::Password {
+                                        let password = std::mem::take(&mut app.input_buffer);
+                                        app.finish_connect(Some(&password)).await;
+                                    } else if app.input_mode == InputMode::Note {
+                                        app.save_note();
+                                    } else if app.input_mode == InputMode::ChannelInput {
+                                        let input = std::mem::take(&mut app.input_buffer);
+                                        app.set_channel(&input).await;
                                     }
                                     app.input_mode = InputMode::Normal;
                                     app.input_buffer.clear();
@@ -399,6 +1832,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 KeyCode::Esc => {
                                     app.input_mode = InputMode::Normal;
                                     app.input_buffer.clear();
+                                    app.connect_target = None;
                                 }
                                 KeyCode::Char(c) => {
                                     app.input_buffer.push(c);
@@ -413,39 +1847,60 @@ async fn run_app<B: ratatui::backend::Backend>(
                 }
             }
         }
+
+        if !app.running {
+            if let Some(session) = app.capture.take() {
+                let _ = session.stop();
+            }
+            return Ok(());
+        }
     }
 }
 
 fn ui(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),  // Header
             Constraint::Length(3),  // Tabs
             Constraint::Min(10),    // Main content
+            Constraint::Length(app.toasts.len() as u16), // Toasts
             Constraint::Length(3),  // Status bar
         ])
         .split(f.area());
 
     // Header
-    let header = Paragraph::new(vec![
-        Line::from(vec![
-            Span::styled("  SOZIN ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled("v2.1.0", Style::default().fg(Color::DarkGray)),
-            Span::raw(" │ "),
-            Span::styled("Professional Network Interface Manager", Style::default().fg(Color::White)),
-        ]),
-    ])
-    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    let mut title = vec![
+        Span::styled("  SOZIN ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        Span::styled("v2.1.0", Style::default().fg(theme.muted)),
+        Span::raw(" │ "),
+        Span::styled("Professional Network Interface Manager", Style::default().fg(theme.text)),
+    ];
+    if !app.is_root {
+        title.push(Span::raw(" │ "));
+        title.push(Span::styled(
+            " READ-ONLY (not root) ",
+            Style::default().fg(theme.badge_fg).bg(theme.bad).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if !app.monitor_interfaces.is_empty() {
+        title.push(Span::raw(" │ "));
+        title.push(Span::styled(
+            format!(" MON: {} ", app.monitor_interfaces.join(", ")),
+            Style::default().fg(theme.badge_fg).bg(theme.warn).add_modifier(Modifier::BOLD),
+        ));
+    }
+    let header = Paragraph::new(vec![Line::from(title)])
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(theme.accent)));
     f.render_widget(header, chunks[0]);
 
     // Tabs
-    let tab_titles = vec!["Interfaces", "Networks", "Info"];
-    let tabs = Tabs::new(tab_titles)
+    let tabs = Tabs::new(TAB_TITLES.iter().enumerate().map(|(i, t)| format!("{} {}", i + 1, t)))
         .block(Block::default().borders(Borders::ALL).title(" Navigation "))
         .select(app.current_tab)
-        .style(Style::default().fg(Color::White))
-        .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        .style(Style::default().fg(theme.text))
+        .highlight_style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD));
     f.render_widget(tabs, chunks[1]);
 
     // Main content based on tab
@@ -453,66 +1908,181 @@ fn ui(f: &mut Frame, app: &App) {
         0 => render_interfaces(f, app, chunks[2]),
         1 => render_networks(f, app, chunks[2]),
         2 => render_info(f, app, chunks[2]),
+        3 => render_spectrum(f, app, chunks[2]),
         _ => {}
     }
 
+    // Toasts, oldest on top
+    let toasts: Vec<Line> = app
+        .toasts
+        .iter()
+        .map(|t| {
+            let (icon, color) = match t.level {
+                ToastLevel::Info => ("»", theme.accent),
+                ToastLevel::Success => ("✓", theme.good),
+                ToastLevel::Error => ("✗", theme.bad),
+            };
+            Line::from(vec![
+                Span::styled(format!(" {} ", icon), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                Span::styled(t.text.as_str(), Style::default().fg(color)),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(toasts), chunks[3]);
+
     // Status bar
-    let status_style = if app.status_message.starts_with("Error") {
-        Style::default().fg(Color::Red)
-    } else {
-        Style::default().fg(Color::Green)
+    let mut status_line = Vec::new();
+    if app.auto_refresh {
+        status_line.push(Span::styled(
+            " AUTO ",
+            Style::default().fg(theme.badge_fg).bg(theme.good).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(capture) = &app.capture {
+        let secs = capture.started.elapsed().as_secs();
+        status_line.push(Span::styled(
+            format!(" REC {} {:02}:{:02} ", capture.interface, secs / 60, secs % 60),
+            Style::default().fg(theme.badge_fg).bg(theme.bad).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(live) = &app.live_scan {
+        status_line.push(Span::styled(
+            format!(" LIVE {}s ", live.interval.get().as_secs()),
+            Style::default().fg(theme.badge_fg).bg(theme.good).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(query) = &app.search_query {
+        let position = match app.search_matches.len() {
+            0 => "no matches".to_string(),
+            n => format!("{}/{}", app.search_cursor + 1, n),
+        };
+        status_line.push(Span::styled(
+            format!(" /{} {} ", query, position),
+            Style::default().fg(theme.badge_fg).bg(theme.heading).add_modifier(Modifier::BOLD),
+        ));
+    }
+    // The newest sighting stands in for when the last scan finished
+    let scan_age = match app.networks.iter().map(|n| n.last_seen).max() {
+        Some(seen) => format!("scanned {} ago", format_age((chrono::Utc::now() - seen).num_seconds())),
+        None => "no scan yet".to_string(),
     };
-    
-    let status = Paragraph::new(vec![
-        Line::from(vec![
-            Span::styled(" Status: ", Style::default().fg(Color::DarkGray)),
-            Span::styled(&app.status_message, status_style),
-            Span::raw("  │  "),
-            Span::styled("Press ? for help", Style::default().fg(Color::DarkGray)),
-        ]),
-    ])
+    status_line.extend([
+        Span::styled(
+            format!(" {} interfaces  │  {} networks, {}", app.interfaces.len(), app.networks.len(), scan_age),
+            Style::default().fg(theme.muted),
+        ),
+        Span::raw("  │  "),
+        Span::styled(format!("Press {} for help", app.keys.label(Action::Help)), Style::default().fg(theme.muted)),
+        Span::raw("  │  "),
+        Span::styled(chrono::Local::now().format("%H:%M:%S").to_string(), Style::default().fg(theme.text)),
+    ]);
+
+    let status = Paragraph::new(vec![Line::from(status_line)])
     .block(Block::default().borders(Borders::ALL));
-    f.render_widget(status, chunks[3]);
+    f.render_widget(status, chunks[4]);
 
     // Help popup
     if app.show_help {
-        render_help_popup(f);
+        render_help_popup(f, &app.keys, theme);
+    }
+
+    // Network inspect popup
+    if app.show_network_details && app.current_tab == 1 {
+        if let Some(net) = app.selected_network() {
+            render_network_popup(f, net, theme);
+        }
+    }
+
+    // Signal watch popup
+    if let Some(watch) = &app.watch {
+        render_watch_popup(f, watch, theme, &app.thresholds);
+    }
+
+    // Saved profiles popup
+    if let Some(connections) = &app.saved_connections {
+        render_connections_popup(f, connections, &mut app.connection_state.clone(), theme);
     }
 
     // Input mode popup
-    if app.input_mode != InputMode::Normal {
-        render_input_popup(f, app);
+    match (app.input_mode, &app.confirm) {
+        (InputMode::Normal, _) => {}
+        (InputMode::Confirm, Some(action)) => render_confirm_popup(f, action, theme),
+        _ => render_input_popup(f, app),
     }
 }
 
+/// List and details areas of the Interfaces tab: side by side, or stacked on narrow terminals
+fn interfaces_layout(area: Rect) -> [Rect; 2] {
+    let (direction, constraints) = if area.width < NARROW_WIDTH {
+        (Direction::Vertical, [Constraint::Percentage(40), Constraint::Percentage(60)])
+    } else {
+        (Direction::Horizontal, [Constraint::Percentage(50), Constraint::Percentage(50)])
+    };
+    let chunks = Layout::default().direction(direction).constraints(constraints).split(area);
+    [chunks[0], chunks[1]]
+}
+
 fn render_interfaces(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
+    let theme = &app.theme;
+    if app.interfaces.is_empty() {
+        let retry = Line::from(vec![
+            Span::raw("Press "),
+            Span::styled(app.keys.label(Action::Refresh), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::raw(" to retry."),
+        ]);
+        let lines = match &app.interfaces_error {
+            Some(error) => vec![
+                Line::from(""),
+                Line::from(Span::styled("Error loading interfaces", Style::default().fg(theme.bad).add_modifier(Modifier::BOLD))),
+                Line::from(""),
+                Line::from(error.as_str()),
+                Line::from("sozin lists interfaces with `ip` from iproute2; check that it is installed."),
+                Line::from(""),
+                retry,
+            ],
+            None => vec![Line::from(""), Line::from("No network interfaces found."), Line::from(""), retry],
+        };
+        let msg = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(ratatui::widgets::Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title(" Interfaces "))
+            .style(Style::default().fg(theme.muted));
+        f.render_widget(msg, area);
+        return;
+    }
+
+    let chunks = interfaces_layout(area);
 
     // Interface list
+    let hit = Style::default().fg(theme.badge_fg).bg(theme.heading).add_modifier(Modifier::BOLD);
     let items: Vec<ListItem> = app
         .interfaces
         .iter()
-        .map(|iface| {
+        .enumerate()
+        .map(|(i, iface)| {
             let state_color = match iface.state {
-                InterfaceState::Up => Color::Green,
-                InterfaceState::Down => Color::Red,
-                InterfaceState::Unknown => Color::Yellow,
+                InterfaceState::Up => theme.good,
+                InterfaceState::Down => theme.bad,
+                InterfaceState::Unknown => theme.warn,
             };
 
-            let type_icon = match iface.interface_type {
-                InterfaceType::Wireless => "📶",
-                InterfaceType::Ethernet => "🔌",
-                InterfaceType::Loopback => "🔄",
-                InterfaceType::Virtual => "🌐",
-                InterfaceType::Unknown => "❓",
-            };
+            let type_icon = type_icon(iface.interface_type, app.ascii);
 
             ListItem::new(Line::from(vec![
+                if app.favorites.contains(&iface.name) {
+                    Span::styled(if app.ascii { "* " } else { "★ " }, Style::default().fg(theme.heading))
+                } else {
+                    Span::raw("  ")
+                },
                 Span::raw(format!("{} ", type_icon)),
-                Span::styled(&iface.name, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    &iface.name,
+                    if app.search_matches.contains(&i) {
+                        hit
+                    } else {
+                        Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
+                    },
+                ),
                 Span::raw(" "),
                 Span::styled(format!("[{}]", iface.state), Style::default().fg(state_color)),
             ]))
@@ -521,9 +2091,11 @@ fn render_interfaces(f: &mut Frame, app: &App, area: Rect) {
 
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(" Interfaces "))
-        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
+        .highlight_style(Style::default().bg(theme.selection_bg).add_modifier(Modifier::BOLD))
         .highlight_symbol("▶ ");
 
+    // Inside the borders
+    app.interface_page.set(chunks[0].height.saturating_sub(2) as usize);
     f.render_stateful_widget(list, chunks[0], &mut app.interface_state.clone());
 
     // Interface details
@@ -536,82 +2108,180 @@ fn render_interfaces(f: &mut Frame, app: &App, area: Rect) {
             "N/A".to_string()
         };
 
-        vec![
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Name: ", Style::default().fg(theme.muted)),
+                Span::styled(&iface.name, Style::default().fg(theme.text)),
+            ]),
             Line::from(vec![
-                Span::styled("Name: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(&iface.name, Style::default().fg(Color::White)),
+                Span::styled("Description: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    iface.description.as_deref().unwrap_or("N/A"),
+                    Style::default().fg(theme.text),
+                ),
             ]),
             Line::from(vec![
-                Span::styled("Type: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(iface.interface_type.to_string(), Style::default().fg(Color::Cyan)),
+                Span::styled("Type: ", Style::default().fg(theme.muted)),
+                Span::styled(iface.interface_type.to_string(), Style::default().fg(theme.accent)),
             ]),
             Line::from(vec![
-                Span::styled("State: ", Style::default().fg(Color::DarkGray)),
+                Span::styled("State: ", Style::default().fg(theme.muted)),
                 Span::styled(
                     iface.state.to_string(),
                     Style::default().fg(match iface.state {
-                        InterfaceState::Up => Color::Green,
-                        InterfaceState::Down => Color::Red,
-                        InterfaceState::Unknown => Color::Yellow,
+                        InterfaceState::Up => theme.good,
+                        InterfaceState::Down => theme.bad,
+                        InterfaceState::Unknown => theme.warn,
                     }),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("MAC: ", Style::default().fg(Color::DarkGray)),
+                Span::styled("MAC: ", Style::default().fg(theme.muted)),
                 Span::styled(
                     iface.mac_address.as_deref().unwrap_or("N/A"),
-                    Style::default().fg(Color::White),
+                    Style::default().fg(theme.text),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("IP: ", Style::default().fg(Color::DarkGray)),
+                Span::styled("IP: ", Style::default().fg(theme.muted)),
                 Span::styled(
                     iface.ip_address.as_deref().unwrap_or("N/A"),
-                    Style::default().fg(Color::White),
+                    Style::default().fg(theme.text),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("MTU: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    iface.mtu.map(|m| m.to_string()).unwrap_or_else(|| "N/A".to_string()),
+                    Style::default().fg(theme.text),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Driver: ", Style::default().fg(Color::DarkGray)),
+                Span::styled("Driver: ", Style::default().fg(theme.muted)),
                 Span::styled(
                     iface.driver.as_deref().unwrap_or("N/A"),
-                    Style::default().fg(Color::White),
+                    Style::default().fg(theme.text),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Mode: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(mode, Style::default().fg(Color::Magenta)),
+                Span::styled("Mode: ", Style::default().fg(theme.muted)),
+                Span::styled(mode, Style::default().fg(theme.emphasis)),
             ]),
+        ];
+
+        if let Some(backend) = app.management.get(&iface.name) {
+            lines.push(Line::from(vec![
+                Span::styled("Managed by: ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    backend.to_string(),
+                    Style::default().fg(match backend {
+                        // These may put back a mode or address changed behind their back
+                        ManagementBackend::NetworkManager | ManagementBackend::SystemdNetworkd => theme.warn,
+                        ManagementBackend::Unmanaged => theme.text,
+                        ManagementBackend::Unknown => theme.muted,
+                    }),
+                ),
+            ]));
+        }
+
+        if iface.interface_type == InterfaceType::Wireless {
+            if let Some(phy) = NetworkManager::get_phy_for_interface(&iface.name) {
+                lines.push(Line::from(vec![
+                    Span::styled("Phy: ", Style::default().fg(theme.muted)),
+                    Span::styled(phy, Style::default().fg(theme.text)),
+                ]));
+            }
+            if let Some(quality) = NetworkManager::get_link_quality(&iface.name) {
+                lines.push(Line::from(vec![
+                    Span::styled("Quality: ", Style::default().fg(theme.muted)),
+                    Span::styled(format!("{}  ", quality.link), Style::default().fg(theme.text)),
+                    Span::styled("Signal: ", Style::default().fg(theme.muted)),
+                    Span::styled(
+                        format!("{} dBm  ", quality.level_dbm),
+                        Style::default().fg(theme.signal_color(quality.level_dbm, &app.thresholds)),
+                    ),
+                    Span::styled("Noise: ", Style::default().fg(theme.muted)),
+                    Span::styled(
+                        quality.noise_dbm.map(|n| format!("{} dBm", n)).unwrap_or_else(|| "N/A".to_string()),
+                        Style::default().fg(theme.text),
+                    ),
+                ]));
+            }
+        }
+
+        if iface.interface_type == InterfaceType::Ethernet {
+            lines.push(Line::from(vec![
+                Span::styled("Link: ", Style::default().fg(theme.muted)),
+                match iface.link_speed {
+                    Some(speed) => Span::styled(speed.to_string(), Style::default().fg(theme.good)),
+                    None => Span::styled("no link", Style::default().fg(theme.bad)),
+                },
+            ]));
+        }
+
+        lines.extend([
             Line::from(""),
             Line::from(vec![
-                Span::styled("─── Actions ───", Style::default().fg(Color::DarkGray)),
+                Span::styled("─── Actions ───", Style::default().fg(theme.muted)),
             ]),
             Line::from(vec![
-                Span::styled("m", Style::default().fg(Color::Cyan)),
+                Span::styled(app.keys.label(Action::ToggleMonitor), Style::default().fg(theme.accent)),
                 Span::raw(" Toggle Monitor  "),
-                Span::styled("u", Style::default().fg(Color::Cyan)),
+                Span::styled(app.keys.label(Action::InterfaceUp), Style::default().fg(theme.accent)),
                 Span::raw(" Up  "),
-                Span::styled("d", Style::default().fg(Color::Cyan)),
+                Span::styled(app.keys.label(Action::InterfaceDown), Style::default().fg(theme.accent)),
                 Span::raw(" Down"),
             ]),
             Line::from(vec![
-                Span::styled("s", Style::default().fg(Color::Cyan)),
+                Span::styled(app.keys.label(Action::Scan), Style::default().fg(theme.accent)),
                 Span::raw(" Scan WiFi  "),
-                Span::styled("M", Style::default().fg(Color::Cyan)),
+                Span::styled(app.keys.label(Action::SpoofMac), Style::default().fg(theme.accent)),
                 Span::raw(" Spoof MAC  "),
-                Span::styled("R", Style::default().fg(Color::Cyan)),
+                Span::styled(app.keys.label(Action::Rename), Style::default().fg(theme.accent)),
                 Span::raw(" Rename"),
             ]),
-        ]
+        ]);
+
+        lines
     } else {
         vec![Line::from("No interface selected")]
     };
 
-    let details_widget = Paragraph::new(details)
-        .block(Block::default().borders(Borders::ALL).title(" Details "));
-    f.render_widget(details_widget, chunks[1]);
+    let block = Block::default().borders(Borders::ALL).title(" Details ");
+    let inner = block.inner(chunks[1]);
+    f.render_widget(block, chunks[1]);
+
+    let throughput = app
+        .throughput
+        .as_ref()
+        .filter(|t| app.selected_interface().is_some_and(|i| i.name == t.interface));
+    let Some(throughput) = throughput.filter(|_| inner.height > 4) else {
+        f.render_widget(Paragraph::new(details), inner);
+        return;
+    };
+    let [text_area, gauge_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)])
+        .areas(inner);
+    f.render_widget(Paragraph::new(details), text_area);
+
+    let max = app.gauge_max.unwrap_or(throughput.peak);
+    let [rx_area, tx_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .areas(gauge_area);
+    for (area, label, rate) in [(rx_area, "RX", throughput.rx_rate), (tx_area, "TX", throughput.tx_rate)] {
+        let gauge = LineGauge::default()
+            .ratio(Throughput::ratio(rate, max))
+            .label(format!("{} {:>12} ", label, format_bitrate(rate)))
+            .filled_style(Style::default().fg(theme.accent))
+            .unfilled_style(Style::default().fg(theme.muted));
+        f.render_widget(gauge, area);
+    }
 }
 
 fn render_networks(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     if app.networks.is_empty() {
         let msg = Paragraph::new(vec![
             Line::from(""),
@@ -619,97 +2289,179 @@ fn render_networks(f: &mut Frame, app: &App, area: Rect) {
             Line::from(""),
             Line::from(vec![
                 Span::raw("Select a wireless interface and press "),
-                Span::styled("s", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(app.keys.label(Action::Scan), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
                 Span::raw(" to scan."),
             ]),
         ])
         .block(Block::default().borders(Borders::ALL).title(" WiFi Networks "))
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(theme.muted));
         f.render_widget(msg, area);
         return;
     }
 
     let header = Row::new(vec![
-        Cell::from("SSID").style(Style::default().fg(Color::Cyan)),
-        Cell::from("BSSID").style(Style::default().fg(Color::Cyan)),
-        Cell::from("CH").style(Style::default().fg(Color::Cyan)),
-        Cell::from("Signal").style(Style::default().fg(Color::Cyan)),
-        Cell::from("Security").style(Style::default().fg(Color::Cyan)),
+        Cell::from("SSID").style(Style::default().fg(theme.accent)),
+        Cell::from("BSSID").style(Style::default().fg(theme.accent)),
+        Cell::from("CH").style(Style::default().fg(theme.accent)),
+        Cell::from("Freq").style(Style::default().fg(theme.accent)),
+        Cell::from("Signal").style(Style::default().fg(theme.accent)),
+        Cell::from("Security").style(Style::default().fg(theme.accent)),
+        Cell::from("Score").style(Style::default().fg(theme.accent)),
     ])
     .height(1)
     .bottom_margin(1);
 
-    let rows: Vec<Row> = app
-        .networks
+    let visible = app.visible_networks();
+    let page = page_range(app.network_state.selected(), visible.len(), app.network_limit);
+    let hit = Style::default().fg(theme.badge_fg).bg(theme.heading).add_modifier(Modifier::BOLD);
+    let query = app.search_query.as_deref().unwrap_or_default();
+    let now = chrono::Utc::now();
+    let rows: Vec<Row> = visible[page.clone()]
         .iter()
-        .map(|net| {
-            let signal_color = if net.signal_strength > -50 {
-                Color::Green
-            } else if net.signal_strength > -70 {
-                Color::Yellow
+        .enumerate()
+        .map(|(row, net)| {
+            let i = page.start + row;
+            let matched = app.search_matches.contains(&i);
+            let signal_color = theme.signal_color(net.signal_strength, &app.thresholds);
+
+            let ssid = if net.ssid == HIDDEN_SSID {
+                Line::from(Span::styled(net.display_ssid(), Style::default().fg(theme.muted)))
+            } else if net.hidden {
+                Line::from(vec![
+                    Span::raw(net.ssid.clone()),
+                    Span::styled(" [hidden]", Style::default().fg(theme.muted)),
+                ])
+            } else {
+                Line::from(net.ssid.clone())
+            };
+
+            let row_style = if app.live_scan.is_some() && is_stale(net, now) {
+                Style::default().fg(theme.muted)
+            } else if is_insecure(net) {
+                Style::default().fg(theme.warn)
             } else {
-                Color::Red
+                Style::default()
             };
 
+            let ssid = if matched && matches_query(&net.ssid, query) { ssid.style(hit) } else { ssid };
+            let bssid = Cell::from(net.bssid.clone());
+            let bssid = if matched && matches_query(&net.bssid, query) { bssid.style(hit) } else { bssid };
+
             Row::new(vec![
-                Cell::from(net.ssid.clone()),
-                Cell::from(net.bssid.clone()),
-                Cell::from(net.channel.to_string()),
+                Cell::from(ssid),
+                bssid,
+                Cell::from(if net.psc {
+                    Line::from(vec![
+                        Span::raw(net.channel.to_string()),
+                        Span::styled(" PSC", Style::default().fg(theme.good)),
+                    ])
+                } else if is_dfs_channel(net.channel) && Band::from_frequency(net.frequency) == Some(Band::Five) {
+                    Line::from(vec![
+                        Span::raw(net.channel.to_string()),
+                        Span::styled(" DFS", Style::default().fg(theme.warn)),
+                    ])
+                } else {
+                    Line::from(net.channel.to_string())
+                }),
+                Cell::from(net.frequency.to_string()),
                 Cell::from(format!(
                     "{} {}dBm",
                     signal_to_bars(net.signal_strength),
                     net.signal_strength
                 ))
                 .style(Style::default().fg(signal_color)),
-                Cell::from(net.security.to_string()),
+                Cell::from(if net.tkip_only {
+                    format!("{} (TKIP)", net.security)
+                } else {
+                    net.security.to_string()
+                }),
+                {
+                    let score = net.quality_score();
+                    let color = match score {
+                        70.. => theme.good,
+                        40..=69 => theme.warn,
+                        _ => theme.bad,
+                    };
+                    Cell::from(score.to_string()).style(Style::default().fg(color))
+                },
             ])
+            .style(row_style)
         })
         .collect();
 
+    let mut block = Block::default().borders(Borders::ALL).title(match app.security_filter {
+        Some(filter) => format!(
+            " WiFi Networks [{}] ({} of {}) ",
+            filter,
+            visible.len(),
+            app.networks.len()
+        ),
+        None => format!(" WiFi Networks ({}) ", app.networks.len()),
+    });
+    if let Some(live) = &app.live_scan {
+        block = block.title(
+            Line::from(format!(" ● LIVE on {} every {}s ", live.interface, live.interval.get().as_secs()))
+                .right_aligned()
+                .style(Style::default().fg(theme.good)),
+        );
+    }
+    if page.len() < visible.len() {
+        block = block.title_bottom(format!(
+            " showing {}-{} of {}  PgUp/PgDn page ",
+            page.start + 1,
+            page.end,
+            visible.len()
+        ));
+    }
+
     let table = Table::new(
         rows,
         [
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(10),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
+            Constraint::Percentage(22),
+            Constraint::Percentage(19),
+            Constraint::Percentage(9),
+            Constraint::Percentage(7),
+            Constraint::Percentage(17),
+            Constraint::Percentage(18),
+            Constraint::Percentage(8),
         ],
     )
     .header(header)
-    .block(Block::default().borders(Borders::ALL).title(format!(
-        " WiFi Networks ({}) ",
-        app.networks.len()
-    )))
-    .highlight_style(Style::default().bg(Color::DarkGray))
+    .block(block)
+    .highlight_style(Style::default().bg(theme.selection_bg))
     .highlight_symbol("▶ ");
 
-    f.render_stateful_widget(table, area, &mut app.network_state.clone());
+    // Inside the borders, less the header row
+    app.network_page.set(area.height.saturating_sub(3) as usize);
+    let mut state = app.network_state.clone();
+    state.select(app.network_state.selected().map(|i| i - page.start));
+    f.render_stateful_widget(table, area, &mut state);
 }
 
-fn render_info(f: &mut Frame, _app: &App, area: Rect) {
-    let info = vec![
+fn render_info(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let mut info = vec![
         Line::from(""),
         Line::from(vec![
-            Span::styled("  SOZIN ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-            Span::styled("- Professional Network Interface Manager", Style::default().fg(Color::White)),
+            Span::styled("  SOZIN ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("- Professional Network Interface Manager", Style::default().fg(theme.text)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  Version: ", Style::default().fg(Color::DarkGray)),
-            Span::styled("2.1.0", Style::default().fg(Color::White)),
+            Span::styled("  Version: ", Style::default().fg(theme.muted)),
+            Span::styled("2.1.0", Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("  Author: ", Style::default().fg(Color::DarkGray)),
-            Span::styled("therealsylva", Style::default().fg(Color::White)),
+            Span::styled("  Author: ", Style::default().fg(theme.muted)),
+            Span::styled("therealsylva", Style::default().fg(theme.text)),
         ]),
         Line::from(vec![
-            Span::styled("  License: ", Style::default().fg(Color::DarkGray)),
-            Span::styled("MIT", Style::default().fg(Color::White)),
+            Span::styled("  License: ", Style::default().fg(theme.muted)),
+            Span::styled("MIT", Style::default().fg(theme.text)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  Features:", Style::default().fg(Color::Cyan)),
+            Span::styled("  Features:", Style::default().fg(theme.accent)),
         ]),
         Line::from("    • Monitor mode management"),
         Line::from("    • WiFi network scanning & discovery"),
@@ -719,64 +2471,377 @@ fn render_info(f: &mut Frame, _app: &App, area: Rect) {
         Line::from("    • Async architecture for performance"),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  ⚠ ", Style::default().fg(Color::Yellow)),
-            Span::styled("Requires root privileges for network operations", Style::default().fg(Color::DarkGray)),
+            Span::styled("  Tools:", Style::default().fg(theme.accent)),
         ]),
     ];
+    for (tool, version) in &app.tool_versions {
+        let (text, color) = match version {
+            Some(v) => (v.as_str(), theme.text),
+            None => ("not found", theme.warn),
+        };
+        info.push(Line::from(vec![
+            Span::styled(format!("    {:<10}", tool), Style::default().fg(theme.muted)),
+            Span::styled(text.to_string(), Style::default().fg(color)),
+        ]));
+    }
+    info.extend([
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  ⚠ ", Style::default().fg(theme.warn)),
+            Span::styled("Requires root privileges for network operations", Style::default().fg(theme.muted)),
+        ]),
+    ]);
 
     let info_widget = Paragraph::new(info)
         .block(Block::default().borders(Borders::ALL).title(" About "));
     f.render_widget(info_widget, area);
 }
 
-fn render_help_popup(f: &mut Frame) {
+fn render_spectrum(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(35), Constraint::Percentage(30)])
+        .split(area);
+
+    let no_scan = if app.networks.is_empty() { " (no scan yet)" } else { "" };
+    for (band, channels, chunk) in [
+        (Band::TwoPointFour, &SPECTRUM_CHANNELS_2GHZ[..], chunks[0]),
+        (Band::Five, &CHANNELS_5GHZ[..], chunks[1]),
+    ] {
+        // Wide APs count on every channel they cover, as in channel recommendations
+        let bars: Vec<Bar> = channels
+            .iter()
+            .map(|&ch| {
+                let color = if is_dfs_channel(ch) { theme.warn } else { theme.accent };
+                Bar::default()
+                    .value(channel_ap_count(&app.networks, band, ch) as u64)
+                    .label(Line::from(ch.to_string()))
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(theme.badge_fg).bg(color))
+            })
+            .collect();
+        let title = match band {
+            Band::Five => format!(" {} APs per channel, DFS highlighted{} ", band, no_scan),
+            _ => format!(" {} APs per channel{} ", band, no_scan),
+        };
+        let chart = BarChart::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(3)
+            .bar_gap(1);
+        f.render_widget(chart, chunk);
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Activity, % of frames heard (estimate, depends on dwell time) ");
+    if app.activity.is_empty() {
+        let hint = Paragraph::new(Line::from(vec![
+            Span::raw("Select a monitor-mode interface and press "),
+            Span::styled(app.keys.label(Action::SampleActivity), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::raw(format!(" to count frames on each channel ({} ms per channel).", crate::network::ACTIVITY_DWELL_MS)),
+        ]))
+        .block(block)
+        .style(Style::default().fg(theme.muted));
+        f.render_widget(hint, chunks[2]);
+        return;
+    }
+    let bars: Vec<Bar> = app
+        .activity
+        .iter()
+        .map(|a| {
+            Bar::default()
+                .value(a.percent as u64)
+                .text_value(format!("{}%", a.percent))
+                .label(Line::from(a.channel.to_string()))
+                .style(Style::default().fg(theme.emphasis))
+                .value_style(Style::default().fg(theme.badge_fg).bg(theme.emphasis))
+        })
+        .collect();
+    let chart = BarChart::default()
+        .block(block)
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(3)
+        .bar_gap(1)
+        .max(100);
+    f.render_widget(chart, chunks[2]);
+}
+
+fn render_help_popup(f: &mut Frame, keys: &KeyBindings, theme: &Theme) {
     let area = centered_rect(60, 70, f.area());
     f.render_widget(Clear, area);
 
+    let key = |label: &str, text: &str| Line::from(format!("  {:<15}{}", label, text));
+    let bound = |action: Action, text: &str| key(&keys.label(action), text);
+    let heading = |title: &'static str| Line::from(vec![Span::styled(title, Style::default().fg(theme.heading))]);
+
     let help_text = vec![
         Line::from(vec![
-            Span::styled("Keyboard Shortcuts", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled("Keyboard Shortcuts", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("Navigation", Style::default().fg(Color::Yellow)),
-        ]),
-        Line::from("  Tab/Shift+Tab  Switch tabs"),
-        Line::from("  j/↓            Move down"),
-        Line::from("  k/↑            Move up"),
-        Line::from("  q              Quit"),
-        Line::from("  ?              Toggle help"),
+        heading("Navigation"),
+        key("Tab/Shift+Tab", "Switch tabs"),
+        key("1-4", "Jump to tab"),
+        key(&format!("{}/↓", keys.label(Action::MoveDown)), "Move down"),
+        key(&format!("{}/↑", keys.label(Action::MoveUp)), "Move up"),
+        key("PgUp/PgDn", "Move a page"),
+        key("Home/End", "First/last entry"),
+        key("Ctrl+F", "Search names, SSIDs, BSSIDs"),
+        key("n/N", "Next/previous match (while searching)"),
+        bound(Action::Quit, "Quit (offers to undo monitor mode and MAC changes)"),
+        key(&format!("{}/F1", keys.label(Action::Help)), "Toggle help"),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("Interface Actions", Style::default().fg(Color::Yellow)),
-        ]),
-        Line::from("  m              Toggle monitor mode"),
-        Line::from("  u              Bring interface up"),
-        Line::from("  d              Bring interface down"),
-        Line::from("  R              Rename interface"),
-        Line::from("  M              Spoof MAC address"),
-        Line::from("  r              Refresh interfaces"),
+        heading("Interface Actions"),
+        bound(Action::ToggleMonitor, "Toggle monitor mode"),
+        bound(Action::InterfaceUp, "Bring interface up"),
+        bound(Action::InterfaceDown, "Bring interface down"),
+        bound(Action::Rename, "Rename interface"),
+        bound(Action::SpoofMac, "Spoof MAC address"),
+        bound(Action::Refresh, "Refresh interfaces"),
+        bound(Action::AutoRefresh, "Toggle auto-refresh"),
+        bound(Action::Favorite, "Pin/unpin interface at the top"),
+        bound(Action::EditNote, "Edit the adapter's note"),
+        bound(Action::Capture, "Start/stop pcap capture (monitor mode)"),
+        bound(Action::Disconnect, "Disconnect interface"),
+        bound(Action::ResetInterface, "Reset interface (asks first)"),
+        bound(Action::ReloadDriver, "Reload interface driver (asks first)"),
+        bound(Action::SetChannel, "Set channel and width"),
+        Line::from(""),
+        heading("Scanning"),
+        bound(Action::Scan, "Scan for WiFi networks"),
+        bound(Action::LiveScan, "Start/stop live scanning"),
+        key(
+            &format!("{}/{}", keys.label(Action::LiveScanSlower), keys.label(Action::LiveScanFaster)),
+            "Live scan less/more often",
+        ),
+        bound(Action::CycleFilter, "Cycle security filter"),
+        bound(Action::OpenOnly, "Show open networks only"),
+        key("Enter", "Inspect selected network"),
+        bound(Action::Watch, "Watch signal of selected network"),
+        bound(Action::Connect, "Connect to selected network"),
+        bound(Action::NewNetworkAlerts, "Toggle bell on newly seen networks"),
+        bound(Action::SampleActivity, "Sample channel activity (Spectrum tab)"),
+        bound(Action::SavedNetworks, "Saved networks (x to forget)"),
         Line::from(""),
+        heading("System"),
+        bound(Action::RestartNetworkManager, "Restart NetworkManager"),
+    ];
+
+    let help = Paragraph::new(help_text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Help ")
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .style(Style::default().fg(theme.text));
+    f.render_widget(help, area);
+}
+
+fn render_network_popup(f: &mut Frame, net: &WifiNetwork, theme: &Theme) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<16}", label), Style::default().fg(theme.muted)),
+            Span::styled(value, Style::default().fg(theme.text)),
+        ])
+    };
+    let or_na = |value: Option<String>| value.unwrap_or_else(|| "N/A".to_string());
+
+    let details = vec![
         Line::from(vec![
-            Span::styled("Scanning", Style::default().fg(Color::Yellow)),
+            Span::styled(net.display_ssid(), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                if net.hidden { " (hidden)" } else { "" },
+                Style::default().fg(theme.muted),
+            ),
         ]),
-        Line::from("  s              Scan for WiFi networks"),
+        Line::from(""),
+        field("BSSID:", net.bssid.clone()),
+        field(
+            "Channel:",
+            format!(
+                "{}{}",
+                net.channel,
+                if net.psc {
+                    " (PSC)"
+                } else if is_dfs_channel(net.channel) && Band::from_frequency(net.frequency) == Some(Band::Five) {
+                    " (DFS)"
+                } else {
+                    ""
+                }
+            ),
+        ),
+        field("Frequency:", format!("{} MHz", net.frequency)),
+        field("Country:", or_na(net.country_code.clone())),
+        field("Width:", {
+            let (first, last) = net.occupied_channels();
+            match net.channel_width_mhz {
+                Some(width) if first != last => format!("{} MHz (channels {}-{})", width, first, last),
+                width => or_na(width.map(|w| format!("{} MHz", w))),
+            }
+        }),
+        field("Signal:", format!("{} {} dBm", signal_to_bars(net.signal_strength), net.signal_strength)),
+        field("Security:", net.security.to_string()),
+        field("Mode:", net.mode.clone()),
+        field(
+            "Max rate:",
+            or_na(net.max_rate_mbps.map(|r| {
+                format!("~{} Mbps (estimate){}", r, if net.is_legacy { ", legacy b/g only" } else { "" })
+            })),
+        ),
+        field("Beacon interval:", or_na(net.beacon_interval_tu.map(|tu| format!("{} TU", tu)))),
+        field("Stations:", or_na(net.station_count.map(|n| n.to_string()))),
+        field("Channel busy:", or_na(net.channel_utilization.map(|p| format!("{}% (reported by AP)", p)))),
+        field("Capability:", or_na(net.capability.clone())),
+        field("Last seen:", or_na(net.last_seen_ms.map(|ms| format!("{} ms ago", ms)))),
         Line::from(""),
         Line::from(vec![
-            Span::styled("System", Style::default().fg(Color::Yellow)),
+            Span::styled("Press Enter or Esc to close", Style::default().fg(theme.muted)),
         ]),
-        Line::from("  n              Restart NetworkManager"),
     ];
 
-    let help = Paragraph::new(help_text)
+    let popup = Paragraph::new(details)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Help ")
-                .border_style(Style::default().fg(Color::Cyan)),
+                .title(" Network Details ")
+                .border_style(Style::default().fg(theme.accent)),
         )
-        .style(Style::default().fg(Color::White));
-    f.render_widget(help, area);
+        .style(Style::default().fg(theme.text));
+    f.render_widget(popup, area);
+}
+
+fn render_watch_popup(f: &mut Frame, watch: &SignalWatch, theme: &Theme, thresholds: &SignalThresholds) {
+    let area = centered_rect(70, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Watching {} ({}) ", watch.ssid, watch.bssid))
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+
+    let current = watch.samples.back().map(|&(_, dbm)| dbm);
+    let min = watch.samples.iter().map(|&(_, dbm)| dbm).min();
+    let max = watch.samples.iter().map(|&(_, dbm)| dbm).max();
+    let summary = match (current, min, max) {
+        (Some(current), Some(min), Some(max)) => Line::from(vec![
+            Span::styled("Now: ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{} {} dBm", signal_to_bars(current), current),
+                Style::default().fg(theme.signal_color(current, thresholds)).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("   Min: ", Style::default().fg(theme.muted)),
+            Span::raw(format!("{} dBm", min)),
+            Span::styled("   Max: ", Style::default().fg(theme.muted)),
+            Span::raw(format!("{} dBm", max)),
+            if watch.seen {
+                Span::raw("")
+            } else {
+                Span::styled("   not seen in last sweep", Style::default().fg(theme.warn))
+            },
+        ]),
+        _ => Line::from("No samples yet"),
+    };
+    f.render_widget(Paragraph::new(summary).style(Style::default().fg(theme.text)), chunks[0]);
+
+    // Shift dBm into 0..=100 so stronger signals draw taller bars
+    let data: Vec<u64> = watch
+        .samples
+        .iter()
+        .map(|&(_, dbm)| (dbm + 100).clamp(0, 100) as u64)
+        .collect();
+    let sparkline = Sparkline::default()
+        .data(&data)
+        .max(100)
+        .style(Style::default().fg(current.map_or(theme.muted, |dbm| theme.signal_color(dbm, thresholds))));
+    f.render_widget(sparkline, chunks[1]);
+
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            if watch.per_frame {
+                format!("Per-frame radiotap signal, read every {}s, Esc to stop", WATCH_INTERVAL_SECS)
+            } else {
+                format!("Sampling every {}s, Esc to stop", WATCH_INTERVAL_SECS)
+            },
+            Style::default().fg(theme.muted),
+        )),
+        chunks[2],
+    );
+}
+
+fn render_connections_popup(f: &mut Frame, connections: &[SavedConnection], state: &mut ListState, theme: &Theme) {
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Saved Networks ")
+        .title_bottom(" x forget  Esc close ")
+        .border_style(Style::default().fg(theme.accent));
+
+    if connections.is_empty() {
+        let empty = Paragraph::new(Span::styled("No saved WiFi networks", Style::default().fg(theme.muted))).block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = connections
+        .iter()
+        .map(|c| {
+            let device = match &c.device {
+                Some(device) => Span::styled(format!("  active on {}", device), Style::default().fg(theme.good)),
+                None => Span::raw(""),
+            };
+            ListItem::new(Line::from(vec![Span::styled(c.name.clone(), Style::default().fg(theme.text)), device]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).add_modifier(Modifier::BOLD))
+        .highlight_symbol("▶ ");
+    f.render_stateful_widget(list, area, state);
+}
+
+fn render_confirm_popup(f: &mut Frame, action: &ConfirmAction, theme: &Theme) {
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(Clear, area);
+
+    let text = vec![
+        Line::from(Span::styled("Are you sure?", Style::default().fg(theme.bad).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(Span::styled(action.prompt(), Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(Span::styled(action.hint(), Style::default().fg(theme.muted))),
+    ];
+
+    let popup = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.bad)),
+    );
+    f.render_widget(popup, area);
+}
+
+/// Compact age for the status bar: "42s", "5m", "2h"
+fn format_age(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3600),
+    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -800,26 +2865,37 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 }
 
 fn render_input_popup(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = centered_rect(50, 20, f.area());
     f.render_widget(Clear, area);
 
     let title = match app.input_mode {
         InputMode::Rename => "Rename Interface",
+        InputMode::Password => "Network Passphrase",
+        InputMode::Search => "Search",
+        InputMode::Note => "Interface Note",
+        InputMode::ChannelInput => "Channel [width]",
         _ => "Input",
     };
 
+    // Secrets are shown as one bullet per character; `input_buffer` keeps the real value
+    let shown = match app.input_mode {
+        InputMode::Password => "•".repeat(app.input_buffer.chars().count()),
+        _ => app.input_buffer.clone(),
+    };
+
     let input_text = vec![
         Line::from(vec![
-            Span::styled(title, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(title, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
         ]),
         Line::from(""),
         Line::from(vec![
             Span::raw("> "),
-            Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
+            Span::styled(shown, Style::default().fg(theme.text)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("Press Enter to confirm, Esc to cancel", Style::default().fg(Color::DarkGray)),
+            Span::styled("Press Enter to confirm, Esc to cancel", Style::default().fg(theme.muted)),
         ]),
     ];
 
@@ -827,9 +2903,209 @@ fn render_input_popup(f: &mut Frame, app: &App) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(Style::default().fg(theme.accent)),
         )
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(input, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reselect_follows_key_and_clears_when_removed() {
+        // An earlier row vanished: the selection moves with wlan0 instead of staying at its old index
+        assert_eq!(reselect(Some("wlan0"), &["wlan0", "eth0"]), Some(0));
+        assert_eq!(reselect(Some("wlan1"), &["eth0", "wlan0"]), None);
+        assert_eq!(reselect(None, &["eth0"]), Some(0));
+        assert_eq!(reselect(None, &[]), None);
+    }
+
+    #[test]
+    fn favorites_sort_first_and_the_rest_keep_their_order() {
+        let mut interfaces: Vec<NetworkInterface> = ["docker0", "veth1", "wlan0", "eth0", "lo"]
+            .into_iter()
+            .map(|name| NetworkInterface {
+                name: name.to_string(),
+                parent: None,
+                mac_address: None,
+                ip_address: None,
+                state: InterfaceState::Unknown,
+                interface_type: InterfaceType::Unknown,
+                driver: None,
+                mtu: None,
+                link_speed: None,
+                permanent_mac: None,
+                description: None,
+            })
+            .collect();
+        favorites_first(&mut interfaces, &HashSet::from(["eth0".to_string(), "wlan0".to_string()]));
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["wlan0", "eth0", "docker0", "veth1", "lo"]);
+    }
+
+    #[test]
+    fn interfaces_layout_stacks_on_narrow_terminals() {
+        let [list, details] = interfaces_layout(Rect::new(0, 0, 80, 30));
+        assert_eq!((list.width, details.width), (80, 80));
+        assert!(details.y >= list.bottom());
+
+        let [list, details] = interfaces_layout(Rect::new(0, 0, 160, 30));
+        assert_eq!((list.y, details.y), (0, 0));
+        assert_eq!((list.width, details.x), (80, 80));
+    }
+
+    #[test]
+    fn search_is_case_insensitive_substring() {
+        assert!(matches_query("HomeNet-5G", "net-5"));
+        assert!(matches_query("AA:BB:CC:00:11:22", "bb:cc"));
+        assert!(!matches_query("wlan0", "eth"));
+    }
+
+    #[test]
+    fn clamped_step_stays_in_bounds() {
+        assert_eq!(clamped_step(Some(3), 5, 10), Some(4));
+        assert_eq!(clamped_step(Some(1), 5, isize::MIN), Some(0));
+        assert_eq!(clamped_step(Some(7), 2, 0), Some(1));
+        assert_eq!(clamped_step(Some(0), 0, 1), None);
+    }
+
+    #[test]
+    fn busy_actions_refuse_a_second_trigger_until_done() {
+        let mut app = App::default();
+        assert!(app.try_begin(BusyAction::Mac));
+        assert!(!app.try_begin(BusyAction::Mac));
+        assert!(app.try_begin(BusyAction::Monitor));
+
+        let failed = Completion::Mac {
+            interface: "wlan0".to_string(),
+            mac: "02:00:00:00:00:01".to_string(),
+            previous: None,
+            result: Err(anyhow::anyhow!("Operation not permitted")),
+        };
+        app.completion_tx.send(failed).unwrap();
+        app.poll_completions();
+        assert_eq!(app.busy, HashSet::from([BusyAction::Monitor]));
+        assert!(app.try_begin(BusyAction::Mac));
+    }
+
+    #[test]
+    fn quitting_offers_to_restore_what_the_session_changed() {
+        let mut app = App::default();
+        app.note_monitor("wlan0", true);
+        app.note_mac_spoof("wlan1", Some("00:11:22:33:44:55".to_string()));
+        app.note_mac_spoof("wlan1", Some("02:aa:bb:cc:dd:ee".to_string()));
+        assert_eq!(app.session_changes["wlan1"].original_mac.as_deref(), Some("00:11:22:33:44:55"));
+
+        // Switching monitor mode back off by hand leaves nothing to restore there
+        app.note_monitor("wlan0", false);
+        assert!(!app.session_changes.contains_key("wlan0"));
+
+        app.request_quit();
+        assert!(app.running);
+        assert_eq!(app.confirm, Some(ConfirmAction::RestoreOnExit(1)));
+
+        let mut app = App { restore_on_exit: false, ..App::default() };
+        app.note_monitor("wlan0", true);
+        app.request_quit();
+        assert!(!app.running);
+        assert_eq!(app.confirm, None);
+    }
+
+    #[test]
+    fn changes_are_refused_without_root_but_reading_is_not() {
+        let mut app = App { is_root: false, ..App::default() };
+        assert!(app.refuse_unprivileged(Action::ToggleMonitor));
+        assert!(app.toasts.iter().any(|t| t.text.starts_with("Requires root")));
+        assert!(!app.refuse_unprivileged(Action::Scan));
+        assert!(!app.refuse_unprivileged(Action::Refresh));
+
+        let mut app = App { is_root: true, ..App::default() };
+        assert!(!app.refuse_unprivileged(Action::SpoofMac));
+        assert!(app.toasts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn live_scan_interval_keys_stay_in_range() {
+        let mut app = App::default();
+        app.adjust_live_scan(true);
+        assert!(app.toasts.last().unwrap().text.contains("No live scan running"));
+
+        let (_tx, sweeps) = std::sync::mpsc::channel();
+        let interval = ScanInterval::new(Duration::from_secs(2));
+        let task = tokio::spawn(async {});
+        app.live_scan = Some(LiveScan { interface: "wlan0".to_string(), interval: interval.clone(), task, sweeps });
+        for _ in 0..3 {
+            app.adjust_live_scan(false);
+        }
+        assert_eq!(interval.get(), Duration::from_secs(1));
+
+        interval.set(Duration::from_secs(LIVE_SCAN_MAX_SECS));
+        app.adjust_live_scan(true);
+        assert_eq!(interval.get(), Duration::from_secs(LIVE_SCAN_MAX_SECS));
+        assert_eq!(app.toasts.last().unwrap().text, format!("Live scan every {}s", LIVE_SCAN_MAX_SECS));
+    }
+
+    #[test]
+    fn live_sweeps_replace_by_bssid_and_age_out() {
+        let mut scanner = WifiScanner::new("wlan0");
+        let heard = |contents: &str, scanner: &mut WifiScanner| scanner.parse_saved(contents).unwrap();
+        let now = chrono::Utc::now();
+
+        let mut earlier = heard(include_str!("../tests/fixtures/wpa2_psk.txt"), &mut scanner);
+        earlier.extend(heard(include_str!("../tests/fixtures/5ghz.txt"), &mut scanner));
+        for network in &mut earlier {
+            network.last_seen = now - chrono::Duration::seconds(LIVE_STALE_SECS);
+        }
+        let gone = earlier.pop().unwrap();
+        let mut forgotten = gone.clone();
+        forgotten.bssid = "00:00:00:00:00:99".to_string();
+        forgotten.last_seen = now - chrono::Duration::seconds(LIVE_FORGET_SECS);
+        earlier.push(gone.clone());
+        earlier.push(forgotten);
+
+        let mut sweep = earlier[..earlier.len() - 2].to_vec();
+        for network in &mut sweep {
+            network.last_seen = now;
+        }
+        let merged = merge_live(earlier, sweep.clone(), now);
+
+        assert_eq!(merged.len(), sweep.len() + 1);
+        assert_eq!(merged.last().unwrap().bssid, gone.bssid);
+        assert!(is_stale(merged.last().unwrap(), now));
+        assert!(merged[..sweep.len()].iter().all(|n| !is_stale(n, now)));
+    }
+
+    #[test]
+    fn throughput_comes_from_counter_deltas() {
+        let stats = |rx_bytes, tx_bytes| Some(InterfaceStats { rx_bytes, tx_bytes, ..Default::default() });
+        let start = Instant::now();
+        let mut t = Throughput::new("wlan0");
+        t.record(start, stats(1_000, 500));
+        assert_eq!((t.rx_rate, t.tx_rate, t.peak), (0, 0, 0));
+
+        t.record(start + Duration::from_millis(500), stats(6_000, 1_500));
+        assert_eq!((t.rx_rate, t.tx_rate, t.peak), (10_000, 2_000, 10_000));
+
+        // Counters reset and an idle interface both read as zero, without losing the peak
+        t.record(start + Duration::from_secs(1), stats(0, 0));
+        t.record(start + Duration::from_secs(2), stats(0, 0));
+        assert_eq!((t.rx_rate, t.tx_rate, t.peak), (0, 0, 10_000));
+
+        assert_eq!(Throughput::ratio(5_000, 10_000), 0.5);
+        assert_eq!(Throughput::ratio(50_000, 10_000), 1.0);
+        assert_eq!(Throughput::ratio(0, 0), 0.0);
+        assert_eq!(format_bitrate(125_000), "1.0 Mbit/s");
+    }
+
+    #[test]
+    fn page_range_follows_selection() {
+        assert_eq!(page_range(Some(7), 12, None), 0..12);
+        assert_eq!(page_range(None, 12, Some(5)), 0..5);
+        assert_eq!(page_range(Some(7), 12, Some(5)), 5..10);
+        assert_eq!(page_range(Some(11), 12, Some(5)), 10..12);
+        assert_eq!(page_range(Some(3), 0, Some(5)), 0..0);
+    }
+}